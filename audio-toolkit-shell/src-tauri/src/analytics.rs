@@ -0,0 +1,814 @@
+//! # Analytics Module
+//!
+//! This module records per-tab activity and draws the "📊 Analytics" dashboard.
+//!
+//! ## Events
+//!
+//! Tabs queue [`TabEvent`]s (process start and exit, restarts and their
+//! trigger, success-pattern matches, commands, bytes of output) and the app
+//! hands them to [`Analytics::record`], which timestamps them and appends them
+//! to a CSV log (`analytics.csv` next to `config.toml` by default):
+//!
+//! ```text
+//! timestamp,tab,event,detail,value
+//! 2026-10-18T09:12:03Z,Normalizer,start,/usr/local/bin/normalizer,0
+//! 2026-10-18T09:20:41Z,Normalizer,restart,pattern,518
+//! ```
+//!
+//! `value` is seconds of uptime for `exit`, `restart` and `stop`, and bytes for
+//! `output`. Days are UTC.
+//!
+//! The log stays open for appending while the app runs. When it loads, events
+//! older than `retention_days` are dropped and the file is rewritten without them.
+//!
+//! ## Shell Integration
+//!
+//! Commands are only known when the shell marks them. [`ShellMarks`] picks up
+//! `OSC 133;C` (command started) and the optional `OSC 633;E;<command line>`
+//! that precedes it.
+
+use eframe::egui;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::AnalyticsSettings;
+use crate::file_manager::format_size;
+use crate::theme::Palette;
+
+const CSV_HEADER: &str = "timestamp,tab,event,detail,value";
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Why a tab was restarted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartTrigger {
+    /// Success patterns matched (`auto_restart_on_success`)
+    Pattern,
+    /// `restart_tab` key binding or button
+    Manual,
+    /// `restart_all`
+    RestartAll,
+    /// The tab's command was changed in the settings editor
+    Settings,
+    /// A `restart` request on the control socket
+    Control,
+    /// `on_idle = "restart"` after `idle_timeout`
+    Idle,
+}
+
+impl RestartTrigger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RestartTrigger::Pattern => "pattern",
+            RestartTrigger::Manual => "manual",
+            RestartTrigger::RestartAll => "restart_all",
+            RestartTrigger::Settings => "settings",
+            RestartTrigger::Control => "control",
+            RestartTrigger::Idle => "idle",
+        }
+    }
+}
+
+/// Kind of a recorded event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// Process spawned; detail is the command
+    Start,
+    /// Process exited; detail is the exit code or signal, value the uptime
+    Exit,
+    /// Tab restarted; detail is the trigger, value the uptime of a still-running process
+    Restart,
+    /// Process still running when the app closed; value is the uptime
+    Stop,
+    /// A success pattern matched; detail is the pattern
+    Pattern,
+    /// The shell started a command; detail is the command line when known
+    Command,
+    /// Output produced by a run; value is the byte count
+    Output,
+}
+
+impl EventKind {
+    const ALL: [EventKind; 7] = [
+        EventKind::Start,
+        EventKind::Exit,
+        EventKind::Restart,
+        EventKind::Stop,
+        EventKind::Pattern,
+        EventKind::Command,
+        EventKind::Output,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventKind::Start => "start",
+            EventKind::Exit => "exit",
+            EventKind::Restart => "restart",
+            EventKind::Stop => "stop",
+            EventKind::Pattern => "pattern",
+            EventKind::Command => "command",
+            EventKind::Output => "output",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == name)
+    }
+}
+
+/// An event queued by a tab, before it is timestamped
+#[derive(Debug, Clone, PartialEq)]
+pub struct TabEvent {
+    pub kind: EventKind,
+    pub detail: String,
+    pub value: u64,
+}
+
+impl TabEvent {
+    pub fn new(kind: EventKind, detail: impl Into<String>, value: u64) -> Self {
+        Self { kind, detail: detail.into(), value }
+    }
+}
+
+/// A recorded event
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Seconds since the Unix epoch
+    pub at: u64,
+    pub tab: String,
+    pub kind: EventKind,
+    pub detail: String,
+    pub value: u64,
+}
+
+impl Event {
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{}",
+            format_timestamp(self.at),
+            csv_field(&self.tab),
+            self.kind.as_str(),
+            csv_field(&self.detail),
+            self.value
+        )
+    }
+
+    fn from_csv(line: &str) -> Option<Self> {
+        let fields = parse_csv_line(line);
+        let [at, tab, kind, detail, value] = fields.as_slice() else {
+            return None;
+        };
+        Some(Self {
+            at: parse_timestamp(at)?,
+            tab: tab.clone(),
+            kind: EventKind::parse(kind)?,
+            detail: detail.clone(),
+            value: value.parse().ok()?,
+        })
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    let text: String = text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect();
+    if text.contains([',', '"']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        match ch {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            _ => field.push(ch),
+        }
+    }
+    fields
+}
+
+/// Days since 1970-01-01 to (year, month, day), proleptic Gregorian
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// (year, month, day) to days since 1970-01-01
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// `YYYY-MM-DD` (UTC) for a day number
+pub fn format_day(day: u64) -> String {
+    let (y, m, d) = civil_from_days(day as i64);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// `YYYY-MM-DDTHH:MM:SSZ` for a Unix timestamp
+pub fn format_timestamp(at: u64) -> String {
+    let secs = at % SECS_PER_DAY;
+    format!("{}T{:02}:{:02}:{:02}Z", format_day(at / SECS_PER_DAY), secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+fn parse_timestamp(text: &str) -> Option<u64> {
+    let (date, time) = text.trim().trim_end_matches('Z').split_once('T')?;
+    let mut date = date.splitn(3, '-').map(str::parse::<u32>);
+    let (y, m, d) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let mut time = time.splitn(3, ':').map(str::parse::<u64>);
+    let (h, min, s) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+    let day = u64::try_from(days_from_civil(i64::from(y), m, d)).ok()?;
+    Some(day * SECS_PER_DAY + h * 3600 + min * 60 + s)
+}
+
+/// Current Unix time in seconds
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// `1h 05m`, `4m 12s` or `37s`
+pub fn format_uptime(secs: u64) -> String {
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, _) => format!("{}h {:02}m", h, m),
+    }
+}
+
+/// Picks shell-integration command marks out of PTY output
+///
+/// Sequences split across reads are carried over to the next call.
+#[derive(Debug, Default)]
+pub struct ShellMarks {
+    /// Unterminated OSC from the previous chunk
+    pending: String,
+    /// Command line announced by `OSC 633;E` for the next `OSC 133;C`
+    command: Option<String>,
+}
+
+impl ShellMarks {
+    /// Returns the commands started in `data` (empty when the shell did not announce the text)
+    pub fn scan(&mut self, data: &str) -> Vec<String> {
+        let mut text = std::mem::take(&mut self.pending);
+        text.push_str(data);
+        let mut started = Vec::new();
+        let mut rest = text.as_str();
+        while let Some(start) = rest.find("\x1b]") {
+            let body = &rest[start + 2..];
+            let Some((end, terminator)) = [("\x07", 1), ("\x1b\\", 2)]
+                .into_iter()
+                .filter_map(|(t, len)| body.find(t).map(|i| (i, len)))
+                .min()
+            else {
+                // Keep a bounded tail so a stray ESC ] cannot grow without limit
+                if body.len() < 4096 {
+                    self.pending = rest[start..].to_string();
+                }
+                break;
+            };
+            let payload = &body[..end];
+            if let Some(command) = payload.strip_prefix("633;E;") {
+                self.command = Some(unescape_633(command));
+            } else if payload == "133;C" || payload.starts_with("133;C;") {
+                started.push(self.command.take().unwrap_or_default());
+            }
+            rest = &body[end + terminator..];
+        }
+        started
+    }
+}
+
+/// Undoes the `\xNN` and `\\` escaping VS Code-style shell integration uses
+fn unescape_633(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                match u8::from_str_radix(&hex, 16) {
+                    Ok(byte) => out.push(byte as char),
+                    Err(_) => out.push_str(&format!("\\x{}", hex)),
+                }
+            }
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Totals for one tab (or one tab on one day)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TabSummary {
+    pub runs: u64,
+    pub restarts: u64,
+    /// Restart counts by trigger
+    pub triggers: BTreeMap<String, u64>,
+    /// Exits with a non-zero code or a signal
+    pub crashes: u64,
+    pub pattern_matches: u64,
+    pub commands: u64,
+    pub output_bytes: u64,
+    pub uptime_secs: u64,
+}
+
+impl TabSummary {
+    fn add(&mut self, event: &Event) {
+        match event.kind {
+            EventKind::Start => self.runs += 1,
+            EventKind::Exit => {
+                self.uptime_secs += event.value;
+                if event.detail != "0" {
+                    self.crashes += 1;
+                }
+            }
+            EventKind::Restart => {
+                self.restarts += 1;
+                self.uptime_secs += event.value;
+                *self.triggers.entry(event.detail.clone()).or_default() += 1;
+            }
+            EventKind::Stop => self.uptime_secs += event.value,
+            EventKind::Pattern => self.pattern_matches += 1,
+            EventKind::Command => self.commands += 1,
+            EventKind::Output => self.output_bytes += event.value,
+        }
+    }
+}
+
+/// Per-tab totals over `events`
+pub fn summarize<'a>(events: impl IntoIterator<Item = &'a Event>) -> BTreeMap<String, TabSummary> {
+    let mut tabs: BTreeMap<String, TabSummary> = BTreeMap::new();
+    for event in events {
+        tabs.entry(event.tab.clone()).or_default().add(event);
+    }
+    tabs
+}
+
+/// Per-day, per-tab totals over `events`, keyed by (day number, tab)
+pub fn daily<'a>(events: impl IntoIterator<Item = &'a Event>) -> BTreeMap<(u64, String), TabSummary> {
+    let mut days: BTreeMap<(u64, String), TabSummary> = BTreeMap::new();
+    for event in events {
+        days.entry((event.at / SECS_PER_DAY, event.tab.clone())).or_default().add(event);
+    }
+    days
+}
+
+/// Writes the per-day summary of `events` as CSV
+pub fn export_csv<'a>(path: &Path, events: impl IntoIterator<Item = &'a Event>) -> Result<usize, String> {
+    let days = daily(events);
+    let mut out = String::from("date,tab,runs,restarts,crashes,pattern_matches,commands,output_bytes,uptime_secs\n");
+    for ((day, tab), s) in &days {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            format_day(*day),
+            csv_field(tab),
+            s.runs,
+            s.restarts,
+            s.crashes,
+            s.pattern_matches,
+            s.commands,
+            s.output_bytes,
+            s.uptime_secs
+        ));
+    }
+    fs::write(path, out).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(days.len())
+}
+
+/// Start of the retention window for `retention_days` (0 = everything)
+fn retention_start(retention_days: u64) -> u64 {
+    if retention_days == 0 {
+        0
+    } else {
+        (now() / SECS_PER_DAY).saturating_sub(retention_days) * SECS_PER_DAY
+    }
+}
+
+/// Loads an event log, skipping unreadable lines. Events before `since` are
+/// dropped, and the file is rewritten without them when there were any
+pub fn load_log(path: &Path, since: u64) -> Vec<Event> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let events: Vec<Event> = content.lines().filter_map(Event::from_csv).collect();
+    if events.iter().all(|e| e.at >= since) {
+        return events;
+    }
+    let kept: Vec<Event> = events.into_iter().filter(|e| e.at >= since).collect();
+    let mut out = format!("{}\n", CSV_HEADER);
+    for event in &kept {
+        out.push_str(&event.to_csv());
+        out.push('\n');
+    }
+    let tmp = path.with_extension("csv.tmp");
+    if let Err(e) = fs::write(&tmp, out).and_then(|_| fs::rename(&tmp, path)) {
+        eprintln!("[ANALYTICS] Failed to prune {:?}: {}", path, e);
+    }
+    kept
+}
+
+/// Opens the log at `path` for appending, writing the header to a new file
+fn open_log(path: &Path) -> std::io::Result<File> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{}", CSV_HEADER)?;
+    }
+    Ok(file)
+}
+
+/// Appends `event` as one line
+fn append_log(file: &mut File, event: &Event) -> std::io::Result<()> {
+    file.write_all(format!("{}\n", event.to_csv()).as_bytes())
+}
+
+/// What the bar chart shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Metric {
+    Runs,
+    Restarts,
+    Crashes,
+    PatternMatches,
+    Commands,
+    UptimeMinutes,
+}
+
+impl Metric {
+    const ALL: [Metric; 6] = [
+        Metric::Runs,
+        Metric::Restarts,
+        Metric::Crashes,
+        Metric::PatternMatches,
+        Metric::Commands,
+        Metric::UptimeMinutes,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Metric::Runs => "Runs",
+            Metric::Restarts => "Restarts",
+            Metric::Crashes => "Crashes",
+            Metric::PatternMatches => "Pattern matches",
+            Metric::Commands => "Commands",
+            Metric::UptimeMinutes => "Uptime (minutes)",
+        }
+    }
+
+    fn value(self, summary: &TabSummary) -> u64 {
+        match self {
+            Metric::Runs => summary.runs,
+            Metric::Restarts => summary.restarts,
+            Metric::Crashes => summary.crashes,
+            Metric::PatternMatches => summary.pattern_matches,
+            Metric::Commands => summary.commands,
+            Metric::UptimeMinutes => summary.uptime_secs / 60,
+        }
+    }
+}
+
+/// Current run of a tab, not yet in the log
+pub struct LiveTab {
+    pub title: String,
+    pub uptime_secs: u64,
+    pub output_bytes: u64,
+}
+
+/// Event log and dashboard state
+pub struct Analytics {
+    pub open: bool,
+    settings: AnalyticsSettings,
+    events: Vec<Event>,
+    /// Log opened by the first recorded event, kept open until the path changes
+    log: Option<File>,
+    /// Days shown (`None` = all time)
+    range: Option<u64>,
+    metric: Metric,
+    /// Tab shown in the chart (`None` = all tabs)
+    chart_tab: Option<String>,
+    export_path: String,
+    /// Result of the last export (message, is_error)
+    status: Option<(String, bool)>,
+}
+
+impl Analytics {
+    pub fn new(settings: &AnalyticsSettings) -> Self {
+        let events = Self::load(settings);
+        let export_path = settings.log_path().with_file_name("analytics-summary.csv").to_string_lossy().into_owned();
+        Self {
+            open: false,
+            settings: settings.clone(),
+            events,
+            log: None,
+            range: Some(14),
+            metric: Metric::Runs,
+            chart_tab: None,
+            export_path,
+            status: None,
+        }
+    }
+
+    fn load(settings: &AnalyticsSettings) -> Vec<Event> {
+        if settings.enabled {
+            load_log(&settings.log_path(), retention_start(settings.retention_days))
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Applies changed `[analytics]` settings, reloading the log when its path or retention changed
+    pub fn set_settings(&mut self, settings: &AnalyticsSettings) {
+        if settings.log_path() != self.settings.log_path()
+            || settings.retention_days != self.settings.retention_days
+            || (settings.enabled && !self.settings.enabled)
+        {
+            self.log = None;
+            self.events = Self::load(settings);
+        }
+        if !settings.enabled {
+            self.log = None;
+        }
+        self.settings = settings.clone();
+    }
+
+    /// Timestamps `event` for `tab` and appends it to the log
+    pub fn record(&mut self, tab: &str, event: TabEvent) {
+        let event = Event { at: now(), tab: tab.to_string(), kind: event.kind, detail: event.detail, value: event.value };
+        if self.settings.enabled {
+            let path = self.settings.log_path();
+            let written = match self.log.as_mut() {
+                Some(file) => append_log(file, &event),
+                None => open_log(&path).and_then(|file| append_log(self.log.insert(file), &event)),
+            };
+            if let Err(e) = written {
+                eprintln!("[ANALYTICS] Failed to write {:?}: {}", path, e);
+                self.log = None;
+            }
+        }
+        self.events.push(event);
+    }
+
+    fn in_range(&self) -> impl Iterator<Item = &Event> {
+        let from = self.range.map(|days| (now() / SECS_PER_DAY).saturating_sub(days - 1) * SECS_PER_DAY).unwrap_or(0);
+        self.events.iter().filter(move |e| e.at >= from)
+    }
+
+    /// Draws the dashboard; `live` adds the uptime and output of running processes
+    pub fn show(&mut self, ui: &mut egui::Ui, palette: &Palette, live: &[LiveTab]) {
+        let mut tabs = summarize(self.in_range());
+        for tab in live {
+            let summary = tabs.entry(tab.title.clone()).or_default();
+            summary.uptime_secs += tab.uptime_secs;
+            summary.output_bytes += tab.output_bytes;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Period:");
+            for (label, range) in [("Today", Some(1)), ("7 days", Some(7)), ("14 days", Some(14)), ("30 days", Some(30)), ("All", None)] {
+                ui.selectable_value(&mut self.range, range, label);
+            }
+        });
+        if !self.settings.enabled {
+            ui.colored_label(palette.yellow, "Logging is off ([analytics] enabled = false); only this session is shown.");
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            if tabs.is_empty() {
+                ui.weak("No activity recorded yet.");
+            }
+            egui::Grid::new("analytics_tabs").num_columns(8).striped(true).spacing([14.0, 4.0]).show(ui, |ui| {
+                for header in ["Tab", "Uptime", "Runs", "Restarts", "Crashes", "Matches", "Commands", "Output"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for (title, s) in &tabs {
+                    ui.label(title);
+                    ui.label(format_uptime(s.uptime_secs));
+                    ui.label(s.runs.to_string());
+                    let triggers: Vec<String> = s.triggers.iter().map(|(t, n)| format!("{}: {}", t, n)).collect();
+                    let restarts = ui.label(s.restarts.to_string());
+                    if !triggers.is_empty() {
+                        restarts.on_hover_text(triggers.join("\n"));
+                    }
+                    if s.crashes > 0 {
+                        ui.colored_label(palette.red, s.crashes.to_string());
+                    } else {
+                        ui.label("0");
+                    }
+                    ui.label(s.pattern_matches.to_string());
+                    ui.label(s.commands.to_string());
+                    ui.label(format_size(s.output_bytes));
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("analytics_metric")
+                    .selected_text(self.metric.label())
+                    .show_ui(ui, |ui| {
+                        for metric in Metric::ALL {
+                            ui.selectable_value(&mut self.metric, metric, metric.label());
+                        }
+                    });
+                ui.label("per day for");
+                egui::ComboBox::from_id_source("analytics_chart_tab")
+                    .selected_text(self.chart_tab.as_deref().unwrap_or("All tabs"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.chart_tab, None, "All tabs");
+                        for title in tabs.keys() {
+                            ui.selectable_value(&mut self.chart_tab, Some(title.clone()), title);
+                        }
+                    });
+            });
+            self.chart(ui, palette);
+
+            let commands: Vec<&Event> = self
+                .in_range()
+                .filter(|e| e.kind == EventKind::Command && !e.detail.is_empty())
+                .collect();
+            egui::CollapsingHeader::new(format!("Recent commands ({})", commands.len())).show(ui, |ui| {
+                if commands.is_empty() {
+                    ui.weak("Commands are recorded when the shell emits OSC 133 marks (see CONFIGURATION.md).");
+                }
+                for event in commands.iter().rev().take(50) {
+                    ui.horizontal(|ui| {
+                        ui.weak(format_timestamp(event.at));
+                        ui.label(&event.tab);
+                        ui.monospace(&event.detail);
+                    });
+                }
+            });
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                ui.label("Export to");
+                ui.add(egui::TextEdit::singleline(&mut self.export_path).desired_width(ui.available_width() - 110.0));
+                if ui.button("Export CSV").clicked() {
+                    let path = PathBuf::from(self.export_path.trim());
+                    self.status = Some(match export_csv(&path, self.in_range()) {
+                        Ok(rows) => (format!("Wrote {} rows to {}", rows, path.display()), false),
+                        Err(e) => (e, true),
+                    });
+                }
+            });
+            if let Some((message, is_error)) = &self.status {
+                ui.colored_label(if *is_error { palette.red } else { palette.green }, message);
+            }
+        });
+    }
+
+    /// Bar chart of the selected metric for each day in the period
+    fn chart(&self, ui: &mut egui::Ui, palette: &Palette) {
+        let today = now() / SECS_PER_DAY;
+        let days = self.range.unwrap_or(30).max(1);
+        let first = today + 1 - days;
+        let mut values = vec![0u64; days as usize];
+        for ((day, tab), summary) in daily(self.in_range()) {
+            if day >= first && self.chart_tab.as_ref().map_or(true, |t| *t == tab) {
+                values[(day - first) as usize] += self.metric.value(&summary);
+            }
+        }
+        let max = values.iter().copied().max().unwrap_or(0).max(1);
+
+        let (rect, response) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 140.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, palette.mantle);
+        let plot = rect.shrink2(egui::vec2(8.0, 18.0));
+        let slot = plot.width() / days as f32;
+        let font = egui::FontId::proportional(11.0);
+        for (i, &value) in values.iter().enumerate() {
+            let x = plot.left() + slot * i as f32;
+            let height = plot.height() * value as f32 / max as f32;
+            let bar = egui::Rect::from_min_max(egui::pos2(x + slot * 0.15, plot.bottom() - height), egui::pos2(x + slot * 0.85, plot.bottom()));
+            let hovered = response.hover_pos().is_some_and(|p| p.x >= x && p.x < x + slot);
+            painter.rect_filled(bar, 2.0, if hovered { palette.sky } else { palette.green });
+            if hovered {
+                response.clone().on_hover_text(format!("{}: {}", format_day(first + i as u64), value));
+            }
+        }
+        painter.text(rect.left_top() + egui::vec2(8.0, 2.0), egui::Align2::LEFT_TOP, max.to_string(), font.clone(), palette.subtext0);
+        painter.text(rect.left_bottom() + egui::vec2(8.0, -2.0), egui::Align2::LEFT_BOTTOM, format_day(first), font.clone(), palette.subtext0);
+        painter.text(rect.right_bottom() + egui::vec2(-8.0, -2.0), egui::Align2::RIGHT_BOTTOM, format_day(today), font, palette.subtext0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(at: u64, tab: &str, kind: EventKind, detail: &str, value: u64) -> Event {
+        Event { at, tab: tab.to_string(), kind, detail: detail.to_string(), value }
+    }
+
+    #[test]
+    fn test_dates_and_csv_round_trip() {
+        assert_eq!(format_day(0), "1970-01-01");
+        assert_eq!(format_day(20_744), "2026-10-18");
+        assert_eq!(format_timestamp(951_827_696), "2000-02-29T12:34:56Z");
+        assert_eq!(parse_timestamp("2000-02-29T12:34:56Z"), Some(951_827_696));
+
+        let original = event(1_760_000_000, "Tab, \"one\"", EventKind::Command, "ls -la,\tx", 0);
+        let parsed = Event::from_csv(&original.to_csv()).unwrap();
+        assert_eq!(parsed.tab, "Tab, \"one\"");
+        assert_eq!(parsed.detail, "ls -la, x");
+        assert!(Event::from_csv(CSV_HEADER).is_none());
+    }
+
+    #[test]
+    fn test_shell_marks() {
+        let mut marks = ShellMarks::default();
+        assert_eq!(marks.scan("$ \x1b]133;B\x07\x1b]633;E;ls -la\\x3b echo\x07\x1b]13"), Vec::<String>::new());
+        assert_eq!(marks.scan("3;C\x1b\\out\x1b]133;C\x07"), vec!["ls -la; echo".to_string(), String::new()]);
+        assert_eq!(marks.scan("plain"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_summaries_and_export() {
+        let day = 20_000 * SECS_PER_DAY;
+        let events = vec![
+            event(day, "Norm", EventKind::Start, "norm", 0),
+            event(day + 10, "Norm", EventKind::Pattern, "Done", 0),
+            event(day + 60, "Norm", EventKind::Restart, "pattern", 60),
+            event(day + 61, "Norm", EventKind::Start, "norm", 0),
+            event(day + SECS_PER_DAY, "Norm", EventKind::Exit, "1", 300),
+            event(day + SECS_PER_DAY, "Norm", EventKind::Output, "", 2048),
+            event(day + SECS_PER_DAY, "Shell", EventKind::Command, "ls", 0),
+        ];
+        let tabs = summarize(&events);
+        let norm = &tabs["Norm"];
+        assert_eq!((norm.runs, norm.restarts, norm.crashes, norm.pattern_matches), (2, 1, 1, 1));
+        assert_eq!((norm.uptime_secs, norm.output_bytes), (360, 2048));
+        assert_eq!(norm.triggers["pattern"], 1);
+        assert_eq!(tabs["Shell"].commands, 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("summary.csv");
+        assert_eq!(export_csv(&path, &events).unwrap(), 3);
+        let csv = fs::read_to_string(&path).unwrap();
+        assert!(csv.contains(&format!("{},Norm,2,1,0,1,0,0,60\n", format_day(20_000))));
+        assert!(csv.contains(&format!("{},Norm,0,0,1,0,0,2048,300\n", format_day(20_001))));
+
+        let log = dir.path().join("analytics.csv");
+        let mut file = open_log(&log).unwrap();
+        for e in &events {
+            append_log(&mut file, e).unwrap();
+        }
+        drop(file);
+        assert_eq!(load_log(&log, 0), events);
+        // Reopening does not repeat the header
+        append_log(&mut open_log(&log).unwrap(), &events[0]).unwrap();
+        assert_eq!(fs::read_to_string(&log).unwrap().matches(CSV_HEADER).count(), 1);
+    }
+
+    #[test]
+    fn test_load_prunes_old_events() {
+        let day = 20_000 * SECS_PER_DAY;
+        let events = vec![
+            event(day, "Norm", EventKind::Start, "norm", 0),
+            event(day + SECS_PER_DAY, "Norm", EventKind::Exit, "0", 60),
+            event(day + 2 * SECS_PER_DAY, "Shell", EventKind::Command, "ls", 0),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("analytics.csv");
+        let mut file = open_log(&log).unwrap();
+        for e in &events {
+            append_log(&mut file, e).unwrap();
+        }
+        drop(file);
+
+        assert_eq!(load_log(&log, day + SECS_PER_DAY), events[1..]);
+        // The pruned file keeps its header and the recent events
+        let content = fs::read_to_string(&log).unwrap();
+        assert!(content.starts_with(CSV_HEADER));
+        assert_eq!(load_log(&log, 0), events[1..]);
+        assert!(!dir.path().join("analytics.csv.tmp").exists());
+    }
+}
@@ -18,9 +18,10 @@
 //! ## Features
 //! 
 //! - Split-screen terminal interface
-//! - Focus cycling with Shift+Tab (Tab is forwarded to the terminal)
+//! - Configurable key bindings (`[keybindings]`); Shift+Tab cycles focus by default
 //! - Auto-restart functionality based on pattern matching
 //! - Full keyboard input support including arrow keys
+//! - Mouse reporting to applications that request it (Shift bypasses)
 //! - ANSI color rendering with Catppuccin theme
 
 use eframe::{egui, App, Frame};
@@ -29,9 +30,74 @@ use std::io::{Read, Write};
 use std::sync::mpsc::{channel, Receiver, TryRecvError};
 use std::thread;
 
-use crate::config::{AppConfig, AppSettings, TabConfig, DndSettings};
-use crate::terminal::{TerminalCell, TerminalEmulator};
-use crate::theme::CatppuccinTheme;
+use crate::analytics::{self, Analytics, EventKind, LiveTab, RestartTrigger, ShellMarks, TabEvent};
+use crate::bookmarks::{self, BookmarkPicker};
+use crate::cast::{self, CastHeader, Player, RecordingPicker, Recorder};
+use crate::config::{self, AppConfig, AppSettings, ButtonConfig, ConfigSource, ControlSettings, TabConfig, MAX_TABS, DndRouting, DndSettings, DropMode, IdleSettings, LogMode, LoggingSettings, NotifySettings, QueueWait, ZoomScope};
+use crate::control::{self, ControlServer, History, Request, Subscription};
+use crate::dnd::{self, DropItem, DropQueue, QueueJob};
+use crate::file_manager::{FileManager, FileManagerEvent};
+use crate::help::{HelpContext, HelpViewer};
+use crate::scripts::{LaunchTarget, ScriptCatalog};
+use crate::session_log::{self, SessionLog};
+use crate::settings::SettingsEditor;
+use crate::fonts::{self, CellMetrics};
+use crate::input::{self, Action, Keymap};
+use crate::inspector::Inspector;
+use crate::palette::{Command, CommandPalette, Entry as PaletteEntry};
+use crate::idle::{IdleEvent, IdleState, IdleWatch};
+use crate::notify::{self, Alert, Notifications, Watch};
+use crate::mouse::{self, MouseButton, MouseEvent, MouseEventKind, MouseTracking};
+use crate::terminal::{self as term, TerminalCell, TerminalEmulator, TraceEvent};
+use crate::theme::{self, Palette, Theme};
+
+/// Most raw output buffered for the inspector between frames
+const MAX_RAW_CAPTURE: usize = 64 * 1024;
+
+/// Shell names treated as idle when deciding whether a job is in the foreground
+const SHELLS: &[&str] = &["bash", "zsh", "sh", "dash", "fish", "ksh", "tcsh", "csh", "nu", "pwsh"];
+
+/// Per-pane control from the header buttons, the header context menu or a key binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneControl {
+    Restart,
+    /// SIGINT to the foreground process group
+    Interrupt,
+    /// SIGTERM to the foreground process group
+    Terminate,
+    /// SIGKILL to the foreground process group
+    Kill,
+    Clear,
+    /// Full terminal reset (RIS)
+    Reset,
+}
+
+impl PaneControl {
+    const ALL: [PaneControl; 6] = [
+        PaneControl::Restart,
+        PaneControl::Interrupt,
+        PaneControl::Terminate,
+        PaneControl::Kill,
+        PaneControl::Clear,
+        PaneControl::Reset,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PaneControl::Restart => "Restart",
+            PaneControl::Interrupt => "Interrupt (SIGINT)",
+            PaneControl::Terminate => "Terminate (SIGTERM)",
+            PaneControl::Kill => "Force kill (SIGKILL)",
+            PaneControl::Clear => "Clear screen",
+            PaneControl::Reset => "Reset terminal",
+        }
+    }
+
+    /// Ends or interrupts the running job, so it is confirmed while one is in the foreground
+    fn disruptive(self) -> bool {
+        matches!(self, PaneControl::Restart | PaneControl::Interrupt | PaneControl::Terminate | PaneControl::Kill)
+    }
+}
 
 /// Represents a single terminal tab with its own PTY and state
 /// 
@@ -43,18 +109,78 @@ use crate::theme::CatppuccinTheme;
 /// - Command execution with proper environment setup
 /// - Terminal output processing through the emulator
 /// - Pattern-based auto-restart functionality
+/// - Activity events (start, exit, restart, commands) for analytics
 /// - Input handling and forwarding to the PTY
 pub struct TerminalTab {
     title: String,
     config: TabConfig,
     pty_master: Box<dyn portable_pty::MasterPty + Send>,
     pty_writer: Option<Box<dyn std::io::Write + Send>>,
+    /// Spawned process, polled each frame to notice when it exits
+    child: Option<Box<dyn portable_pty::Child + Send + Sync>>,
+    /// The process has exited and its exit was recorded
+    exited: bool,
     output_rx: Receiver<String>,
     output: String,
     terminal_emulator: TerminalEmulator,
     needs_restart: bool,
+    /// Why `needs_restart` was set
+    restart_trigger: RestartTrigger,
     startup_time: std::time::Instant,
     pattern_matches: u32,
+    /// Current PTY/emulator grid size as (rows, cols)
+    grid_size: (usize, usize),
+    /// Per-pane zoom factor applied on top of the configured font size
+    zoom: f32,
+    /// Button pressed inside this pane while mouse reporting is active
+    mouse_held: Option<MouseButton>,
+    /// Last cell reported for motion, to avoid repeating reports within one cell
+    mouse_last_cell: Option<(usize, usize)>,
+    /// Find-bar query while the find bar is open for this pane
+    search: Option<String>,
+    /// Output bytes of the current run not yet reported
+    output_bytes: u64,
+    /// Shell-integration parser for command marks
+    shell_marks: ShellMarks,
+    /// Analytics events waiting to be collected by the app
+    events: Vec<TabEvent>,
+    /// The developer tools inspector is following this pane
+    inspect: bool,
+    /// Raw output received since the inspector last collected it
+    raw_capture: String,
+    /// Cell under the pointer while inspecting, as (row, col)
+    hover_cell: Option<(usize, usize)>,
+    /// Control requested from the pane header, handled by the app after drawing
+    control: Option<PaneControl>,
+    /// Files being dragged over the window would be dropped here
+    drop_target: bool,
+    /// Dropped files waiting to run one at a time (`[tabs.dnd] mode = "queue"`)
+    drop_queue: Option<DropQueue>,
+    /// Session log of the output (`log` in `[[tabs]]`)
+    session_log: Option<SessionLog>,
+    /// `[logging]` settings the session log was started with
+    log_settings: LoggingSettings,
+    /// asciicast recording started from the pane menu
+    recorder: Option<Recorder>,
+    /// Plain-text output kept for control socket `scrollback` requests
+    history: Option<History>,
+    /// Output for control socket subscribers since the app last collected it
+    control_capture: Option<String>,
+    /// `[tabs.notify]` events of the current run
+    watch: Watch,
+    /// Alerts waiting to be collected by the app
+    alerts: Vec<Alert>,
+    /// Alerts delivered while the pane was not focused (header badge)
+    unread: u32,
+    /// Output arrived while the pane was not focused (header badge)
+    activity: bool,
+    /// Time since output and input, for hang detection
+    idle: IdleWatch,
+    /// Last hang check (about once a second)
+    idle_checked: std::time::Instant,
+    /// Name of the last foreground process group seen, so `ps` runs once per job
+    #[cfg(unix)]
+    foreground_name: std::cell::RefCell<Option<(i32, String)>>,
 }
 
 impl TerminalTab {
@@ -118,15 +244,18 @@ impl TerminalTab {
             cmd
         };
 
-        match pty_pair.slave.spawn_command(cmd) {
-            Ok(_child) => println!("Command spawned successfully for tab: {}", config.title),
+        let child = match pty_pair.slave.spawn_command(cmd) {
+            Ok(child) => {
+                println!("Command spawned successfully for tab: {}", config.title);
+                child
+            }
             Err(e) => {
                 eprintln!("Failed to spawn command for tab {}: {}", config.title, e);
                 // Fall back to bash if the command fails
                 let fallback_cmd = CommandBuilder::new("bash");
-                let _fallback_child = pty_pair.slave.spawn_command(fallback_cmd).unwrap();
+                pty_pair.slave.spawn_command(fallback_cmd).unwrap()
             }
-        }
+        };
 
         let mut reader = pty_pair.master.try_clone_reader().unwrap();
 
@@ -151,17 +280,243 @@ impl TerminalTab {
         // Get the writer once and store it
         let writer = pty_pair.master.take_writer().ok();
 
+        let events = vec![TabEvent::new(EventKind::Start, config.command.clone(), 0)];
+        let now = std::time::Instant::now();
+        let watch = Watch::new(config.notify.clone(), now);
+        let idle = IdleWatch::new(config.idle.clone(), now);
         Self {
             title: config.title.clone(),
             config,
             pty_master: pty_pair.master,
             pty_writer: writer,
+            child: Some(child),
+            exited: false,
             output_rx,
             output: String::new(),
             terminal_emulator: TerminalEmulator::new(24, 80),
             needs_restart: false,
+            restart_trigger: RestartTrigger::Manual,
             startup_time: std::time::Instant::now(),
             pattern_matches: 0,
+            grid_size: (24, 80),
+            zoom: 1.0,
+            mouse_held: None,
+            mouse_last_cell: None,
+            search: None,
+            output_bytes: 0,
+            shell_marks: ShellMarks::default(),
+            events,
+            inspect: false,
+            raw_capture: String::new(),
+            hover_cell: None,
+            control: None,
+            drop_target: false,
+            drop_queue: None,
+            session_log: None,
+            log_settings: LoggingSettings::default(),
+            recorder: None,
+            history: None,
+            control_capture: None,
+            watch,
+            alerts: Vec::new(),
+            unread: 0,
+            activity: false,
+            idle,
+            idle_checked: now,
+            #[cfg(unix)]
+            foreground_name: Default::default(),
+        }
+    }
+
+    /// Resizes the PTY and terminal emulator to `rows` x `cols`
+    ///
+    /// The child process receives SIGWINCH from the PTY so full-screen tools
+    /// can redraw at the new size. No-op when the size is unchanged.
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        if rows == 0 || cols == 0 || self.grid_size == (rows, cols) {
+            return;
+        }
+        self.grid_size = (rows, cols);
+        let size = PtySize {
+            rows: rows.min(u16::MAX as usize) as u16,
+            cols: cols.min(u16::MAX as usize) as u16,
+            ..Default::default()
+        };
+        if let Err(e) = self.pty_master.resize(size) {
+            eprintln!("[RESIZE] Failed to resize PTY for {}: {}", self.title, e);
+        }
+        self.terminal_emulator.resize(rows, cols);
+        if let Some(result) = self.recorder.as_mut().map(|r| r.resize(rows, cols)) {
+            self.check_recording(result);
+        }
+    }
+
+    /// Writes raw bytes to the PTY
+    pub fn send(&mut self, bytes: &[u8]) {
+        if let Some(ref mut writer) = self.pty_writer {
+            let _ = writer.write_all(bytes);
+        }
+        self.idle.input(std::time::Instant::now());
+    }
+
+    /// Writes pasted text to the PTY, bracketed when the application enabled DEC 2004
+    pub fn paste(&mut self, text: &str) {
+        let bytes = term::encode_paste(text, self.terminal_emulator.bracketed_paste());
+        self.send(&bytes);
+    }
+
+    /// Clears the pane and sends a form feed so the shell redraws its prompt
+    pub fn clear(&mut self) {
+        self.terminal_emulator.clear_screen();
+        self.send(b"\x0c");
+    }
+
+    /// Full terminal reset (RIS); the form feed asks the program to redraw
+    pub fn reset(&mut self) {
+        self.terminal_emulator.reset();
+        self.mouse_held = None;
+        self.mouse_last_cell = None;
+        self.send(b"\x0c");
+    }
+
+    /// Applies a pane control; asking for confirmation is up to the caller
+    pub fn apply_control(&mut self, control: PaneControl) {
+        match control {
+            PaneControl::Restart => self.request_restart(RestartTrigger::Manual),
+            PaneControl::Clear => self.clear(),
+            PaneControl::Reset => self.reset(),
+            signal => self.signal_foreground(signal),
+        }
+    }
+
+    /// Sends the control's signal to the pane's foreground process group
+    ///
+    /// Without process groups, SIGINT falls back to typing Ctrl+C and the others
+    /// end the tab's own process.
+    fn signal_foreground(&mut self, control: PaneControl) {
+        #[cfg(unix)]
+        if let Some(pgid) = self.pty_master.process_group_leader() {
+            let signal = match control {
+                PaneControl::Interrupt => libc::SIGINT,
+                PaneControl::Terminate => libc::SIGTERM,
+                _ => libc::SIGKILL,
+            };
+            // SAFETY: kill(2) has no memory-safety requirements; a negative pid addresses the group
+            if unsafe { libc::kill(-pgid, signal) } == 0 {
+                return;
+            }
+            eprintln!("[SIGNAL] Failed to signal '{}': {}", self.title, std::io::Error::last_os_error());
+        }
+        if control == PaneControl::Interrupt {
+            self.send(b"\x03");
+        } else if let Some(child) = self.child.as_mut() {
+            if let Err(e) = child.kill() {
+                eprintln!("[SIGNAL] Failed to stop '{}': {}", self.title, e);
+            }
+        }
+    }
+
+    /// Name of the job in the foreground, unless it is the shell itself
+    pub fn foreground_process(&self) -> Option<String> {
+        if self.exited {
+            return None;
+        }
+        #[cfg(unix)]
+        {
+            let pgid = self.pty_master.process_group_leader()?;
+            let mut cached = self.foreground_name.borrow_mut();
+            let name = match cached.as_ref() {
+                Some((pid, name)) if *pid == pgid => name.clone(),
+                _ => {
+                    let name = process_name(pgid).unwrap_or_else(|| format!("pid {}", pgid));
+                    *cached = Some((pgid, name.clone()));
+                    name
+                }
+            };
+            (!SHELLS.contains(&name.as_str())).then_some(name)
+        }
+        #[cfg(not(unix))]
+        {
+            None
+        }
+    }
+
+    /// Forwards pointer input over the grid in `rect` to the PTY
+    ///
+    /// Only active when the running application enabled mouse reporting (DEC
+    /// 1000/1002/1003). Holding Shift bypasses reporting so the pane keeps its
+    /// local behavior. Drags that leave the pane are clamped to the edge cell.
+    pub fn forward_mouse(&mut self, ui: &egui::Ui, rect: egui::Rect, metrics: &CellMetrics) {
+        let (tracking, encoding) = self.terminal_emulator.mouse_mode();
+        if tracking == MouseTracking::Off {
+            self.mouse_held = None;
+            self.mouse_last_cell = None;
+            return;
+        }
+
+        let grid = self.grid_size;
+        let cell_at = |pos: egui::Pos2| metrics.cell_at(rect.min, pos, grid);
+        let mut held = self.mouse_held;
+        let mut last_cell = self.mouse_last_cell;
+        let mut events: Vec<MouseEvent> = Vec::new();
+
+        ui.input(|i| {
+            let make = |kind, (row, col): (usize, usize), m: egui::Modifiers| MouseEvent {
+                kind,
+                row,
+                col,
+                alt: m.alt,
+                ctrl: m.ctrl,
+            };
+            for ev in &i.events {
+                match ev {
+                    egui::Event::PointerButton { pos, button, pressed, modifiers } if !modifiers.shift => {
+                        let button = match button {
+                            egui::PointerButton::Primary => MouseButton::Left,
+                            egui::PointerButton::Middle => MouseButton::Middle,
+                            egui::PointerButton::Secondary => MouseButton::Right,
+                            _ => continue,
+                        };
+                        if *pressed && rect.contains(*pos) {
+                            held = Some(button);
+                            events.push(make(MouseEventKind::Press(button), cell_at(*pos), *modifiers));
+                        } else if !*pressed && held == Some(button) {
+                            held = None;
+                            events.push(make(MouseEventKind::Release(button), cell_at(*pos), *modifiers));
+                        }
+                    }
+                    egui::Event::PointerMoved(pos) if !i.modifiers.shift => {
+                        let cell = cell_at(*pos);
+                        if last_cell == Some(cell) {
+                            continue;
+                        }
+                        if let Some(button) = held {
+                            last_cell = Some(cell);
+                            events.push(make(MouseEventKind::Drag(button), cell, i.modifiers));
+                        } else if rect.contains(*pos) {
+                            last_cell = Some(cell);
+                            events.push(make(MouseEventKind::Motion, cell, i.modifiers));
+                        }
+                    }
+                    egui::Event::MouseWheel { delta, modifiers, .. } if !modifiers.shift && delta.y != 0.0 => {
+                        if let Some(pos) = i.pointer.hover_pos().filter(|p| rect.contains(*p)) {
+                            let kind = if delta.y > 0.0 { MouseEventKind::WheelUp } else { MouseEventKind::WheelDown };
+                            events.push(make(kind, cell_at(pos), *modifiers));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        self.mouse_held = held;
+        self.mouse_last_cell = last_cell;
+        if let Some(ref mut writer) = self.pty_writer {
+            for event in &events {
+                if let Some(bytes) = mouse::encode(tracking, encoding, event) {
+                    let _ = writer.write_all(&bytes);
+                }
+            }
         }
     }
 
@@ -173,12 +528,39 @@ impl TerminalTab {
         loop {
             match self.output_rx.try_recv() {
                 Ok(data) => {
+                    self.output_bytes += data.len() as u64;
+                    self.activity = true;
+                    self.idle.output(std::time::Instant::now());
+                    if self.inspect && self.raw_capture.len() < MAX_RAW_CAPTURE {
+                        self.raw_capture.push_str(&data);
+                    }
+                    if let Some(log) = self.session_log.as_mut() {
+                        log.write(&data);
+                    }
+                    if let Some(result) = self.recorder.as_mut().map(|r| r.output(&data)) {
+                        self.check_recording(result);
+                    }
+                    if let Some(history) = self.history.as_mut() {
+                        history.push(&data);
+                    }
+                    if let Some(capture) = self.control_capture.as_mut() {
+                        capture.push_str(&data);
+                    }
+                    for command in self.shell_marks.scan(&data) {
+                        self.events.push(TabEvent::new(EventKind::Command, command, 0));
+                    }
+
                     // Process data through terminal emulator
                     self.terminal_emulator.process_ansi_data(&data);
 
                     // Strip ANSI codes for pattern matching
                     let plain_text = Self::strip_ansi_codes(&data);
                     self.output.push_str(&plain_text);
+                    self.alerts.extend(self.watch.output(&plain_text, std::time::Instant::now()));
+
+                    if let Some(queue) = self.drop_queue.as_mut() {
+                        queue.observe(&data, &plain_text, &self.config.success_patterns);
+                    }
 
                     // Check for success patterns if auto-restart is enabled
                     if self.config.auto_restart_on_success {
@@ -188,6 +570,7 @@ impl TerminalTab {
                             for pattern in &self.config.success_patterns {
                                 if plain_text.contains(pattern) {
                                     self.pattern_matches += 1;
+                                    self.events.push(TabEvent::new(EventKind::Pattern, pattern.clone(), 0));
                                     println!(
                                         "[PATTERN] Found '{}' in tab '{}' (match #{}/2)",
                                         pattern, self.title, self.pattern_matches
@@ -199,7 +582,7 @@ impl TerminalTab {
                                             "[PATTERN] Triggering restart for tab '{}'",
                                             self.title
                                         );
-                                        self.needs_restart = true;
+                                        self.request_restart(RestartTrigger::Pattern);
                                         break;
                                     }
                                 }
@@ -220,6 +603,160 @@ impl TerminalTab {
                 Err(TryRecvError::Disconnected) => break,
             }
         }
+
+        if !self.exited {
+            if let Some(Ok(Some(status))) = self.child.as_mut().map(|c| c.try_wait()) {
+                let code = status.signal().map(str::to_string).unwrap_or_else(|| status.exit_code().to_string());
+                println!("[EXIT] Process in tab '{}' exited ({})", self.title, code);
+                self.alerts.extend(self.watch.exit(&code));
+                self.end_run(EventKind::Exit, code);
+                self.exited = true;
+            }
+        }
+        self.run_drop_queue();
+    }
+
+    /// Queues dropped items to run one at a time
+    pub fn enqueue_drop(&mut self, jobs: Vec<QueueJob>) {
+        let wait = self.config.dnd.queue_wait;
+        self.drop_queue.get_or_insert_with(|| DropQueue::new(wait)).push(jobs);
+    }
+
+    /// Starts the next queued item when the previous one finished
+    fn run_drop_queue(&mut self) {
+        let Some(queue) = self.drop_queue.as_mut() else {
+            return;
+        };
+        if self.exited {
+            queue.interrupt();
+            queue.cancel();
+        } else if !self.needs_restart {
+            // A pending restart ends the item; the next one goes to the new process
+            if let Some(command) = queue.next() {
+                self.send(format!("{}\n", command).as_bytes());
+            }
+        }
+        if let Some(queue) = self.drop_queue.as_ref().filter(|queue| queue.is_finished()) {
+            println!("[DND] Drop queue in tab '{}' finished: {}", self.title, queue.summary());
+            self.drop_queue = None;
+        }
+    }
+
+    /// Skips the running queue item, interrupting it when the queue waits for exit status
+    pub fn skip_queue_item(&mut self) {
+        let wait = self.config.dnd.queue_wait;
+        if self.drop_queue.as_mut().is_some_and(DropQueue::skip) && wait == QueueWait::Exit {
+            self.send(b"\x03");
+        }
+    }
+
+    /// Marks the tab for restart, remembering why for analytics
+    pub fn request_restart(&mut self, trigger: RestartTrigger) {
+        self.needs_restart = true;
+        self.restart_trigger = trigger;
+    }
+
+    /// Queues the end of the current run: its uptime and the output it produced
+    fn end_run(&mut self, kind: EventKind, detail: String) {
+        let uptime = if self.exited { 0 } else { self.startup_time.elapsed().as_secs() };
+        self.events.push(TabEvent::new(kind, detail, uptime));
+        if self.output_bytes > 0 {
+            self.events.push(TabEvent::new(EventKind::Output, "", std::mem::take(&mut self.output_bytes)));
+        }
+    }
+
+    /// Records that the process was still running when its pane or the app closed
+    pub fn stop(&mut self, reason: &str) {
+        if !self.exited {
+            self.end_run(EventKind::Stop, reason.to_string());
+            self.exited = true;
+        }
+    }
+
+    /// Takes the analytics events queued since the last call
+    pub fn take_events(&mut self) -> Vec<TabEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Takes the notification alerts raised since the last call
+    pub fn take_alerts(&mut self) -> Vec<Alert> {
+        std::mem::take(&mut self.alerts)
+    }
+
+    /// Updates the hang detection state and runs `on_idle`; checks about once a second
+    /// when `idle_timeout` or `stall_pattern` is set
+    pub fn check_idle(&mut self, now: std::time::Instant) {
+        if self.exited || !self.idle.enabled() || now.saturating_duration_since(self.idle_checked).as_secs() < 1 {
+            return;
+        }
+        self.idle_checked = now;
+        let job_running = self.foreground_process().is_some();
+        let line = self.terminal_emulator.cursor_line();
+        match self.idle.check(&line, job_running, now) {
+            Some(IdleEvent::Respond(text)) => {
+                println!("[IDLE] Tab '{}' waits for input at {:?}; sending {:?}", self.title, line, text);
+                self.send(format!("{}\r", text).as_bytes());
+            }
+            Some(IdleEvent::Restart) => {
+                println!("[IDLE] Tab '{}' idle for {}s; restarting", self.title, self.config.idle.idle_timeout);
+                self.request_restart(RestartTrigger::Idle);
+            }
+            None => {}
+        }
+    }
+
+    /// Clears the unread and activity badges
+    pub fn mark_seen(&mut self) {
+        self.unread = 0;
+        self.activity = false;
+    }
+
+    /// Starts or stops capturing raw output and the parsed stream for the inspector
+    pub fn set_inspect(&mut self, on: bool) {
+        if self.inspect != on {
+            self.inspect = on;
+            self.raw_capture.clear();
+            self.hover_cell = None;
+            self.terminal_emulator.set_tracing(on);
+        }
+    }
+
+    /// Takes the raw output and parsed events captured since the last call
+    pub fn take_inspection(&mut self) -> (String, Vec<TraceEvent>) {
+        (std::mem::take(&mut self.raw_capture), self.terminal_emulator.take_trace())
+    }
+
+    /// Keeps `lines` lines of output for `scrollback` requests; `None` stops keeping them
+    pub fn configure_history(&mut self, lines: Option<usize>) {
+        if self.history.as_ref().map(History::max_lines) != lines {
+            self.history = lines.map(History::new);
+        }
+    }
+
+    /// Starts or stops collecting output for control socket subscribers
+    pub fn set_control_capture(&mut self, on: bool) {
+        if self.control_capture.is_some() != on {
+            self.control_capture = on.then(String::new);
+        }
+    }
+
+    /// Output collected for subscribers since the last call
+    pub fn take_control_output(&mut self) -> String {
+        self.control_capture.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Cell under the pointer while inspecting
+    pub fn hover_cell(&self) -> Option<(usize, usize)> {
+        self.hover_cell
+    }
+
+    /// Uptime and unreported output of the running process, for the dashboard
+    pub fn live(&self) -> LiveTab {
+        LiveTab {
+            title: self.title.clone(),
+            uptime_secs: if self.exited { 0 } else { self.startup_time.elapsed().as_secs() },
+            output_bytes: self.output_bytes,
+        }
     }
 
     /// Restarts the terminal tab if needed
@@ -231,22 +768,37 @@ impl TerminalTab {
         }
 
         println!("[RESTART] Restarting tab: {}", self.title);
+        self.end_run(EventKind::Restart, self.restart_trigger.as_str().to_string());
+        self.restart_trigger = RestartTrigger::Manual;
 
         // Clear output and reset restart flag
         self.output.clear();
-        self.terminal_emulator.clear_screen();
+        // Fresh emulator so modes set by the previous process (e.g., mouse reporting) do not leak
+        self.terminal_emulator = TerminalEmulator::new(self.grid_size.0, self.grid_size.1);
+        self.terminal_emulator.set_tracing(self.inspect);
+        self.mouse_held = None;
         self.needs_restart = false;
         self.startup_time = std::time::Instant::now();
         self.pattern_matches = 0;
+        self.watch.restart(self.startup_time);
+        self.idle.restart(self.startup_time);
+        // The running queue item died with the old process; the rest go to the new one
+        if let Some(queue) = self.drop_queue.as_mut() {
+            queue.interrupt();
+        }
+        if let Some(log) = self.session_log.as_mut() {
+            log.restart(&self.config.command);
+        }
 
         // Add a small delay to allow previous PTY resources to clean up
         std::thread::sleep(std::time::Duration::from_millis(100));
 
         // Create new PTY with better error handling
+        let (rows, cols) = self.grid_size;
         let pty_system = NativePtySystem::default();
         let pty_pair = match pty_system.openpty(PtySize {
-            rows: 24,
-            cols: 80,
+            rows: rows as u16,
+            cols: cols as u16,
             ..Default::default()
         }) {
             Ok(pair) => pair,
@@ -266,8 +818,8 @@ impl TerminalTab {
             cmd.env("TERM", "xterm-256color");
             cmd.env("COLORTERM", "truecolor");
             cmd.env("SHELL", "/bin/zsh");
-            cmd.env("COLUMNS", "80");
-            cmd.env("LINES", "24");
+            cmd.env("COLUMNS", cols.to_string());
+            cmd.env("LINES", rows.to_string());
             // Force color output
             cmd.env("FORCE_COLOR", "1");
             cmd.env("CLICOLOR", "1");
@@ -290,8 +842,8 @@ impl TerminalTab {
             cmd.env("TERM", "xterm-256color");
             cmd.env("COLORTERM", "truecolor");
             cmd.env("SHELL", "/bin/zsh");
-            cmd.env("COLUMNS", "80");
-            cmd.env("LINES", "24");
+            cmd.env("COLUMNS", cols.to_string());
+            cmd.env("LINES", rows.to_string());
             cmd.env("FORCE_COLOR", "1");
             cmd.env("CLICOLOR", "1");
             cmd.env("CLICOLOR_FORCE", "1");
@@ -302,8 +854,11 @@ impl TerminalTab {
         };
 
         match pty_pair.slave.spawn_command(cmd) {
-            Ok(_child) => {
+            Ok(child) => {
                 println!("[RESTART] Command spawned successfully for: {}", self.title);
+                self.child = Some(child);
+                self.exited = false;
+                self.events.push(TabEvent::new(EventKind::Start, self.config.command.clone(), 0));
                 // Add success completion message to output
                 self.output
                     .push_str("\n✅ Script executed successfully\n\n");
@@ -405,6 +960,85 @@ impl TerminalTab {
         &self.terminal_emulator
     }
 
+    /// Replaces the tab's configuration, restarting it when the command changed
+    pub fn reconfigure(&mut self, config: TabConfig) {
+        if config.command != self.config.command {
+            self.request_restart(RestartTrigger::Settings);
+        }
+        if config.title != self.title {
+            // Log files are named after the title; `configure_log` starts a new one
+            self.session_log = None;
+        }
+        self.title = config.title.clone();
+        self.watch.configure(config.notify.clone());
+        self.idle.configure(config.idle.clone());
+        self.config = config;
+    }
+
+    /// Starts or stops the session log to match `log` and `[logging]`
+    pub fn configure_log(&mut self, settings: &LoggingSettings) {
+        let mode = self.session_log.as_ref().map_or(LogMode::Off, SessionLog::mode);
+        if mode == self.config.log && self.log_settings == *settings {
+            return;
+        }
+        self.log_settings = settings.clone();
+        self.session_log = SessionLog::start(settings, self.config.log, &self.title, &self.config.command);
+    }
+
+    /// File the session log is writing, when logging is on
+    pub fn log_path(&self) -> Option<&std::path::Path> {
+        self.session_log.as_ref()?.path()
+    }
+
+    /// Starts an asciicast recording in the `[logging]` folder, or stops the current one
+    pub fn toggle_recording(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            println!("[CAST] Saved {}", recorder.path().display());
+            return;
+        }
+        let (rows, cols) = self.grid_size;
+        let mut header = CastHeader::new(cols.max(1), rows.max(1));
+        let at = analytics::now();
+        header.timestamp = Some(at);
+        header.title = Some(self.title.clone());
+        header.env.insert("TERM".to_string(), "xterm-256color".to_string());
+        let name = session_log::file_name(&session_log::file_prefix(&self.title), at, "cast");
+        match Recorder::start(self.log_settings.log_dir().join(name), &header) {
+            Ok(recorder) => self.recorder = Some(recorder),
+            Err(e) => eprintln!("[CAST] {}", e),
+        }
+    }
+
+    /// Stops recording after a write error
+    fn check_recording(&mut self, result: Result<(), String>) {
+        if let Err(e) = result {
+            eprintln!("[CAST] Stopped recording: {}", e);
+            self.recorder = None;
+        }
+    }
+
+    /// File being recorded to, while recording
+    pub fn recording(&self) -> Option<&std::path::Path> {
+        self.recorder.as_ref().map(Recorder::path)
+    }
+
+    /// Looks up the working directory of the pane's foreground process, when the
+    /// platform exposes it; the lookup may be slow, so run it off the UI thread
+    pub fn working_dir_lookup(&self) -> impl FnOnce() -> Option<std::path::PathBuf> + Send + 'static {
+        #[cfg(unix)]
+        let pid = self.pty_master.process_group_leader();
+        move || {
+            #[cfg(unix)]
+            {
+                process_cwd(pid?)
+            }
+            #[cfg(not(unix))]
+            {
+                None
+            }
+        }
+    }
+
     /// Strips ANSI escape codes from text for pattern matching
     /// 
     /// # Arguments
@@ -444,6 +1078,28 @@ impl TerminalTab {
     }
 }
 
+/// Shell started in fixed pane `index` when the configuration has no tab for it
+fn default_tab(index: usize) -> TabConfig {
+    TabConfig {
+        title: format!("Terminal {}", index + 1),
+        command: "bash".to_string(),
+        auto_restart_on_success: false,
+        success_patterns: vec![],
+        dnd: DndSettings::default(),
+        log: LogMode::Off,
+        notify: NotifySettings::default(),
+        idle: IdleSettings::default(),
+    }
+}
+
+/// Profile switch that needs an answer: jobs it would stop, or why it cannot load
+struct ProfileSwitch {
+    name: Option<String>,
+    config: Result<AppConfig, String>,
+    // "Pane 2: ffmpeg" for each running job whose pane gets a new command
+    jobs: Vec<String>,
+}
+
 /// Main application struct managing terminal tabs and UI state
 /// 
 /// Handles the overall application state, terminal focus management, and UI rendering.
@@ -458,7 +1114,57 @@ pub struct AudioToolkitApp {
     // Runtime, interactive split fractions (persist defaults from config)
     right_top_frac: f32,       // top row height share in right cluster
     right_hsplit_frac: f32,    // top-left vs top-right width share in right cluster
+    #[allow(dead_code)]
     left_buttons_frac: f32,    // fraction of left panel height devoted to buttons container
+    // Zoom factor applied to every pane (multiplies each pane's own zoom)
+    global_zoom: f32,
+    // Active color theme and every theme available for live switching
+    theme: Theme,
+    themes: Vec<Theme>,
+    // Multi-line paste awaiting confirmation: (tab index, text)
+    pending_paste: Option<(usize, String)>,
+    // Chord -> action bindings (defaults merged with [keybindings])
+    keymap: Keymap,
+    // Give the find bar keyboard focus on the next frame
+    focus_search: bool,
+    // Actions panel buttons ([[buttons]] or the built-in set)
+    buttons: Vec<ButtonConfig>,
+    // Button awaiting confirmation (index into `buttons`)
+    pending_button: Option<usize>,
+    // Pane control awaiting confirmation: (tab index, control, foreground job)
+    pending_control: Option<(usize, PaneControl, String)>,
+    // Profile switch awaiting confirmation
+    pending_profile: Option<ProfileSwitch>,
+    // Stable window ids for ephemeral panes (aligned with `tabs[MAX_TABS..]`)
+    ephemeral_ids: Vec<u64>,
+    next_pane_id: u64,
+    // The pointer moved during the current file drag, so its position is live
+    dnd_pointer_live: bool,
+    // File Manager side panel
+    file_manager: FileManager,
+    // Configuration as loaded from (or last saved to) config.toml; the settings editor diffs against it
+    config: AppConfig,
+    settings: SettingsEditor,
+    // Command bookmarks picker
+    bookmark_picker: BookmarkPicker,
+    // Script catalog window
+    script_catalog: ScriptCatalog,
+    // Per-tab activity log and dashboard
+    analytics: Analytics,
+    // Help viewer, and how the startup config was obtained (shown there)
+    help: HelpViewer,
+    config_source: ConfigSource,
+    // Developer tools: escape-sequence inspector for the focused pane
+    inspector: Inspector,
+    // Command palette (every action, pane, theme and bookmark)
+    command_palette: CommandPalette,
+    // Recording picker and open players, with window ids from `next_pane_id`
+    recordings: RecordingPicker,
+    players: Vec<(u64, Player)>,
+    // Control socket (`[control] enabled`)
+    control: Option<ControlServer>,
+    // Toasts and desktop notifications for `[tabs.notify]` alerts
+    notifications: Notifications,
     // Debug: overlay pane and handle rects
     debug_overlay: bool,
     // Debug: window resize tracing
@@ -477,30 +1183,36 @@ impl AudioToolkitApp {
     /// # Arguments
     /// 
     /// * `config` - The application configuration containing tab settings
+    /// * `config_source` - How `config` was obtained, for the help viewer
     /// 
     /// # Returns
     /// 
     /// A new `AudioToolkitApp` instance ready for use with eframe
-    pub fn new(config: AppConfig) -> Self {
-        let AppConfig { app, tabs } = config;
+    pub fn new(config: AppConfig, config_source: ConfigSource) -> Self {
+        let loaded = config.clone();
+        let analytics = Analytics::new(&loaded.analytics);
+        let AppConfig { app, tabs, keybindings, buttons, .. } = config;
+        let (keymap, problems) = Keymap::from_config(&keybindings);
+        for problem in problems {
+            eprintln!("[KEYS] {}", problem);
+        }
         let mut tabs: Vec<TerminalTab> = tabs.into_iter().map(TerminalTab::new).collect();
 
         // Ensure we have exactly four terminals for the fixed layout:
         // Fill missing with default bash tabs; ignore extras beyond four.
-        while tabs.len() < 4 {
-            let idx = tabs.len() + 1;
-            let cfg = TabConfig {
-                title: format!("Terminal {}", idx),
-                command: "bash".to_string(),
-                auto_restart_on_success: false,
-                success_patterns: vec![],
-                dnd: DndSettings::default(),
-            };
-            tabs.push(TerminalTab::new(cfg));
+        while tabs.len() < MAX_TABS {
+            tabs.push(TerminalTab::new(default_tab(tabs.len())));
+        }
+        if tabs.len() > MAX_TABS {
+            tabs.truncate(MAX_TABS);
         }
-        if tabs.len() > 4 {
-            tabs.truncate(4);
+        let scrollback = loaded.control.enabled.then_some(loaded.control.scrollback_lines);
+        for tab in &mut tabs {
+            tab.configure_log(&loaded.logging);
+            tab.configure_history(scrollback);
         }
+        let control = Self::start_control(&loaded.control);
+        let notifications = Notifications::new(&loaded.notifications, Self::shell_quote_str);
 
         // Initialize interactive split fractions from config defaults
         let right_top_frac = app.right_top_fraction.clamp(0.2, 0.8);
@@ -525,201 +1237,1422 @@ impl AudioToolkitApp {
             })
             .unwrap_or(false);
 
-        Self {
-            tabs,
-            focused_terminal: 0, // Start with left terminal focused
-            app_settings: app,
-            right_top_frac,
-            right_hsplit_frac,
-            left_buttons_frac,
-            debug_overlay,
-            window_trace,
-            last_win_w: 0.0,
-            last_win_h: 0.0,
-            last_ppp: 0.0,
+        let (active_theme, themes) = Self::load_themes(&app);
+
+        Self {
+            tabs,
+            focused_terminal: 0, // Start with left terminal focused
+            app_settings: app,
+            right_top_frac,
+            right_hsplit_frac,
+            left_buttons_frac,
+            global_zoom: 1.0,
+            theme: active_theme,
+            themes,
+            pending_paste: None,
+            keymap,
+            focus_search: false,
+            buttons,
+            pending_button: None,
+            pending_control: None,
+            pending_profile: None,
+            ephemeral_ids: Vec::new(),
+            next_pane_id: 0,
+            dnd_pointer_live: false,
+            file_manager: FileManager::new(),
+            config: loaded,
+            settings: SettingsEditor::new(),
+            bookmark_picker: BookmarkPicker::new(),
+            script_catalog: ScriptCatalog::new(),
+            analytics,
+            help: HelpViewer::new(),
+            inspector: Inspector::new(),
+            command_palette: CommandPalette::new(),
+            recordings: RecordingPicker::new(),
+            players: Vec::new(),
+            control,
+            notifications,
+            config_source,
+            debug_overlay,
+            window_trace,
+            last_win_w: 0.0,
+            last_win_h: 0.0,
+            last_ppp: 0.0,
+        }
+    }
+
+    /// Built-in themes followed by user themes, and the one named by `[app] theme`
+    ///
+    /// Unknown names fall back to Frappé.
+    fn load_themes(app: &AppSettings) -> (Theme, Vec<Theme>) {
+        let mut themes = Theme::builtins();
+        themes.extend(theme::load_user_themes(&app.themes_path()));
+        let active_theme = themes
+            .iter()
+            .rev() // user themes shadow built-ins with the same id
+            .find(|t| t.matches(&app.theme))
+            .cloned()
+            .unwrap_or_else(|| {
+                eprintln!("[THEME] Unknown theme {:?}, using Frappé", app.theme);
+                Theme::frappe()
+            });
+        (active_theme, themes)
+    }
+
+    /// Applies a new configuration to the running app
+    ///
+    /// Tabs whose command changed are restarted; everything else (theme, font,
+    /// window size, splits, key bindings, buttons, titles) updates in place.
+    fn apply_config(&mut self, ctx: &egui::Context, config: AppConfig) {
+        let AppConfig { app, tabs, keybindings, buttons, .. } = config.clone();
+        let (keymap, problems) = Keymap::from_config(&keybindings);
+        for problem in problems {
+            eprintln!("[KEYS] {}", problem);
+        }
+        self.keymap = keymap;
+        self.buttons = buttons;
+        self.pending_button = None;
+        self.analytics.set_settings(&config.analytics);
+        self.notifications.set_settings(&config.notifications);
+
+        let old = &self.app_settings;
+        if app.theme != old.theme || app.themes_path() != old.themes_path() {
+            (self.theme, self.themes) = Self::load_themes(&app);
+        }
+        if app.font != old.font {
+            ctx.set_fonts(fonts::build_font_definitions(&app.font).unwrap_or_default());
+        }
+        if app.window_width != old.window_width || app.window_height != old.window_height {
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(app.window_width, app.window_height)));
+        }
+        if app.name != old.name {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(app.name.clone()));
+        }
+        if app.right_top_fraction != old.right_top_fraction {
+            self.right_top_frac = app.right_top_fraction.clamp(0.2, 0.8);
+        }
+        if app.right_top_hsplit_fraction != old.right_top_hsplit_fraction {
+            self.right_hsplit_frac = app.right_top_hsplit_fraction.clamp(0.2, 0.8);
+        }
+        self.app_settings = app;
+
+        // Panes without a [[tabs]] entry keep running as default shells
+        for (tab, tab_config) in self.tabs.iter_mut().zip(tabs) {
+            if tab.config != tab_config {
+                tab.reconfigure(tab_config);
+            }
+        }
+        for tab in self.tabs.iter_mut().take(MAX_TABS) {
+            tab.configure_log(&config.logging);
+        }
+        if config.control != self.config.control {
+            // Close the old socket first so the same path can be reused
+            self.control = None;
+            self.control = Self::start_control(&config.control);
+            let scrollback = config.control.enabled.then_some(config.control.scrollback_lines);
+            for tab in &mut self.tabs {
+                tab.configure_history(scrollback);
+            }
+        }
+        self.config = config;
+    }
+
+    /// Opens the control socket when `[control] enabled` is set
+    fn start_control(settings: &ControlSettings) -> Option<ControlServer> {
+        if !settings.enabled {
+            return None;
+        }
+        match ControlServer::start(settings.socket_path()) {
+            Ok(server) => {
+                println!("[CONTROL] Listening on {}", server.path().display());
+                Some(server)
+            }
+            Err(e) => {
+                eprintln!("[CONTROL] {}", e);
+                None
+            }
+        }
+    }
+
+    /// Answers the requests that arrived on the control socket
+    fn handle_control(&mut self) {
+        let Some(mut server) = self.control.take() else {
+            return;
+        };
+        for request in server.poll() {
+            let result = request.request.and_then(|r| self.run_control(&mut server, request.client, r));
+            server.reply(request.client, request.id, result);
+        }
+        self.control = Some(server);
+    }
+
+    /// Index of the tab a control request names
+    fn control_tab(&self, tab: &control::TabRef) -> Result<usize, String> {
+        tab.resolve(self.tabs.iter().map(TerminalTab::title))
+    }
+
+    /// Runs one control socket request; `Ok` holds the response fields
+    fn run_control(
+        &mut self,
+        server: &mut ControlServer,
+        client: u64,
+        request: Request,
+    ) -> Result<serde_json::Value, String> {
+        use serde_json::json;
+
+        match request {
+            Request::List => {
+                let tabs: Vec<_> = self
+                    .tabs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, tab)| {
+                        json!({
+                            "pane": i + 1,
+                            "title": tab.title(),
+                            "command": tab.config.command,
+                            "running": !tab.exited,
+                            "focused": i == self.focused_terminal,
+                        })
+                    })
+                    .collect();
+                Ok(json!({ "tabs": tabs }))
+            }
+            Request::Send { tab, text, enter } => {
+                let i = self.control_tab(&tab)?;
+                let tab = &mut self.tabs[i];
+                tab.send(text.as_bytes());
+                if enter {
+                    tab.send(b"\r");
+                }
+                Ok(json!({}))
+            }
+            Request::Keys { tab, keys } => {
+                let bytes = control::encode_keys(&keys)?;
+                let i = self.control_tab(&tab)?;
+                self.tabs[i].send(&bytes);
+                Ok(json!({}))
+            }
+            Request::Restart { tab } => {
+                let i = self.control_tab(&tab)?;
+                self.tabs[i].request_restart(RestartTrigger::Control);
+                Ok(json!({}))
+            }
+            Request::Focus { tab } => {
+                self.focused_terminal = self.control_tab(&tab)?;
+                Ok(json!({}))
+            }
+            Request::Screen { tab } => {
+                let tab = &self.tabs[self.control_tab(&tab)?];
+                Ok(json!({ "text": tab.terminal_emulator().screen_text() }))
+            }
+            Request::Scrollback { tab, lines } => {
+                let tab = &self.tabs[self.control_tab(&tab)?];
+                let history = tab.history.as_ref().ok_or("scrollback is not kept for this tab")?;
+                Ok(json!({ "text": history.tail(lines.unwrap_or(history.max_lines())) }))
+            }
+            Request::Subscribe { tab, events } => {
+                let title = match tab {
+                    Some(tab) => Some(self.tabs[self.control_tab(&tab)?].title().to_string()),
+                    None => None,
+                };
+                server.subscribe(client, Subscription::new(title, events)?);
+                Ok(json!({}))
+            }
+        }
+    }
+
+    /// Analytics dashboard window
+    fn render_analytics(&mut self, ctx: &egui::Context) {
+        if !self.analytics.open {
+            return;
+        }
+        let mut open = true;
+        let palette = self.theme.palette;
+        let live: Vec<LiveTab> = self.tabs.iter().map(TerminalTab::live).collect();
+        let analytics = &mut self.analytics;
+        egui::Window::new("📊 Analytics")
+            .open(&mut open)
+            .default_size(egui::vec2(720.0, 520.0))
+            .resizable(true)
+            .collapsible(false)
+            .show(ctx, |ui| analytics.show(ui, &palette, &live));
+        if !open {
+            self.analytics.open = false;
+        }
+    }
+
+    /// Picker for recordings to play
+    fn render_recordings(&mut self, ctx: &egui::Context) {
+        if !self.recordings.open {
+            return;
+        }
+        let mut open = true;
+        let palette = self.theme.palette;
+        let recordings = &mut self.recordings;
+        let chosen = egui::Window::new("🎞 Recordings")
+            .open(&mut open)
+            .default_width(480.0)
+            .collapsible(false)
+            .show(ctx, |ui| recordings.show(ui, &palette))
+            .and_then(|r| r.inner)
+            .flatten();
+        if !open || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.recordings.open = false;
+        }
+        if let Some(path) = chosen {
+            match cast::load(&path) {
+                Ok(recording) => {
+                    self.players.push((self.next_pane_id, Player::new(path, recording)));
+                    self.next_pane_id += 1;
+                    self.recordings.open = false;
+                }
+                Err(e) => {
+                    eprintln!("[CAST] {}", e);
+                    self.recordings.set_error(e);
+                }
+            }
+        }
+    }
+
+    /// Read-only windows playing recordings
+    fn render_players(&mut self, ctx: &egui::Context) {
+        if self.players.is_empty() {
+            return;
+        }
+        let metrics = self.cell_metrics(ctx, usize::MAX);
+        let theme = self.theme.clone();
+        let p = theme.palette;
+        let dt = ctx.input(|i| i.unstable_dt).min(0.5) as f64;
+        let mut closed = Vec::new();
+        for (id, player) in self.players.iter_mut() {
+            player.advance(dt);
+            let mut open = true;
+            egui::Window::new(format!("🎞 {}", player.title))
+                .id(egui::Id::new(("player", *id)))
+                .open(&mut open)
+                .default_size(egui::vec2(640.0, 400.0))
+                .resizable(true)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        let play = if player.paused || player.finished() { "▶" } else { "⏸" };
+                        if ui.button(play).on_hover_text("Play or pause").clicked() {
+                            if player.finished() {
+                                player.rewind();
+                                player.paused = false;
+                            } else {
+                                player.paused = !player.paused;
+                            }
+                        }
+                        if ui.button("⏮").on_hover_text("Rewind").clicked() {
+                            player.rewind();
+                        }
+                        egui::ComboBox::from_id_source(("player_speed", *id))
+                            .width(60.0)
+                            .selected_text(format!("{}×", player.speed))
+                            .show_ui(ui, |ui| {
+                                for speed in cast::SPEEDS {
+                                    ui.selectable_value(&mut player.speed, speed, format!("{}×", speed));
+                                }
+                            });
+                        let duration = player.duration();
+                        let mut position = player.position();
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{} / {}",
+                                cast::format_position(position),
+                                cast::format_position(duration)
+                            ))
+                            .color(p.subtext0)
+                            .monospace(),
+                        );
+                        let slider = egui::Slider::new(&mut position, 0.0..=duration.max(0.001)).show_value(false);
+                        if ui.add_sized([ui.available_width(), 18.0], slider).changed() {
+                            player.seek(position);
+                        }
+                    });
+                    ui.label(egui::RichText::new(player.path.display().to_string()).small().color(p.overlay0));
+                    let (rows, cols) = player.emulator.size();
+                    let size = egui::vec2(cols as f32 * metrics.cell_w, rows as f32 * metrics.cell_h);
+                    egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+                        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 0.0, p.base);
+                        Self::render_terminal_buffer(ui, rect, &player.emulator.buffer, &metrics, &theme, &[]);
+                    });
+                });
+            if !open {
+                closed.push(*id);
+            }
+        }
+        self.players.retain(|(id, _)| !closed.contains(id));
+        if self.players.iter().any(|(_, player)| !player.paused && !player.finished()) {
+            ctx.request_repaint();
+        }
+    }
+
+    /// Notification toasts; clicking one focuses its pane
+    fn render_toasts(&mut self, ctx: &egui::Context) {
+        self.notifications.expire(std::time::Instant::now());
+        let palette = self.theme.palette;
+        if let Some(pane) = self.notifications.show(ctx, &palette) {
+            if pane < self.tabs.len() {
+                self.focused_terminal = pane;
+            }
+        }
+    }
+
+    /// Help viewer window
+    fn render_help(&mut self, ctx: &egui::Context) {
+        if !self.help.open {
+            return;
+        }
+        let mut open = true;
+        let palette = self.theme.palette;
+        let live = HelpContext { config: &self.config, source: &self.config_source, keymap: &self.keymap };
+        let help = &mut self.help;
+        egui::Window::new("💡 Help")
+            .open(&mut open)
+            .default_size(egui::vec2(820.0, 600.0))
+            .resizable(true)
+            .collapsible(false)
+            .show(ctx, |ui| help.show(ui, &palette, &live));
+        if !open || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.help.open = false;
+        }
+    }
+
+    /// Developer tools window; capture runs only for the focused pane while it is open
+    fn render_tools(&mut self, ctx: &egui::Context) {
+        let focused = self.focused_terminal;
+        for (i, tab) in self.tabs.iter_mut().enumerate() {
+            tab.set_inspect(self.inspector.open && i == focused);
+        }
+        if !self.inspector.open {
+            return;
+        }
+        let Some(tab) = self.tabs.get_mut(focused) else { return };
+        let (raw, events) = tab.take_inspection();
+        self.inspector.collect(focused, raw, events);
+
+        let mut open = true;
+        let theme = &self.theme;
+        let inspector = &mut self.inspector;
+        egui::Window::new("🔧 Tools")
+            .open(&mut open)
+            .default_size(egui::vec2(900.0, 560.0))
+            .resizable(true)
+            .collapsible(false)
+            .show(ctx, |ui| inspector.show(ui, theme, tab.title(), tab.terminal_emulator(), tab.hover_cell()));
+        if !open {
+            self.inspector.open = false;
+        }
+    }
+
+    /// Bookmarks picker window; the chosen command is typed into the focused pane
+    fn render_bookmarks(&mut self, ctx: &egui::Context) {
+        if !self.bookmark_picker.open {
+            return;
+        }
+        let mut open = true;
+        let palette = self.theme.palette;
+        let picker = &mut self.bookmark_picker;
+        let choice = egui::Window::new("🔖 Bookmarks")
+            .open(&mut open)
+            .default_size(egui::vec2(520.0, 420.0))
+            .resizable(true)
+            .collapsible(false)
+            .show(ctx, |ui| picker.show(ui, &palette, Self::shell_quote_str))
+            .and_then(|r| r.inner.flatten());
+        if !open || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.bookmark_picker.open = false;
+        }
+        if let Some(choice) = choice {
+            let mut text = bookmarks::expand(&choice.command, &choice.values, Self::shell_quote_str);
+            if choice.run {
+                text.push('\r');
+            }
+            if let Some(tab) = self.tabs.get_mut(self.focused_terminal) {
+                tab.send(text.as_bytes());
+            }
+        }
+    }
+
+    /// Everything the command palette offers, with current key bindings
+    fn palette_entries(&self) -> Vec<PaletteEntry> {
+        let entry = |title: String, action: Action| {
+            let keys = self.keymap.keys_for(&action);
+            PaletteEntry::new(title, keys, Command::Action(action))
+        };
+        let mut entries: Vec<PaletteEntry> = Action::palette()
+            .map(|(action, title)| entry(title.to_string(), action))
+            .collect();
+        for (i, tab) in self.tabs.iter().enumerate() {
+            entries.push(entry(format!("Focus pane {}: {}", i + 1, tab.title()), Action::FocusPane(i)));
+            entries.push(entry(format!("Restart pane {}: {}", i + 1, tab.title()), Action::RestartPane(i)));
+        }
+        for theme in &self.themes {
+            entries.push(PaletteEntry::new(format!("Theme: {}", theme.name), "", Command::Theme(theme.id.clone())));
+        }
+        let profiles = config::list_profiles(&self.config);
+        if !profiles.is_empty() {
+            entries.push(PaletteEntry::new("Profile: none (config.toml only)", "", Command::Profile(None)));
+        }
+        for name in profiles {
+            entries.push(PaletteEntry::new(format!("Profile: {}", name), "", Command::Profile(Some(name))));
+        }
+        // Errors are reported by the bookmarks picker itself
+        let bookmarks = bookmarks::load_bookmarks(&self.app_settings.bookmarks_path()).unwrap_or_default();
+        for bookmark in bookmarks {
+            entries.push(PaletteEntry::new(
+                format!("Run bookmark: {}", bookmark.name),
+                "",
+                Command::Bookmark(bookmark.name),
+            ));
+        }
+        entries
+    }
+
+    /// Command palette, anchored at the top of the window
+    fn render_palette(&mut self, ctx: &egui::Context) {
+        if !self.command_palette.open {
+            return;
+        }
+        let palette = self.theme.palette;
+        let command_palette = &mut self.command_palette;
+        let command = egui::Window::new("Command Palette")
+            .title_bar(false)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 60.0))
+            .fixed_size(egui::vec2(560.0, 0.0))
+            .collapsible(false)
+            .show(ctx, |ui| command_palette.show(ui, &palette))
+            .and_then(|r| r.inner.flatten());
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.command_palette.open = false;
+        }
+        match command {
+            Some(Command::Action(action)) => self.run_action(ctx, action, None),
+            Some(Command::Theme(id)) => {
+                if let Some(theme) = self.themes.iter().find(|t| t.id == id).cloned() {
+                    self.set_theme(theme);
+                }
+            }
+            Some(Command::Profile(name)) => self.request_profile(ctx, name),
+            Some(Command::Bookmark(name)) => {
+                let file = self.file_manager.selection().into_iter().next();
+                self.bookmark_picker.open_bookmark(self.app_settings.bookmarks_path(), None, file, &name);
+                self.bookmark_picker.lookup_dir(self.focused_dir_lookup());
+            }
+            None => {}
+        }
+    }
+
+    /// Script catalog window; the launched script is typed into the chosen pane
+    fn render_scripts(&mut self, ctx: &egui::Context) {
+        if !self.script_catalog.open {
+            return;
+        }
+        let mut open = true;
+        let palette = self.theme.palette;
+        let panes: Vec<String> = self.tabs.iter().map(|t| t.title().to_string()).collect();
+        let catalog = &mut self.script_catalog;
+        let launch = egui::Window::new("📜 Scripts")
+            .open(&mut open)
+            .default_size(egui::vec2(560.0, 480.0))
+            .resizable(true)
+            .collapsible(false)
+            .show(ctx, |ui| catalog.show(ui, &palette, &panes, Self::shell_quote_str))
+            .and_then(|r| r.inner.flatten());
+        if !open || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.script_catalog.open = false;
+        }
+        if let Some(launch) = launch {
+            let index = match launch.target {
+                LaunchTarget::Pane(index) => index,
+                LaunchTarget::NewPane => self.open_ephemeral_pane(format!("📜 {}", launch.name)),
+            };
+            if let Some(tab) = self.tabs.get_mut(index) {
+                tab.send(format!("{}\r", launch.command).as_bytes());
+                self.focused_terminal = index;
+            }
+        }
+    }
+
+    /// Settings editor window; saving writes config.toml and applies the changes
+    fn render_settings(&mut self, ctx: &egui::Context) {
+        if !self.settings.open {
+            return;
+        }
+        let mut open = true;
+        let palette = self.theme.palette;
+        let themes = &self.themes;
+        let settings = &mut self.settings;
+        let saved = egui::Window::new("⚙️ Settings")
+            .open(&mut open)
+            .default_size(egui::vec2(560.0, 620.0))
+            .resizable(true)
+            .collapsible(false)
+            .show(ctx, |ui| settings.show(ui, themes, &palette))
+            .and_then(|r| r.inner.flatten());
+        if !open {
+            self.settings.open = false;
+        }
+        if let Some(config) = saved {
+            match config::save_config(&self.config, &config) {
+                Ok(path) => {
+                    self.apply_config(ctx, config);
+                    self.settings.set_status(format!("Saved to {}", path.display()), false);
+                }
+                Err(e) => {
+                    eprintln!("[CONFIG] {}", e);
+                    self.settings.set_status(e, true);
+                }
+            }
+        }
+    }
+
+    /// Cell metrics for the pane at `tab_index`, combining the configured font
+    /// size with the global and per-pane zoom factors.
+    fn cell_metrics(&self, ctx: &egui::Context, tab_index: usize) -> CellMetrics {
+        let font = &self.app_settings.font;
+        let pane_zoom = self.tabs.get(tab_index).map(|t| t.zoom).unwrap_or(1.0);
+        let size = (font.size * self.global_zoom * pane_zoom).max(4.0);
+        CellMetrics::measure(ctx, size, font.line_height)
+    }
+
+    /// Steps the zoom in (`+1`) or out (`-1`) for the focused pane or all panes,
+    /// depending on `[app.font] zoom_scope`. Panes pick up the new size (and resize
+    /// their PTY) on the next frame.
+    fn zoom(&mut self, direction: i32) {
+        match self.app_settings.font.zoom_scope {
+            ZoomScope::Global => self.global_zoom = fonts::step_zoom(self.global_zoom, direction),
+            ZoomScope::Pane => {
+                if let Some(tab) = self.tabs.get_mut(self.focused_terminal) {
+                    tab.zoom = fonts::step_zoom(tab.zoom, direction);
+                }
+            }
+        }
+    }
+
+    /// Resets the zoom for the focused pane or all panes, depending on the zoom scope.
+    fn reset_zoom(&mut self) {
+        match self.app_settings.font.zoom_scope {
+            ZoomScope::Global => self.global_zoom = 1.0,
+            ZoomScope::Pane => {
+                if let Some(tab) = self.tabs.get_mut(self.focused_terminal) {
+                    tab.zoom = 1.0;
+                }
+            }
+        }
+    }
+
+    /// Theme picker shown next to the Actions label; switching applies on the next frame
+    fn render_theme_menu(&mut self, ui: &mut egui::Ui) {
+        let mut selected: Option<Theme> = None;
+        ui.menu_button(egui::RichText::new("🎨 Theme").size(11.0), |ui| {
+            for theme in &self.themes {
+                if ui.radio(theme.id == self.theme.id, &theme.name).clicked() {
+                    selected = Some(theme.clone());
+                    ui.close_menu();
+                }
+            }
+        });
+        if let Some(theme) = selected {
+            self.set_theme(theme);
+        }
+    }
+
+    /// Switches the active theme for this session
+    fn set_theme(&mut self, theme: Theme) {
+        self.app_settings.theme = theme.id.clone();
+        self.theme = theme;
+    }
+
+    /// Profile picker shown next to the theme menu
+    fn render_profile_menu(&mut self, ui: &mut egui::Ui) {
+        let active = config::active_profile();
+        let label = match &active {
+            Some(name) => format!("🗂 {}", name),
+            None => "🗂 Profile".to_string(),
+        };
+        let mut selected: Option<Option<String>> = None;
+        ui.menu_button(egui::RichText::new(label).size(11.0), |ui| {
+            if ui.radio(active.is_none(), "None (config.toml only)").clicked() {
+                selected = Some(None);
+                ui.close_menu();
+            }
+            for name in config::list_profiles(&self.config) {
+                if ui.radio(active.as_deref() == Some(name.as_str()), &name).clicked() {
+                    selected = Some(Some(name));
+                    ui.close_menu();
+                }
+            }
+        });
+        if let Some(name) = selected {
+            self.request_profile(ui.ctx(), name);
+        }
+    }
+
+    /// Tabs for the fixed panes under `config`; panes it leaves out get plain shells
+    fn profile_tabs(config: &AppConfig) -> Vec<TabConfig> {
+        let mut tabs: Vec<TabConfig> = config.tabs.iter().take(MAX_TABS).cloned().collect();
+        while tabs.len() < MAX_TABS {
+            tabs.push(default_tab(tabs.len()));
+        }
+        tabs
+    }
+
+    /// Switches to profile `name` (`None` for `config.toml` alone)
+    ///
+    /// Asks first when a pane getting a new command is running a job, and reports
+    /// a profile that fails to load instead of switching.
+    fn request_profile(&mut self, ctx: &egui::Context, name: Option<String>) {
+        if name == config::active_profile() {
+            return;
+        }
+        let config = config::load_profile(name.as_deref());
+        let jobs = match &config {
+            Ok(config) => self
+                .tabs
+                .iter()
+                .zip(Self::profile_tabs(config))
+                .enumerate()
+                .filter(|(_, (tab, new))| tab.config.command != new.command)
+                .filter_map(|(i, (tab, _))| tab.foreground_process().map(|job| format!("Pane {}: {}", i + 1, job)))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        match config {
+            Ok(config) if jobs.is_empty() => self.switch_profile(ctx, name, config),
+            config => self.pending_profile = Some(ProfileSwitch { name, config, jobs }),
+        }
+    }
+
+    /// Makes `name` the active profile and applies `config`, restarting panes whose command changed
+    fn switch_profile(&mut self, ctx: &egui::Context, name: Option<String>, config: AppConfig) {
+        let tabs = Self::profile_tabs(&config);
+        config::set_active_profile(name);
+        self.apply_config(ctx, config);
+        // Toasts name panes of the old profile
+        self.notifications.clear();
+        for (tab, tab_config) in self.tabs.iter_mut().zip(tabs) {
+            if tab.config != tab_config {
+                tab.reconfigure(tab_config);
+                tab.configure_log(&self.config.logging);
+            }
+        }
+        // Each profile starts from its own layout rather than the dragged splits
+        self.right_top_frac = self.app_settings.right_top_fraction.clamp(0.2, 0.8);
+        self.right_hsplit_frac = self.app_settings.right_top_hsplit_fraction.clamp(0.2, 0.8);
+    }
+
+    /// Confirmation (or error) for a profile switch
+    fn render_profile_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(switch) = &self.pending_profile else {
+            return;
+        };
+        let title = match &switch.name {
+            Some(name) => format!("Switch to profile \u{201c}{}\u{201d}?", name),
+            None => "Leave profile?".to_string(),
+        };
+        let mut choice = None;
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                match &switch.config {
+                    Ok(_) => {
+                        ui.label("These jobs are still running and will be stopped:");
+                        for job in &switch.jobs {
+                            ui.label(format!("• {}", job));
+                        }
+                    }
+                    Err(e) => {
+                        ui.colored_label(self.theme.palette.red, e);
+                    }
+                }
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if switch.config.is_ok() && ui.button("Switch").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(if switch.config.is_ok() { "Cancel" } else { "Close" }).clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        if choice.is_none() && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            choice = Some(false);
+        }
+        if let Some(confirmed) = choice {
+            if let Some(ProfileSwitch { name, config: Ok(config), .. }) = self.pending_profile.take() {
+                if confirmed {
+                    self.switch_profile(ctx, name, config);
+                }
+            }
+        }
+    }
+
+    /// Re-reads `config.toml` and applies it; a file that fails to parse is reported and ignored
+    fn reload_config(&mut self, ctx: &egui::Context) {
+        let (config, source) = config::load_config();
+        if let ConfigSource::Defaults(reason) = &source {
+            eprintln!("[CONFIG] Reload skipped, keeping the current settings: {}", reason);
+            return;
+        }
+        self.apply_config(ctx, config);
+        // Pick up edits to theme files as well
+        (self.theme, self.themes) = Self::load_themes(&self.app_settings);
+        self.config_source = source;
+    }
+
+    /// Single keyboard pipeline for the focused pane
+    ///
+    /// Chords bound in the keymap run their action and are removed from this frame's
+    /// input, so neither the PTY nor egui sees them. Everything else is encoded and
+    /// written to the focused pane, unless a text field (e.g. the find bar) has
+    /// keyboard focus. Nothing is processed while a paste confirmation is open.
+    fn process_input(&mut self, ctx: &egui::Context) {
+        if self.pending_paste.is_some() {
+            return;
+        }
+        let (events, modifiers) = ctx.input(|i| (i.events.clone(), i.modifiers));
+        let to_terminal = !ctx.wants_keyboard_input();
+        let mut consumed: Vec<egui::Event> = Vec::new();
+        // Steps run in event order; plain terminal input is an `Action::Send`
+        let mut steps: Vec<(Action, Option<String>)> = Vec::new();
+
+        for ev in &events {
+            match ev {
+                egui::Event::Key { key, pressed: true, modifiers, .. } => {
+                    if let Some(action) = self.keymap.lookup(*key, *modifiers) {
+                        steps.push((action.clone(), None));
+                        consumed.push(ev.clone());
+                    } else if to_terminal {
+                        if let Some(bytes) = input::encode_key(*key, *modifiers) {
+                            steps.push((Action::Send(String::from_utf8_lossy(&bytes).to_string()), None));
+                        }
+                    }
+                }
+                // egui-winit reports Ctrl/Cmd + C, X, V as clipboard events with no key event
+                egui::Event::Copy | egui::Event::Cut | egui::Event::Paste(_) if to_terminal => {
+                    let (key, pasted) = match ev {
+                        egui::Event::Copy => (egui::Key::C, None),
+                        egui::Event::Cut => (egui::Key::X, None),
+                        egui::Event::Paste(text) => (egui::Key::V, Some(text.clone())),
+                        _ => continue,
+                    };
+                    if let Some(action) = self.keymap.lookup(key, modifiers) {
+                        steps.push((action.clone(), pasted));
+                        consumed.push(ev.clone());
+                    } else if pasted.is_some() {
+                        steps.push((Action::Paste, pasted));
+                    } else if modifiers.ctrl {
+                        // Plain Ctrl+C / Ctrl+X are terminal control codes (ETX / CAN)
+                        let code = if key == egui::Key::C { "\x03" } else { "\x18" };
+                        steps.push((Action::Send(code.to_string()), None));
+                    } else if key == egui::Key::C {
+                        steps.push((Action::Copy, None));
+                    }
+                }
+                egui::Event::Text(text) if to_terminal => {
+                    steps.push((Action::Send(text.clone()), None));
+                }
+                _ => {}
+            }
+        }
+
+        if !consumed.is_empty() {
+            ctx.input_mut(|i| i.events.retain(|e| !consumed.contains(e)));
+        }
+        for (action, pasted) in steps {
+            self.run_action(ctx, action, pasted);
+        }
+    }
+
+    /// Runs a bound action against the focused pane (or the whole app)
+    ///
+    /// `pasted` carries the clipboard text when the action came from a paste event;
+    /// otherwise `paste` reads the system clipboard.
+    fn run_action(&mut self, ctx: &egui::Context, action: Action, pasted: Option<String>) {
+        let count = self.tabs.len().max(1);
+        let focused = self.focused_terminal;
+        match action {
+            Action::FocusPane(n) if n < self.tabs.len() => self.focused_terminal = n,
+            Action::FocusPane(_) => {}
+            Action::FocusNext => self.focused_terminal = (focused + 1) % count,
+            Action::FocusPrev => self.focused_terminal = (focused + count - 1) % count,
+            Action::RestartAll => {
+                for tab in &mut self.tabs {
+                    tab.request_restart(RestartTrigger::RestartAll);
+                }
+            }
+            Action::ZoomIn => self.zoom(1),
+            Action::ZoomOut => self.zoom(-1),
+            Action::ZoomReset => self.reset_zoom(),
+            Action::Settings => self.settings.open_with(&self.config),
+            Action::Bookmarks => {
+                let file = self.file_manager.selection().into_iter().next();
+                self.bookmark_picker.open_with(self.app_settings.bookmarks_path(), None, file);
+                self.bookmark_picker.lookup_dir(self.focused_dir_lookup());
+            }
+            Action::Analytics => self.analytics.open = !self.analytics.open,
+            Action::Help => self.help.show_window(),
+            Action::Tools => self.inspector.open = !self.inspector.open,
+            Action::CommandPalette if self.command_palette.open => self.command_palette.open = false,
+            Action::CommandPalette => {
+                let entries = self.palette_entries();
+                self.command_palette.open_with(entries);
+            }
+            Action::ReloadConfig => self.reload_config(ctx),
+            Action::DebugOverlay => self.debug_overlay = !self.debug_overlay,
+            Action::WindowTrace => self.window_trace = !self.window_trace,
+            Action::Record => {
+                if let Some(tab) = self.tabs.get_mut(focused) {
+                    tab.toggle_recording();
+                }
+            }
+            Action::Recordings => self.recordings.open_in(self.config.logging.log_dir()),
+            Action::RestartPane(n) => self.request_control(n, PaneControl::Restart),
+            Action::RestartTab => self.request_control(focused, PaneControl::Restart),
+            Action::Interrupt => self.request_control(focused, PaneControl::Interrupt),
+            Action::Terminate => self.request_control(focused, PaneControl::Terminate),
+            Action::ForceKill => self.request_control(focused, PaneControl::Kill),
+            Action::Reset => self.request_control(focused, PaneControl::Reset),
+            Action::Scripts => {
+                let file = self.file_manager.selection().into_iter().next();
+                let recent = config::config_dir().join("recent_scripts.toml");
+                self.script_catalog.open_with(&self.config.scripts, recent, focused, None, file);
+                self.script_catalog.lookup_dir(self.focused_dir_lookup());
+            }
+            Action::FileManager => {
+                self.file_manager.open = !self.file_manager.open;
+                if self.file_manager.open {
+                    self.file_manager.navigate_with(self.focused_dir());
+                }
+            }
+            Action::Paste => {
+                if let Some(text) = pasted.or_else(read_clipboard) {
+                    self.paste_into(focused, text);
+                }
+            }
+            Action::Search => {
+                if let Some(tab) = self.tabs.get_mut(focused) {
+                    tab.search.get_or_insert_with(String::new);
+                    self.focus_search = true;
+                }
+            }
+            action => {
+                let Some(tab) = self.tabs.get_mut(focused) else {
+                    return;
+                };
+                match action {
+                    Action::Clear => tab.clear(),
+                    Action::Copy => {
+                        let text = tab.terminal_emulator.screen_text();
+                        ctx.output_mut(|o| o.copied_text = text);
+                    }
+                    Action::Send(text) => tab.send(text.as_bytes()),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Looks up the working directory of the focused pane (see [`TerminalTab::working_dir_lookup`])
+    fn focused_dir_lookup(&self) -> impl FnOnce() -> Option<std::path::PathBuf> + Send + 'static {
+        let lookup = self.tabs.get(self.focused_terminal).map(TerminalTab::working_dir_lookup);
+        move || lookup.and_then(|lookup| lookup())
+    }
+
+    /// Looks up the working directory of the focused pane, falling back to the app's own
+    fn focused_dir(&self) -> impl FnOnce() -> std::path::PathBuf + Send + 'static {
+        let lookup = self.focused_dir_lookup();
+        move || lookup().or_else(|| std::env::current_dir().ok()).unwrap_or_default()
+    }
+
+    /// File Manager side panel; selected files are typed into the focused pane
+    fn render_file_manager(&mut self, ctx: &egui::Context) {
+        if !self.file_manager.open {
+            return;
+        }
+        let palette = self.theme.palette;
+        let event = egui::SidePanel::right("file_manager")
+            .resizable(true)
+            .default_width(420.0)
+            .width_range(260.0..=900.0)
+            .show(ctx, |ui| self.file_manager.show(ui, &palette))
+            .inner;
+        match event {
+            Some(FileManagerEvent::Send(paths)) => {
+                // Same form as a multi-file drop: quoted, space-joined, trailing space, no Enter
+                let mut text = paths.iter().map(|p| Self::shell_quote_path(p)).collect::<Vec<_>>().join(" ");
+                text.push(' ');
+                if let Some(tab) = self.tabs.get_mut(self.focused_terminal) {
+                    tab.send(text.as_bytes());
+                }
+            }
+            Some(FileManagerEvent::FollowPane) => {
+                let find = self.focused_dir();
+                self.file_manager.navigate_with(find);
+            }
+            None => {}
+        }
+    }
+
+    /// Pastes into a pane, asking first for multi-line text when the application
+    /// has not enabled bracketed paste (and `confirm_multiline_paste` is on)
+    fn paste_into(&mut self, tab_index: usize, text: String) {
+        let Some(tab) = self.tabs.get_mut(tab_index) else {
+            return;
+        };
+        let bracketed = tab.terminal_emulator.bracketed_paste();
+        if self.app_settings.confirm_multiline_paste && !bracketed && text.contains('\n') {
+            self.pending_paste = Some((tab_index, text));
+        } else {
+            tab.paste(&text);
+        }
+    }
+
+    /// Find bar for the focused pane; matches are highlighted in the pane
+    fn render_find_bar(&mut self, ctx: &egui::Context) {
+        let focus_search = std::mem::take(&mut self.focus_search);
+        let Some(tab) = self.tabs.get_mut(self.focused_terminal) else {
+            return;
+        };
+        let Some(query) = tab.search.as_mut() else {
+            return;
+        };
+        let mut close = false;
+        egui::Window::new("Find")
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(false)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-12.0, 12.0))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("🔍");
+                    let edit = ui.add(
+                        egui::TextEdit::singleline(query)
+                            .hint_text("Find in pane")
+                            .desired_width(180.0),
+                    );
+                    if focus_search {
+                        edit.request_focus();
+                    }
+                    if edit.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        close = true;
+                    }
+                    let found = tab.terminal_emulator.find(query).len();
+                    if !query.is_empty() {
+                        ui.label(format!("{} match{}", found, if found == 1 { "" } else { "es" }));
+                    }
+                    if ui.small_button("✖").on_hover_text("Close (Esc)").clicked() {
+                        close = true;
+                    }
+                });
+            });
+        if close {
+            tab.search = None;
+        }
+    }
+
+    /// Runs an Actions panel button, asking first when it has `confirm = true`
+    fn run_button(&mut self, ctx: &egui::Context, index: usize, confirmed: bool) {
+        let Some(button) = self.buttons.get(index).cloned() else {
+            return;
+        };
+        if button.confirm && !confirmed {
+            self.pending_button = Some(index);
+            return;
+        }
+
+        if !button.action.trim().is_empty() {
+            match Action::parse(&button.action) {
+                Ok(Some(action)) => self.run_action(ctx, action, None),
+                Ok(None) => {}
+                Err(e) => eprintln!("[BUTTON] {:?}: {}", button.label, e),
+            }
+        }
+
+        let mut payload = String::new();
+        if !button.command.is_empty() {
+            payload.push_str(&button.command);
+            payload.push('\r');
+        }
+        payload.push_str(&button.keys);
+        if payload.is_empty() {
+            return;
+        }
+
+        let target = button.target.trim();
+        let index = if target.eq_ignore_ascii_case("new") {
+            Some(self.open_ephemeral_pane(format!("{} {}", button.icon, button.label).trim().to_string()))
+        } else if target.is_empty() || target.eq_ignore_ascii_case("focused") {
+            Some(self.focused_terminal)
+        } else {
+            self.tabs.iter().position(|t| t.title().eq_ignore_ascii_case(target))
+        };
+        match index.and_then(|i| self.tabs.get_mut(i)) {
+            Some(tab) => tab.send(payload.as_bytes()),
+            None => eprintln!("[BUTTON] {:?}: no tab titled {:?}", button.label, target),
+        }
+    }
+
+    /// Opens a shell in a new floating pane (button `target = "new"`, scripts) and focuses it
+    fn open_ephemeral_pane(&mut self, title: String) -> usize {
+        let mut tab = TerminalTab::new(TabConfig {
+            title,
+            command: "bash".to_string(),
+            auto_restart_on_success: false,
+            success_patterns: vec![],
+            dnd: DndSettings::default(),
+            log: LogMode::Off,
+            notify: NotifySettings::default(),
+            idle: IdleSettings::default(),
+        });
+        tab.configure_history(self.config.control.enabled.then_some(self.config.control.scrollback_lines));
+        self.tabs.push(tab);
+        self.ephemeral_ids.push(self.next_pane_id);
+        self.next_pane_id += 1;
+        self.focused_terminal = self.tabs.len() - 1;
+        self.focused_terminal
+    }
+
+    /// Shows ephemeral panes as windows; closing a window ends its shell
+    fn render_ephemeral_panes(&mut self, ctx: &egui::Context) {
+        let theme = self.theme.clone();
+        let mut closed = Vec::new();
+        for offset in 0..self.ephemeral_ids.len() {
+            let index = MAX_TABS + offset;
+            let metrics = self.cell_metrics(ctx, index);
+            let is_focused = self.focused_terminal == index;
+            let debug_overlay = self.debug_overlay;
+            let Some(tab) = self.tabs.get_mut(index) else {
+                continue;
+            };
+            let mut open = true;
+            let mut clicked = false;
+            egui::Window::new(tab.title().to_string())
+                .id(egui::Id::new(("ephemeral_pane", self.ephemeral_ids[offset])))
+                .open(&mut open)
+                .default_size(egui::vec2(640.0, 360.0))
+                .resizable(true)
+                .show(ctx, |ui| {
+                    clicked = Self::render_terminal_panel(ui, tab, is_focused, index, debug_overlay, &metrics, &theme);
+                });
+            if clicked {
+                self.focused_terminal = index;
+            }
+            if !open {
+                closed.push(index);
+            }
+        }
+        for &index in closed.iter().rev() {
+            let mut tab = self.tabs.remove(index);
+            tab.stop("closed");
+            for event in tab.take_events() {
+                self.analytics.record(tab.title(), event);
+            }
+            self.ephemeral_ids.remove(index - MAX_TABS);
+            match &mut self.pending_paste {
+                Some((i, _)) if *i == index => self.pending_paste = None,
+                Some((i, _)) if *i > index => *i -= 1,
+                _ => {}
+            }
+            match &mut self.pending_control {
+                Some((i, _, _)) if *i == index => self.pending_control = None,
+                Some((i, _, _)) if *i > index => *i -= 1,
+                _ => {}
+            }
+            self.notifications.pane_removed(index);
+            if self.focused_terminal == index {
+                self.focused_terminal = 0;
+            } else if self.focused_terminal > index {
+                self.focused_terminal -= 1;
+            }
+        }
+    }
+
+    /// Confirmation window for buttons with `confirm = true`
+    fn render_button_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(index) = self.pending_button else {
+            return;
+        };
+        let Some(button) = self.buttons.get(index).cloned() else {
+            self.pending_button = None;
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new(format!("Run \u{201c}{}\u{201d}?", button.label))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(Self::button_tooltip(&button));
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Run").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        if choice.is_none() && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            choice = Some(false);
+        }
+        if let Some(run) = choice {
+            self.pending_button = None;
+            if run {
+                self.run_button(ctx, index, true);
+            }
+        }
+    }
+
+    /// Runs controls requested from pane headers this frame
+    fn handle_pane_controls(&mut self) {
+        for index in 0..self.tabs.len() {
+            if let Some(control) = self.tabs[index].control.take() {
+                self.request_control(index, control);
+            }
+        }
+    }
+
+    /// Applies a pane control, asking first when it would end or interrupt a running job
+    fn request_control(&mut self, index: usize, control: PaneControl) {
+        let Some(tab) = self.tabs.get_mut(index) else {
+            return;
+        };
+        match tab.foreground_process() {
+            Some(job) if control.disruptive() => self.pending_control = Some((index, control, job)),
+            _ => tab.apply_control(control),
         }
     }
 
-    // ... (rest of the code remains the same)
-    /// 
-    /// * `row` - The row of terminal cells to render
-    /// * `ui` - The egui UI context
-    fn render_row(row: &[TerminalCell], ui: &mut egui::Ui) {
-        ui.horizontal(|ui| {
-            ui.spacing_mut().item_spacing.x = 0.0;
-            ui.spacing_mut().item_spacing.y = 0.0;
-
-            // Strict layout mode: render wide glyphs as a fixed two-cell spacer to preserve alignment
-            let mut i = 0usize;
-            while i < row.len() {
-                let cell = &row[i];
-
-                // If this is a wide-glyph lead cell, the next cell will be the placeholder '\0'
-                if i + 1 < row.len() && row[i + 1].character == '\0' && cell.character != '\0' {
-                    // Render exactly two monospace spaces to preserve grid alignment, skip the placeholder cell
-                    let spacer2 = egui::RichText::new("  ")
-                        .font(egui::FontId::monospace(12.0));
-                    ui.add(egui::Label::new(spacer2).wrap(false).selectable(false));
-                    i += 2;
-                    continue;
+    /// Confirmation for a pane control while a job is in the foreground
+    fn render_control_confirmation(&mut self, ctx: &egui::Context) {
+        let Some((index, control, job)) = self.pending_control.clone() else {
+            return;
+        };
+        let Some(title) = self.tabs.get(index).map(|t| t.title().to_string()) else {
+            self.pending_control = None;
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new(format!("{} \u{201c}{}\u{201d}?", control.label(), title))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!("\u{201c}{}\u{201d} is still running in this pane.", job));
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.button(control.label()).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        if choice.is_none() && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            choice = Some(false);
+        }
+        if let Some(confirmed) = choice {
+            self.pending_control = None;
+            if confirmed {
+                if let Some(tab) = self.tabs.get_mut(index) {
+                    tab.apply_control(control);
                 }
+            }
+        }
+    }
 
-                // Preserve column width for stray placeholder by rendering a single-space spacer
-                if cell.character == '\0' {
-                    let spacer = egui::RichText::new(" ")
-                        .font(egui::FontId::monospace(12.0));
-                    ui.add(egui::Label::new(spacer).wrap(false).selectable(false));
-                    i += 1;
-                    continue;
+    /// Hover text for a button: its `tooltip`, or a summary of what it does
+    fn button_tooltip(button: &ButtonConfig) -> String {
+        if !button.tooltip.is_empty() {
+            return button.tooltip.clone();
+        }
+        let target = match button.target.trim() {
+            "" => "the focused pane".to_string(),
+            t if t.eq_ignore_ascii_case("focused") => "the focused pane".to_string(),
+            t if t.eq_ignore_ascii_case("new") => "a new pane".to_string(),
+            t => format!("\"{}\"", t),
+        };
+        let mut parts = Vec::new();
+        if !button.action.is_empty() {
+            parts.push(button.action.replace('_', " "));
+        }
+        if !button.command.is_empty() {
+            parts.push(format!("run `{}` in {}", button.command, target));
+        }
+        if !button.keys.is_empty() {
+            parts.push(format!("send keys to {}", target));
+        }
+        parts.join("; ")
+    }
+
+    /// Accent color for a button: theme color name, `#rrggbb`, or the theme's blue
+    fn button_accent(button: &ButtonConfig, palette: &Palette) -> egui::Color32 {
+        let color = button.color.trim();
+        palette
+            .role(&color.to_ascii_lowercase())
+            .or_else(|| theme::parse_hex_color(color).ok())
+            .unwrap_or(palette.blue)
+    }
+
+    /// Confirmation window for a multi-line paste into an app without bracketed paste
+    ///
+    /// Enter pastes as-is, Escape cancels.
+    fn render_paste_confirmation(&mut self, ctx: &egui::Context) {
+        let Some((tab_index, text)) = self.pending_paste.clone() else {
+            return;
+        };
+        let line_count = text.lines().count();
+        let title = self.tabs.get(tab_index).map(|t| t.title().to_string()).unwrap_or_default();
+        let mut choice: Option<Option<String>> = None; // Some(None) = cancel
+
+        egui::Window::new("Paste multiple lines?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "The clipboard contains {} line(s). Each line will run in \"{}\" as soon as it is pasted.",
+                    line_count, title
+                ));
+                ui.add_space(4.0);
+                let preview: String = text.lines().take(8).collect::<Vec<_>>().join("\n");
+                egui::ScrollArea::vertical().max_height(140.0).show(ui, |ui| {
+                    ui.add(egui::Label::new(egui::RichText::new(preview).monospace()).wrap(true));
+                });
+                if line_count > 8 {
+                    ui.label(egui::RichText::new(format!("… {} more", line_count - 8)).italics());
                 }
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Paste").clicked() {
+                        choice = Some(Some(text.clone()));
+                    }
+                    if ui.button("Paste without trailing newline").clicked() {
+                        choice = Some(Some(text.trim_end_matches(['\r', '\n']).to_string()));
+                    }
+                    if ui.button("Cancel").clicked() {
+                        choice = Some(None);
+                    }
+                });
+            });
 
-                // Normal single-width glyph
-                let mut rich_text = egui::RichText::new(cell.character.to_string())
-                    .font(egui::FontId::monospace(12.0))
-                    .color(cell.color);
-                
-                if cell.bold {
-                    rich_text = rich_text.strong();
+        if choice.is_none() {
+            ctx.input_mut(|i| {
+                if i.consume_key(egui::Modifiers::NONE, egui::Key::Enter) {
+                    choice = Some(Some(text.clone()));
+                } else if i.consume_key(egui::Modifiers::NONE, egui::Key::Escape) {
+                    choice = Some(None);
                 }
-                
-                ui.add(egui::Label::new(rich_text).wrap(false).selectable(false));
-                i += 1;
+            });
+        }
+        if let Some(result) = choice {
+            self.pending_paste = None;
+            if let (Some(text), Some(tab)) = (result, self.tabs.get_mut(tab_index)) {
+                tab.paste(&text);
             }
-        });
+        }
     }
 
-    /// Global keyboard input handler: reads from ctx so input is not lost to nested widgets
-    fn handle_terminal_key_input_ctx(
-        ctx: &egui::Context,
-        pty_writer: &mut Option<Box<dyn Write + Send>>,
+    /// Paints one terminal row at `origin` on a fixed cell grid
+    ///
+    /// Runs of ASCII with the same style are painted as one string; any other glyph
+    /// is painted at its own cell so wide glyphs and fallback-font glyphs stay aligned.
+    /// Placeholder cells ('\0') following a wide glyph are skipped.
+    ///
+    /// * `row` - The row of terminal cells to render
+    /// * `painter` - Painter clipped to the terminal area
+    /// * `theme` - Theme used to resolve cell colors
+    fn paint_row(
+        row: &[TerminalCell],
+        painter: &egui::Painter,
+        origin: egui::Pos2,
+        metrics: &CellMetrics,
+        theme: &Theme,
     ) {
-        ctx.input(|i| {
-            // Handle all inputs via raw events so no widget consumption can block them
-            for ev in &i.events {
-                match ev {
-                    egui::Event::Text(text) => {
-                        // If CTRL is held and a single ASCII char is typed, map to control code.
-                        // Example: Ctrl+C -> 0x03 (ETX), Ctrl+D -> 0x04 (EOT), etc.
-                        // This enables terminal signals like SIGINT via PTY line discipline.
-                        if i.modifiers.ctrl {
-                            if text.len() == 1 {
-                                let mut ch_iter = text.chars();
-                                if let Some(ch) = ch_iter.next() {
-                                    if ch.is_ascii() {
-                                        let upper = ch.to_ascii_uppercase();
-                                        let code = (upper as u8) & 0x1F; // Map A..Z to 0x01..0x1A
-                                        if code != 0 { // Skip NUL for non A..Z, still harmless
-                                            if let Some(ref mut writer) = pty_writer {
-                                                let _ = writer.write_all(&[code]);
-                                            }
-                                            // Swallow regular text for Ctrl+<char>
-                                            continue;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        // Default: forward typed text as-is
-                        if let Some(ref mut writer) = pty_writer {
-                            let _ = writer.write_all(text.as_bytes());
-                        }
-                    }
-                    egui::Event::Paste(text) => {
-                        if let Some(ref mut writer) = pty_writer {
-                            let _ = writer.write_all(text.as_bytes());
-                        }
-                    }
-                    egui::Event::Key { key, pressed, repeat: _, modifiers, .. } => {
-                        if *pressed {
-                            // Handle Ctrl-based TTY signals only (avoid hijacking Command shortcuts)
-                            let mut handled_ctrl = false;
-                            if modifiers.ctrl {
-                                match key {
-                                    // Ctrl+C -> ETX (SIGINT)
-                                    egui::Key::C => {
-                                        if let Some(ref mut writer) = pty_writer {
-                                            let _ = writer.write_all(&[0x03]);
-                                        }
-                                        handled_ctrl = true;
-                                    }
-                                    // Ctrl+D -> EOT (EOF)
-                                    egui::Key::D => {
-                                        if let Some(ref mut writer) = pty_writer {
-                                            let _ = writer.write_all(&[0x04]);
-                                        }
-                                        handled_ctrl = true;
-                                    }
-                                    _ => {}
-                                }
-                            }
-                            if handled_ctrl { return; }
-                            match key {
-                                egui::Key::Enter => {
-                                    if let Some(ref mut writer) = pty_writer {
-                                        // CR is the most compatible for PTYs
-                                        let _ = writer.write_all(b"\r");
-                                    }
-                                }
-                                egui::Key::Backspace => {
-                                    if let Some(ref mut writer) = pty_writer {
-                                        // Send DEL (0x7F). If a target app expects BS (0x08), we can add an option later.
-                                        let _ = writer.write_all(&[0x7F]);
-                                    }
-                                }
-                                egui::Key::Tab => {
-                                    if !modifiers.shift {
-                                        if let Some(ref mut writer) = pty_writer {
-                                            let _ = writer.write_all(b"\t");
-                                        }
-                                    }
-                                }
-                                egui::Key::Escape => {
-                                    if let Some(ref mut writer) = pty_writer {
-                                        let _ = writer.write_all(b"\x1b");
-                                    }
-                                }
-                                egui::Key::ArrowUp => {
-                                    if let Some(ref mut writer) = pty_writer {
-                                        let _ = writer.write_all(b"\x1b[A");
-                                    }
-                                }
-                                egui::Key::ArrowDown => {
-                                    if let Some(ref mut writer) = pty_writer {
-                                        let _ = writer.write_all(b"\x1b[B");
-                                    }
-                                }
-                                egui::Key::ArrowLeft => {
-                                    if let Some(ref mut writer) = pty_writer {
-                                        let _ = writer.write_all(b"\x1b[D");
-                                    }
-                                }
-                                egui::Key::ArrowRight => {
-                                    if let Some(ref mut writer) = pty_writer {
-                                        let _ = writer.write_all(b"\x1b[C");
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
+        let y = origin.y + metrics.text_offset_y;
+        let mut i = 0usize;
+        while i < row.len() {
+            let cell = &row[i];
+            if cell.character == ' ' || cell.character == '\0' {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            let mut text = String::new();
+            if cell.character.is_ascii() {
+                while i < row.len() {
+                    let next = &row[i];
+                    if !next.character.is_ascii()
+                        || next.character == '\0'
+                        || next.color != cell.color
+                        || next.bold != cell.bold
+                    {
+                        break;
                     }
-                    _ => {}
+                    text.push(next.character);
+                    i += 1;
                 }
+            } else {
+                text.push(cell.character);
+                i += 1;
             }
-        });
+
+            let pos = egui::pos2(origin.x + start as f32 * metrics.cell_w, y);
+            let color = theme.resolve(cell.color);
+            painter.text(pos, egui::Align2::LEFT_TOP, &text, metrics.font_id.clone(), color);
+            if cell.bold {
+                // egui has no bold monospace face; overdraw with a small offset instead
+                painter.text(
+                    pos + egui::vec2(0.5, 0.0),
+                    egui::Align2::LEFT_TOP,
+                    &text,
+                    metrics.font_id.clone(),
+                    color,
+                );
+            }
+        }
     }
 
-    /// Renders the complete terminal buffer
+    /// Renders the complete terminal buffer into `rect`
     /// 
     /// # Arguments
     /// 
     /// * `ui` - The egui UI context
+    /// * `rect` - Area reserved for the terminal grid
     /// * `buffer` - The terminal buffer to render
-    fn render_terminal_buffer(ui: &mut egui::Ui, buffer: &[Vec<TerminalCell>]) {
-        ui.spacing_mut().item_spacing.y = 0.0;
-        ui.spacing_mut().item_spacing.x = 0.0;
-        
-        for row in buffer {
-            Self::render_row(row, ui);
+    /// * `metrics` - Cell size and font for this pane
+    /// * `theme` - Active color theme
+    /// * `highlights` - Find matches as `(row, col, width)` in cells
+    fn render_terminal_buffer(
+        ui: &mut egui::Ui,
+        rect: egui::Rect,
+        buffer: &[Vec<TerminalCell>],
+        metrics: &CellMetrics,
+        theme: &Theme,
+        highlights: &[(usize, usize, usize)],
+    ) {
+        let painter = ui.painter_at(rect);
+        for &(row, col, width) in highlights {
+            let min = rect.min + egui::vec2(col as f32 * metrics.cell_w, row as f32 * metrics.cell_h);
+            let size = egui::vec2(width as f32 * metrics.cell_w, metrics.cell_h);
+            painter.rect_filled(egui::Rect::from_min_size(min, size), 2.0, theme.palette.yellow.linear_multiply(0.35));
+        }
+        for (r, row) in buffer.iter().enumerate() {
+            let origin = egui::pos2(rect.left(), rect.top() + r as f32 * metrics.cell_h);
+            if origin.y > rect.bottom() {
+                break;
+            }
+            Self::paint_row(row, &painter, origin, metrics, theme);
         }
     }
 
@@ -730,22 +2663,18 @@ impl AudioToolkitApp {
         accent: egui::Color32,
         size: egui::Vec2,
         is_right_col: bool,
+        palette: &Palette,
     ) -> egui::Response {
         let (rect, resp) = ui.allocate_exact_size(size, egui::Sense::click());
         // Base paint rect
-        let near_right_edge = (rect.max.x + 0.5) >= ui.max_rect().right();
         let mut paint_rect = rect;
         // Seam overlap to defeat AA between columns
         if is_right_col {
-            paint_rect.min.x = paint_rect.min.x - 0.5;
+            paint_rect.min.x -= 0.5;
         } else {
-            paint_rect.max.x = paint_rect.max.x + 0.5;
-        }
-        // Do not recess the far-right edge anymore (avoid visible cutoff)
-        if near_right_edge {
-            // no-op: keep full width
-            paint_rect.max.x = paint_rect.max.x;
+            paint_rect.max.x += 0.5;
         }
+        // Do not recess the far-right edge anymore (avoid visible cutoff): keep full width
 
         // Outer-corner rounding: zero out inner corners between columns
         let mut rounding = egui::Rounding::same(4.0);
@@ -764,9 +2693,9 @@ impl AudioToolkitApp {
         let bg = if resp.is_pointer_button_down_on() {
             visuals.widgets.inactive.bg_fill
         } else if resp.hovered() {
-            CatppuccinTheme::FRAPPE.surface1
+            palette.surface1
         } else {
-            CatppuccinTheme::FRAPPE.surface0
+            palette.surface0
         };
 
         let painter = ui.painter();
@@ -874,6 +2803,7 @@ impl AudioToolkitApp {
     }
 
     /// Split a rect vertically into (top, handle, bottom) using a fractional split with pixel minimums
+    #[allow(dead_code)]
     fn split_vertical(
         ui: &mut egui::Ui,
         fraction: &mut f32,
@@ -881,6 +2811,7 @@ impl AudioToolkitApp {
         min_bottom_px: f32,
         handle_px: f32,
         id: egui::Id,
+        palette: &Palette,
     ) -> (egui::Rect, egui::Rect, egui::Rect) {
         let rect = ui.available_rect_before_wrap();
         let total_h = rect.height().max(1.0);
@@ -906,9 +2837,9 @@ impl AudioToolkitApp {
 
         // Paint handle (subtle)
         let color = if resp.hovered() || resp.dragged() {
-            CatppuccinTheme::FRAPPE.overlay0
+            palette.overlay0
         } else {
-            CatppuccinTheme::FRAPPE.surface1
+            palette.surface1
         };
         ui.painter()
             .rect_filled(handle_rect, 2.0, color.linear_multiply(0.35));
@@ -922,6 +2853,7 @@ impl AudioToolkitApp {
     }
 
     /// Split a rect horizontally into (left, handle, right) using a fractional split with pixel minimums
+    #[allow(dead_code)]
     fn split_horizontal(
         ui: &mut egui::Ui,
         fraction: &mut f32,
@@ -929,6 +2861,7 @@ impl AudioToolkitApp {
         min_right_px: f32,
         handle_px: f32,
         id: egui::Id,
+        palette: &Palette,
     ) -> (egui::Rect, egui::Rect, egui::Rect) {
         let rect = ui.available_rect_before_wrap();
         let total_w = rect.width().max(1.0);
@@ -954,9 +2887,9 @@ impl AudioToolkitApp {
 
         // Paint handle (subtle)
         let color = if resp.hovered() || resp.dragged() {
-            CatppuccinTheme::FRAPPE.overlay0
+            palette.overlay0
         } else {
-            CatppuccinTheme::FRAPPE.surface1
+            palette.surface1
         };
         ui.painter()
             .rect_filled(handle_rect, 2.0, color.linear_multiply(0.35));
@@ -1002,15 +2935,35 @@ impl AudioToolkitApp {
 
     
     
-    /// Single-pass drag-and-drop handler: always route to the focused terminal.
-    /// Pointer and rect hit-testing are intentionally ignored.
+    /// Single-pass drag-and-drop handler: routes drops by `[app] dnd_routing` and
+    /// types the target pane's drop templates (see [`crate::dnd`]).
+    ///
+    /// Some platforms (winit on macOS) report no cursor movement while an OS file
+    /// drag is over the window, leaving the pointer where it was before the drag.
+    /// The pointer position is only trusted once it has moved during the drag;
+    /// until then the focused pane is both highlighted and used.
     fn handle_dnd_single_pass(&mut self, ctx: &egui::Context, focus_rects: &[(usize, egui::Rect)]) {
-        // Silence unused parameter warning; rects are collected for potential future use
-        let _ = focus_rects;
-
-        let (hovering_files, dropped_files) = ctx.input(|i| {
-            (!i.raw.hovered_files.is_empty(), i.raw.dropped_files.clone())
+        let (hovering_files, dropped_files, pointer, moved) = ctx.input(|i| {
+            let moved = i.events.iter().any(|e| matches!(e, egui::Event::PointerMoved(_)));
+            (!i.raw.hovered_files.is_empty(), i.raw.dropped_files.clone(), i.pointer.latest_pos(), moved)
         });
+        if hovering_files || !dropped_files.is_empty() {
+            self.dnd_pointer_live |= moved;
+        } else {
+            self.dnd_pointer_live = false;
+        }
+
+        // In pointer mode the pane under the cursor wins; anywhere else falls back to the focused pane
+        let target = match self.app_settings.dnd_routing {
+            DndRouting::Focused => self.focused_terminal,
+            DndRouting::Pointer => pointer
+                .filter(|_| self.dnd_pointer_live)
+                .and_then(|pos| self.pane_at(ctx, focus_rects, pos))
+                .unwrap_or(self.focused_terminal),
+        };
+        for (index, tab) in self.tabs.iter_mut().enumerate() {
+            tab.drop_target = hovering_files && index == target;
+        }
 
         if hovering_files {
             // Ensure smooth hover visuals while dragging
@@ -1020,79 +2973,43 @@ impl AudioToolkitApp {
         if dropped_files.is_empty() {
             return;
         }
+        self.dnd_pointer_live = false;
 
-        let target = self.focused_terminal;
         if let Some(tab) = self.tabs.get_mut(target) {
-            use std::io::Write;
-            // If exactly one directory dropped, we may apply special behavior
-            let single_dir = if dropped_files.len() == 1 {
-                if let Some(path) = dropped_files[0].path.as_ref() {
-                    std::fs::metadata(path).map(|m| m.is_dir()).unwrap_or(false)
-                } else {
-                    false
-                }
-            } else {
-                false
-            };
-
-            // Build the text to send and whether to press Enter after
-            let mut send_enter = false;
-            let text_to_send = if single_dir {
-                // Per-tab precedence: auto-cd -> auto-run -> default (no Enter)
-                let cd_flag = tab.config.dnd.auto_cd_on_folder_drop;
-                let run_flag = tab.config.dnd.auto_run_on_folder_drop;
-                if cd_flag {
-                    send_enter = true;
-                    if let Some(path) = dropped_files[0].path.as_ref() {
-                        format!("cd {}", Self::shell_quote_path(path))
-                    } else {
-                        format!("cd {}", Self::shell_quote_str(&dropped_files[0].name))
-                    }
-                } else if run_flag {
-                    send_enter = true;
-                    if let Some(path) = dropped_files[0].path.as_ref() {
-                        Self::shell_quote_path(path)
-                    } else {
-                        Self::shell_quote_str(&dropped_files[0].name)
-                    }
-                } else {
-                    // Default: insert quoted item with trailing space (no Enter)
-                    if let Some(path) = dropped_files[0].path.as_ref() {
-                        let mut s = Self::shell_quote_path(path);
-                        s.push(' ');
-                        s
-                    } else {
-                        let mut s = Self::shell_quote_str(&dropped_files[0].name);
-                        s.push(' ');
-                        s
-                    }
-                }
+            let items: Vec<DropItem> = dropped_files
+                .iter()
+                .map(|f| DropItem::new(f.path.clone().unwrap_or_else(|| f.name.clone().into())))
+                .collect();
+            if tab.config.dnd.mode == DropMode::Queue {
+                tab.enqueue_drop(dnd::queue_jobs(&tab.config.dnd, &items, Self::shell_quote_str));
             } else {
-                // Multi-item or non-directory: join quoted items with spaces and add a trailing space
-                let mut parts: Vec<String> = Vec::with_capacity(dropped_files.len());
-                for f in &dropped_files {
-                    if let Some(path) = f.path.as_ref() {
-                        parts.push(Self::shell_quote_path(path));
-                    } else {
-                        parts.push(Self::shell_quote_str(&f.name));
-                    }
-                }
-                let mut s = parts.join(" ");
-                s.push(' ');
-                s
-            };
-
-            if let Some(ref mut writer) = tab.pty_writer {
-                let _ = writer.write_all(text_to_send.as_bytes());
-                if send_enter {
-                    let _ = writer.write_all(b"\n");
-                }
+                let commands = dnd::plan(&tab.config.dnd, &items, Self::shell_quote_str);
+                tab.send(dnd::to_input(&commands).as_bytes());
             }
 
-            // Keep focus on the focused terminal (explicitly set for clarity)
+            // The pane that received the drop takes focus
             self.focused_terminal = target;
         }
     }
+
+    /// Pane under `pos`: an ephemeral pane window, else one of the fixed panes
+    ///
+    /// Uses last frame's window layers, so a dialog covering a pane hides it from
+    /// drops. Returns `None` outside every pane.
+    fn pane_at(&self, ctx: &egui::Context, fixed_rects: &[(usize, egui::Rect)], pos: egui::Pos2) -> Option<usize> {
+        let layer = ctx.layer_id_at(pos)?;
+        if let Some(offset) = self
+            .ephemeral_ids
+            .iter()
+            .position(|&id| layer.id == egui::Id::new(("ephemeral_pane", id)))
+        {
+            return Some(MAX_TABS + offset);
+        }
+        if layer.order != egui::Order::Background {
+            return None;
+        }
+        fixed_rects.iter().find(|(_, rect)| rect.contains(pos)).map(|&(index, _)| index)
+    }
     
     /// Compute the header band height used by terminal panels.
     /// This must be kept consistent across `render_terminal_panel` and any
@@ -1111,6 +3028,40 @@ impl AudioToolkitApp {
         title_size + 10.0
     }
 
+    /// Quick controls at the right of a pane header; every control is in the header's context menu
+    fn render_pane_controls(ui: &mut egui::Ui, tab: &mut TerminalTab) {
+        // Right-to-left: shown as restart, interrupt, clear
+        for (icon, control) in [("🗑", PaneControl::Clear), ("✖", PaneControl::Interrupt), ("🔄", PaneControl::Restart)] {
+            let hint = format!("{} (right-click the header for more)", control.label());
+            if ui.small_button(icon).on_hover_text(hint).clicked() {
+                tab.control = Some(control);
+            }
+        }
+    }
+
+    /// Drop queue progress with pause, skip and cancel, shown in the pane header
+    fn render_queue_controls(ui: &mut egui::Ui, tab: &mut TerminalTab, p: &Palette) {
+        let Some(queue) = tab.drop_queue.as_mut() else {
+            return;
+        };
+        let (started, total) = queue.progress();
+        let current = queue.current().unwrap_or(if queue.paused { "paused" } else { "waiting" });
+        ui.label(egui::RichText::new(format!("📥 {}/{} {}", started, total, current)).color(p.peach).size(11.0))
+            .on_hover_text(queue.summary());
+        let (icon, hint) = if queue.paused { ("▶", "Resume the queue") } else { ("⏸", "Pause after the current file") };
+        if ui.small_button(icon).on_hover_text(hint).clicked() {
+            queue.paused = !queue.paused;
+        }
+        if ui.small_button("⏭").on_hover_text("Skip the current file").clicked() {
+            tab.skip_queue_item();
+        }
+        if ui.small_button("⏹").on_hover_text("Cancel the remaining files").clicked() {
+            if let Some(queue) = tab.drop_queue.as_mut() {
+                queue.cancel();
+            }
+        }
+    }
+
     /// Renders a single terminal panel (header, output). Returns true if the header was clicked.
     /// `tab_index` is used to assign stable, unique widget ids (e.g., ScrollArea state).
     fn render_terminal_panel(
@@ -1119,13 +3070,16 @@ impl AudioToolkitApp {
         is_focused: bool,
         tab_index: usize,
         debug_overlay: bool,
+        metrics: &CellMetrics,
+        theme: &Theme,
     ) -> bool {
+        let p = &theme.palette;
         let mut clicked = false;
         let focus_indicator = if is_focused { "🔵" } else { "⚪" };
         let title_color = if is_focused {
-            CatppuccinTheme::FRAPPE.blue
+            p.blue
         } else {
-            CatppuccinTheme::FRAPPE.subtext0
+            p.subtext0
         };
 
         let frame_inner = egui::Frame::default()
@@ -1149,152 +3103,169 @@ impl AudioToolkitApp {
                     );
                 }
                 ui.allocate_ui_at_rect(header_rect, |ui| {
-                    ui.horizontal(|ui| {
-                        let title = egui::RichText::new(format!("{} 🖥️ {}", focus_indicator, tab.title()))
-                            .color(title_color)
-                            .strong()
-                            .size((header_h - 10.0).max(1.0));
-                        ui.add(egui::Label::new(title).truncate(true));
-                        if !is_focused {
-                            ui.add(
-                                egui::Label::new(
-                                    egui::RichText::new("(Click to focus)")
-                                        .color(CatppuccinTheme::FRAPPE.overlay0)
-                                        .italics(),
-                                )
-                                .truncate(true),
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        Self::render_pane_controls(ui, tab);
+                        ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                            let title = egui::RichText::new(format!("{} 🖥️ {}", focus_indicator, tab.title()))
+                                .color(title_color)
+                                .strong()
+                                .size((header_h - 10.0).max(1.0));
+                            ui.add(egui::Label::new(title).truncate(true));
+                            if tab.exited {
+                                ui.label(egui::RichText::new("(exited)").color(p.red).italics());
+                            }
+                            let (since_output, since_input) = tab.idle.since(std::time::Instant::now());
+                            let quiet = format!(
+                                "No output for {}, no input for {}",
+                                notify::format_quiet(since_output),
+                                notify::format_quiet(since_input)
                             );
-                        }
+                            match tab.idle.state() {
+                                IdleState::Waiting(_) => {
+                                    ui.label(egui::RichText::new("⌨ waiting for input?").color(p.peach).strong().size(11.0))
+                                        .on_hover_text(format!("The last line looks like a prompt. {}", quiet));
+                                }
+                                IdleState::Idle(for_how_long) => {
+                                    ui.label(
+                                        egui::RichText::new(format!("⏸ idle {}", notify::format_quiet(for_how_long)))
+                                            .color(p.overlay1)
+                                            .size(11.0),
+                                    )
+                                    .on_hover_text(quiet);
+                                }
+                                IdleState::Active => {}
+                            }
+                            if !is_focused && tab.unread > 0 {
+                                ui.label(egui::RichText::new(format!("🔔 {}", tab.unread)).color(p.yellow).strong().size(11.0))
+                                    .on_hover_text("Notifications since you last looked at this pane");
+                            } else if !is_focused && tab.activity {
+                                ui.label(egui::RichText::new("●").color(p.sky).size(11.0))
+                                    .on_hover_text("New output since you last looked at this pane");
+                            }
+                            if let Some(path) = tab.log_path() {
+                                ui.label(egui::RichText::new("⏺ LOG").color(p.red).size(11.0))
+                                    .on_hover_text(format!("Logging output to {}", path.display()));
+                            }
+                            if let Some(path) = tab.recording() {
+                                ui.label(egui::RichText::new("⏺ REC").color(p.red).strong().size(11.0))
+                                    .on_hover_text(format!("Recording to {}", path.display()));
+                            }
+                            Self::render_queue_controls(ui, tab, p);
+                            if (tab.zoom - 1.0).abs() > 0.01 {
+                                ui.label(
+                                    egui::RichText::new(format!("🔍 {:.0}%", tab.zoom * 100.0))
+                                        .color(p.overlay1)
+                                        .size(11.0),
+                                );
+                            }
+                            if !is_focused {
+                                ui.add(
+                                    egui::Label::new(
+                                        egui::RichText::new("(Click to focus)")
+                                            .color(p.overlay0)
+                                            .italics(),
+                                    )
+                                    .truncate(true),
+                                );
+                            }
+                        });
                     });
                 });
+                header_resp.context_menu(|ui| {
+                    for control in PaneControl::ALL {
+                        if ui.button(control.label()).clicked() {
+                            tab.control = Some(control);
+                            ui.close_menu();
+                        }
+                    }
+                    ui.separator();
+                    let label = if tab.recording().is_some() { "⏹ Stop recording" } else { "⏺ Start recording" };
+                    if ui.button(label).clicked() {
+                        tab.toggle_recording();
+                        ui.close_menu();
+                    }
+                });
                 if header_resp.clicked() {
                     if debug_overlay { eprintln!("[FOCUS] header clicked idx={} rect={:?}", tab_index, header_rect); }
                     clicked = true;
                 }
 
-                // Output: occupy full width; sticky to bottom; assign unique id per terminal to avoid shared scrolling
-                egui::ScrollArea::vertical()
-                    .id_source(egui::Id::new(("terminal_output_scroll_idx", tab_index)))
-                    .auto_shrink([false, false])
-                    .stick_to_bottom(true)
-                    .show(ui, |ui| {
-                        // Ensure inner content takes the full available width (flush to the right edge)
-                        ui.set_width(ui.available_width());
-                        ui.style_mut().override_text_style = Some(egui::TextStyle::Monospace);
-                        Self::render_terminal_buffer(ui, &tab.terminal_emulator().buffer);
-                    });
+                // Output: the grid fills the rest of the pane; the PTY follows the pane size
+                let (term_rect, _) = ui.allocate_exact_size(ui.available_size(), egui::Sense::hover());
+                let (rows, cols) = metrics.grid_size(term_rect.size());
+                tab.resize(rows, cols);
+                tab.forward_mouse(ui, term_rect, metrics);
+                let highlights = match tab.search.as_deref() {
+                    Some(query) => tab.terminal_emulator().find(query),
+                    None => Vec::new(),
+                };
+                Self::render_terminal_buffer(ui, term_rect, &tab.terminal_emulator().buffer, metrics, theme, &highlights);
+                if tab.inspect {
+                    tab.hover_cell = ui
+                        .input(|i| i.pointer.hover_pos())
+                        .filter(|_| ui.rect_contains_pointer(term_rect))
+                        .map(|pos| metrics.cell_at(term_rect.min, pos, tab.grid_size));
+                    if let Some((row, col)) = tab.hover_cell {
+                        let min = term_rect.min + egui::vec2(col as f32 * metrics.cell_w, row as f32 * metrics.cell_h);
+                        let cell = egui::Rect::from_min_size(min, egui::vec2(metrics.cell_w, metrics.cell_h));
+                        ui.painter().rect_stroke(cell, 0.0, egui::Stroke::new(1.0, theme.palette.peach));
+                    }
+                }
             });
+        // While files are dragged over the app, a soft outer glow marks the drop target
+        let rect = frame_inner.response.rect;
+        if tab.drop_target {
+            ui.painter().rect_stroke(
+                rect,
+                egui::Rounding::same(3.0),
+                egui::Stroke { width: 4.0, color: theme.palette.blue.linear_multiply(0.35) },
+            );
+        }
         // Draw a more visible focus border around the entire panel.
-        // When a drag is in progress, add a soft outer glow to make the drop target obvious.
         if is_focused {
-            let rect = frame_inner.response.rect;
             let p = ui.painter();
-            // Subtle glow only while dragging files over the app
-            let hovering_dnd = ui.input(|i| !i.raw.hovered_files.is_empty());
-            if hovering_dnd {
-                p.rect_stroke(
-                    rect,
-                    egui::Rounding::same(3.0),
-                    egui::Stroke { width: 4.0, color: CatppuccinTheme::FRAPPE.blue.linear_multiply(0.35) },
-                );
-            }
             // Crisp border for the focused panel
             p.rect_stroke(
                 rect,
                 egui::Rounding::same(3.0),
-                egui::Stroke { width: 2.0, color: CatppuccinTheme::FRAPPE.blue },
+                egui::Stroke { width: 2.0, color: theme.palette.blue },
             );
         }
         clicked
     }
 
-    /// Handles terminal keyboard input including arrow keys and special keys (panel-local)
-    /// 
-    /// # Arguments
-    /// 
-    /// * `ui` - The egui UI context
-    /// * `pty_writer` - The PTY writer for sending input
-    fn handle_terminal_key_input(
-        ui: &mut egui::Ui,
-        pty_writer: &mut Option<Box<dyn Write + Send>>,
-    ) {
-        ui.input(|i| {
-            // Forward text input and paste directly to PTY
-            for ev in &i.events {
-                match ev {
-                    egui::Event::Text(text) => {
-                        if let Some(ref mut writer) = pty_writer {
-                            let _ = writer.write_all(text.as_bytes());
-                        }
-                    }
-                    egui::Event::Paste(text) => {
-                        if let Some(ref mut writer) = pty_writer {
-                            let _ = writer.write_all(text.as_bytes());
-                        }
-                    }
-                    _ => {}
-                }
-            }
-
-            // Special keys
-            if i.key_pressed(egui::Key::Enter) {
-                if let Some(ref mut writer) = pty_writer {
-                    let _ = writer.write_all(b"\n");
-                }
-            }
-            if i.key_pressed(egui::Key::Backspace) {
-                if let Some(ref mut writer) = pty_writer {
-                    let _ = writer.write_all(&[0x7F]); // DEL
-                }
-            }
-            // Forward Tab to PTY; Shift+Tab is reserved for focus cycling globally
-            if i.key_pressed(egui::Key::Tab) && !i.modifiers.shift {
-                if let Some(ref mut writer) = pty_writer {
-                    let _ = writer.write_all(b"\t");
-                }
-            }
-            if i.key_pressed(egui::Key::Escape) {
-                if let Some(ref mut writer) = pty_writer {
-                    let _ = writer.write_all(b"\x1b");
-                }
-            }
+}
 
-            // Arrow keys -> CSI sequences
-            if i.key_pressed(egui::Key::ArrowUp) {
-                if let Some(ref mut writer) = pty_writer {
-                    let _ = writer.write_all(b"\x1b[A");
-                }
-            }
-            if i.key_pressed(egui::Key::ArrowDown) {
-                if let Some(ref mut writer) = pty_writer {
-                    let _ = writer.write_all(b"\x1b[B");
-                }
-            }
-            if i.key_pressed(egui::Key::ArrowLeft) {
-                if let Some(ref mut writer) = pty_writer {
-                    let _ = writer.write_all(b"\x1b[D");
-                }
-            }
-            if i.key_pressed(egui::Key::ArrowRight) {
-                if let Some(ref mut writer) = pty_writer {
-                    let _ = writer.write_all(b"\x1b[C");
-                }
+impl Drop for AudioToolkitApp {
+    /// Records the uptime and output of processes still running at exit
+    fn drop(&mut self) {
+        for tab in &mut self.tabs {
+            tab.stop("app_exit");
+            for event in tab.take_events() {
+                self.analytics.record(tab.title(), event);
             }
-        });
+        }
     }
 }
 
 impl App for AudioToolkitApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
-        // Apply Catppuccin Frappé theme to egui context
+        // Apply the active theme to the egui context
+        let theme = self.theme.clone();
+        let p = theme.palette;
         let mut style = (*ctx.style()).clone();
         
-        // Set window and panel backgrounds to Catppuccin base color
-        style.visuals.window_fill = CatppuccinTheme::FRAPPE.base;
-        style.visuals.panel_fill = CatppuccinTheme::FRAPPE.base;
+        // Start from egui's dark or light visuals so widgets match the theme brightness
+        style.visuals = if theme.dark { egui::Visuals::dark() } else { egui::Visuals::light() };
+        
+        // Set window and panel backgrounds to the theme's base color
+        style.visuals.window_fill = p.base;
+        style.visuals.panel_fill = p.base;
+        style.visuals.extreme_bg_color = p.mantle;
+        style.visuals.selection.bg_fill = p.blue.linear_multiply(0.4);
         
-        // Set text color to Catppuccin text color
-        style.visuals.override_text_color = Some(CatppuccinTheme::FRAPPE.text);
+        // Set text color to the theme's text color
+        style.visuals.override_text_color = Some(p.text);
         // Make splitters easier to grab so users can resize panels even when narrow
         style.interaction.resize_grab_radius_side = 12.0;
         
@@ -1330,19 +3301,46 @@ impl App for AudioToolkitApp {
             }
         }
 
-        // Global keyboard shortcut: cycle focus across terminals (Shift+Tab)
-        if ctx.input(|i| i.modifiers.shift && i.key_pressed(egui::Key::Tab)) {
-            if !self.tabs.is_empty() {
-                self.focused_terminal = (self.focused_terminal + 1) % self.tabs.len().max(1);
-            }
-        }
+        // Key bindings and terminal input (one pipeline for every pane)
+        self.process_input(ctx);
+
+        // Requests from the control socket, before output so `screen` sees this frame's state
+        self.handle_control();
 
         // Update output for all tabs and handle restarts
-        for tab in &mut self.tabs {
+        let now = std::time::Instant::now();
+        let window_focused = ctx.input(|i| i.focused);
+        for (index, tab) in self.tabs.iter_mut().enumerate() {
+            tab.set_control_capture(self.control.as_ref().is_some_and(|s| s.wants_output(tab.title())));
             tab.update_output();
+            tab.check_idle(now);
             if tab.needs_restart {
                 tab.restart();
             }
+            let focused = index == self.focused_terminal;
+            for alert in tab.take_alerts() {
+                let delivered = self.notifications.push(index, tab.title(), &alert, focused && window_focused, now);
+                if delivered && !focused {
+                    tab.unread += 1;
+                }
+            }
+            if focused {
+                tab.mark_seen();
+            }
+            let events = tab.take_events();
+            if let Some(server) = self.control.as_mut() {
+                let output = tab.take_control_output();
+                if !output.is_empty() {
+                    server.publish(index, tab.title(), control::OUTPUT_EVENT, serde_json::json!({ "data": output }));
+                }
+                for event in &events {
+                    let fields = serde_json::json!({ "detail": event.detail, "value": event.value });
+                    server.publish(index, tab.title(), event.kind.as_str(), fields);
+                }
+            }
+            for event in events {
+                self.analytics.record(tab.title(), event);
+            }
         }
 
         // Collect DnD focus rects for this frame
@@ -1380,13 +3378,14 @@ impl App for AudioToolkitApp {
 
                 // Top: Terminal 1
                 let mut t1_ui = ui.child_ui(t1_rect, egui::Layout::top_down(egui::Align::Min));
-                if self.tabs.len() >= 1 {
+                if !self.tabs.is_empty() {
+                    let metrics = self.cell_metrics(ctx, 0);
                     let tab = &mut self.tabs[0];
                     let is_focused = self.focused_terminal == 0;
                     let clicked = {
                         let mut clicked_local = false;
                         t1_ui.push_id(("pane_scope", 0usize), |ui| {
-                            clicked_local = Self::render_terminal_panel(ui, tab, is_focused, 0, self.debug_overlay);
+                            clicked_local = Self::render_terminal_panel(ui, tab, is_focused, 0, self.debug_overlay, &metrics, &theme);
                         });
                         clicked_local
                     };
@@ -1421,18 +3420,34 @@ impl App for AudioToolkitApp {
 
                 // Bottom: Buttons container with its own scroll area
                 let mut btn_ui = ui.child_ui(btn_rect, egui::Layout::top_down(egui::Align::Min));
-                btn_ui.label(
-                    egui::RichText::new("🛠️ Actions")
-                        .color(CatppuccinTheme::FRAPPE.overlay0)
-                        .size(11.0),
-                );
+                btn_ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("🛠️ Actions")
+                            .color(p.overlay0)
+                            .size(11.0),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        self.render_theme_menu(ui);
+                        self.render_profile_menu(ui);
+                    });
+                });
                 btn_ui.add_space(4.0);
                 // Compute remaining height for the grid and derive button height to fill it exactly
                 let avail_h = btn_ui.available_height().max(1.0);
-                let rows: usize = 4; // currently 8 buttons in 2 columns -> 4 rows
+                let rows: usize = self.buttons.len().div_ceil(2).max(1); // two columns
                 let v_spacing = 8.0;
                 let button_height = ((avail_h - v_spacing * (rows.saturating_sub(1) as f32)) / rows as f32)
                     .clamp(24.0, 54.0);
+                // (label, accent, tooltip) per button; an odd last button spans the full row
+                let buttons_meta: Vec<(String, egui::Color32, String)> = self
+                    .buttons
+                    .iter()
+                    .map(|b| {
+                        let label = format!("{} {}", b.icon, b.label).trim().to_string();
+                        (label, Self::button_accent(b, &p), Self::button_tooltip(b))
+                    })
+                    .collect();
+                let mut clicked_button: Option<usize> = None;
 
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
@@ -1453,58 +3468,49 @@ impl App for AudioToolkitApp {
                         let col_w_right = (total_w - h_spacing - col_w_left).max(1.0);
                         let button_size_left = egui::vec2(col_w_left, button_height);
                         let button_size_right = egui::vec2(col_w_right, button_height);
+                        let button_size_full = egui::vec2(total_w, button_height);
 
                         let row_prepass = std::env::var("ATS_BTN_ROW_PREPASS")
                             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
                             .unwrap_or(true);
 
                         if row_prepass {
-                            // Paint one row-wide background per row, then render two half-width buttons without BG
-                            let rows_meta = [
-                                (("🔄 Restart All", CatppuccinTheme::FRAPPE.blue),   ("📁 File Manager", CatppuccinTheme::FRAPPE.lavender)),
-                                (("⚙️ Settings",    CatppuccinTheme::FRAPPE.sapphire),("🔧 Tools",        CatppuccinTheme::FRAPPE.peach)),
-                                (("📊 Analytics",   CatppuccinTheme::FRAPPE.green),  ("🔖 Bookmarks",   CatppuccinTheme::FRAPPE.pink)),
-                                (("📜 Scripts",     CatppuccinTheme::FRAPPE.mauve),  ("💡 Help",        CatppuccinTheme::FRAPPE.yellow)),
-                            ];
-
-                            for (i, ((l_label, l_acc), (r_label, r_acc))) in rows_meta.iter().enumerate() {
+                            // Paint one row-wide background per row, then render half-width buttons without BG
+                            let row_count = buttons_meta.len().div_ceil(2);
+                            for (i, pair) in buttons_meta.chunks(2).enumerate() {
                                 // Row rect and background with outer rounding on extremes
                                 let (row_rect, _) = ui.allocate_exact_size(egui::vec2(total_w, button_height), egui::Sense::hover());
                                 let mut rounding = egui::Rounding::ZERO;
                                 if i == 0 { rounding.nw = 4.0; rounding.ne = 4.0; }
-                                if i + 1 == rows_meta.len() { rounding.sw = 4.0; rounding.se = 4.0; }
-                                ui.painter().rect_filled(row_rect, rounding, CatppuccinTheme::FRAPPE.surface0);
-
-                                // Split row into left/right rects
-                                let left_rect = egui::Rect::from_min_size(row_rect.min, egui::vec2(col_w_left, button_height));
-                                let right_min = egui::pos2(row_rect.min.x + col_w_left, row_rect.min.y);
-                                let right_rect = egui::Rect::from_min_size(right_min, egui::vec2(col_w_right, button_height));
-
-                                // Left button
-                                let mut left_clicked = false;
-                                ui.allocate_ui_at_rect(left_rect, |ui| {
-                                    if Self::render_action_button_no_bg(ui, l_label, *l_acc, button_size_left, false)
-                                        .on_hover_text(match i { 0 => "Restart all terminals", 1 => "Open settings (coming soon)", 2 => "Performance analytics (coming soon)", _ => "Script management (coming soon)" })
-                                        .clicked() {
-                                        left_clicked = true;
+                                if i + 1 == row_count { rounding.sw = 4.0; rounding.se = 4.0; }
+                                ui.painter().rect_filled(row_rect, rounding, p.surface0);
+
+                                if let [(label, accent, tooltip)] = pair {
+                                    ui.allocate_ui_at_rect(row_rect, |ui| {
+                                        if Self::render_action_button_no_bg(ui, label, *accent, button_size_full, false)
+                                            .on_hover_text(tooltip)
+                                            .clicked() {
+                                            clicked_button = Some(i * 2);
+                                        }
+                                    });
+                                } else {
+                                    // Split row into left/right rects
+                                    let left_rect = egui::Rect::from_min_size(row_rect.min, egui::vec2(col_w_left, button_height));
+                                    let right_min = egui::pos2(row_rect.min.x + col_w_left, row_rect.min.y);
+                                    let right_rect = egui::Rect::from_min_size(right_min, egui::vec2(col_w_right, button_height));
+                                    for (j, (label, accent, tooltip)) in pair.iter().enumerate() {
+                                        let (rect, size) = if j == 0 { (left_rect, button_size_left) } else { (right_rect, button_size_right) };
+                                        ui.allocate_ui_at_rect(rect, |ui| {
+                                            if Self::render_action_button_no_bg(ui, label, *accent, size, j == 1)
+                                                .on_hover_text(tooltip)
+                                                .clicked() {
+                                                clicked_button = Some(i * 2 + j);
+                                            }
+                                        });
                                     }
-                                });
-                                if left_clicked {
-                                    if i == 0 { for tab in &mut self.tabs { tab.needs_restart = true; } }
                                 }
 
-                                // Right button
-                                let mut right_clicked = false;
-                                ui.allocate_ui_at_rect(right_rect, |ui| {
-                                    if Self::render_action_button_no_bg(ui, r_label, *r_acc, button_size_right, true)
-                                        .on_hover_text(match i { 0 => "Open file manager (coming soon)", 1 => "Developer tools (coming soon)", 2 => "Command bookmarks (coming soon)", _ => "Help & documentation (coming soon)" })
-                                        .clicked() {
-                                        right_clicked = true;
-                                    }
-                                });
-                                if right_clicked { /* placeholders */ }
-
-                                if i + 1 < rows_meta.len() { ui.add_space(spacing); }
+                                if i + 1 < row_count { ui.add_space(spacing); }
                             }
                         } else {
                             // Legacy Grid path (per-cell backgrounds)
@@ -1512,35 +3518,28 @@ impl App for AudioToolkitApp {
                                 .num_columns(2)
                                 .spacing([h_spacing, spacing])
                                 .show(ui, |ui| {
-                                    if Self::render_action_button(ui, "🔄 Restart All", CatppuccinTheme::FRAPPE.blue, button_size_left, false)
-                                        .on_hover_text("Restart all terminals").clicked() {
-                                        for tab in &mut self.tabs { tab.needs_restart = true; }
-                                    }
-                                    if Self::render_action_button(ui, "📁 File Manager", CatppuccinTheme::FRAPPE.lavender, button_size_right, true)
-                                        .on_hover_text("Open file manager (coming soon)").clicked() {
+                                    for (i, (label, accent, tooltip)) in buttons_meta.iter().enumerate() {
+                                        let is_right_col = i % 2 == 1;
+                                        let size = if is_right_col { button_size_right } else { button_size_left };
+                                        if Self::render_action_button(ui, label, *accent, size, is_right_col, &p)
+                                            .on_hover_text(tooltip)
+                                            .clicked() {
+                                            clicked_button = Some(i);
+                                        }
+                                        if is_right_col { ui.end_row(); }
                                     }
-                                    ui.end_row();
-                                    if Self::render_action_button(ui, "⚙️ Settings", CatppuccinTheme::FRAPPE.sapphire, button_size_left, false)
-                                        .on_hover_text("Open settings (coming soon)").clicked() {}
-                                    if Self::render_action_button(ui, "🔧 Tools", CatppuccinTheme::FRAPPE.peach, button_size_right, true)
-                                        .on_hover_text("Developer tools (coming soon)").clicked() {}
-                                    ui.end_row();
-                                    if Self::render_action_button(ui, "📊 Analytics", CatppuccinTheme::FRAPPE.green, button_size_left, false)
-                                        .on_hover_text("Performance analytics (coming soon)").clicked() {}
-                                    if Self::render_action_button(ui, "🔖 Bookmarks", CatppuccinTheme::FRAPPE.pink, button_size_right, true)
-                                        .on_hover_text("Command bookmarks (coming soon)").clicked() {}
-                                    ui.end_row();
-                                    if Self::render_action_button(ui, "📜 Scripts", CatppuccinTheme::FRAPPE.mauve, button_size_left, false)
-                                        .on_hover_text("Script management (coming soon)").clicked() {}
-                                    if Self::render_action_button(ui, "💡 Help", CatppuccinTheme::FRAPPE.yellow, button_size_right, true)
-                                        .on_hover_text("Help & documentation (coming soon)").clicked() {}
-                                    ui.end_row();
                                 });
                         }
                     });
+                if let Some(index) = clicked_button {
+                    self.run_button(ctx, index, false);
+                }
             });
 
         // Right cluster in a CentralPanel: top row (two terminals), bottom row (one terminal)
+        // File Manager must claim its side before the central panel takes the rest
+        self.render_file_manager(ctx);
+
         egui::CentralPanel::default()
             .frame(
                 egui::Frame::default()
@@ -1606,12 +3605,13 @@ impl App for AudioToolkitApp {
                 // Top-left terminal (tab 1 index)
                 let mut top_left_ui = ui.child_ui(left_rect, egui::Layout::top_down(egui::Align::Min));
                 if self.tabs.len() >= 2 {
+                    let metrics = self.cell_metrics(ctx, 1);
                     let tab = &mut self.tabs[1];
                     let is_focused = self.focused_terminal == 1;
                     let clicked = {
                         let mut clicked_local = false;
                         top_left_ui.push_id(("pane_scope", 1usize), |ui| {
-                            clicked_local = Self::render_terminal_panel(ui, tab, is_focused, 1, self.debug_overlay);
+                            clicked_local = Self::render_terminal_panel(ui, tab, is_focused, 1, self.debug_overlay, &metrics, &theme);
                         });
                         clicked_local
                     };
@@ -1624,12 +3624,13 @@ impl App for AudioToolkitApp {
                 // Top-right terminal (tab 2 index)
                 let mut top_right_ui = ui.child_ui(right_rect, egui::Layout::top_down(egui::Align::Min));
                 if self.tabs.len() >= 3 {
+                    let metrics = self.cell_metrics(ctx, 2);
                     let tab = &mut self.tabs[2];
                     let is_focused = self.focused_terminal == 2;
                     let clicked = {
                         let mut clicked_local = false;
                         top_right_ui.push_id(("pane_scope", 2usize), |ui| {
-                            clicked_local = Self::render_terminal_panel(ui, tab, is_focused, 2, self.debug_overlay);
+                            clicked_local = Self::render_terminal_panel(ui, tab, is_focused, 2, self.debug_overlay, &metrics, &theme);
                         });
                         clicked_local
                     };
@@ -1642,12 +3643,13 @@ impl App for AudioToolkitApp {
                 // Bottom terminal (tab 3 index)
                 let mut bottom_ui = ui.child_ui(bottom_rect, egui::Layout::top_down(egui::Align::Min));
                 if self.tabs.len() >= 4 {
+                    let metrics = self.cell_metrics(ctx, 3);
                     let tab = &mut self.tabs[3];
                     let is_focused = self.focused_terminal == 3;
                     let clicked = {
                         let mut clicked_local = false;
                         bottom_ui.push_id(("pane_scope", 3usize), |ui| {
-                            clicked_local = Self::render_terminal_panel(ui, tab, is_focused, 3, self.debug_overlay);
+                            clicked_local = Self::render_terminal_panel(ui, tab, is_focused, 3, self.debug_overlay, &metrics, &theme);
                         });
                         clicked_local
                     };
@@ -1728,14 +3730,14 @@ impl App for AudioToolkitApp {
 
                 // Paint visible dividers/handles
                 let handle_color_v = if v_resp.hovered() || v_resp.dragged() {
-                    CatppuccinTheme::FRAPPE.overlay0
+                    p.overlay0
                 } else {
-                    CatppuccinTheme::FRAPPE.surface1
+                    p.surface1
                 };
                 let handle_color_h = if h_resp.hovered() || h_resp.dragged() {
-                    CatppuccinTheme::FRAPPE.overlay0
+                    p.overlay0
                 } else {
-                    CatppuccinTheme::FRAPPE.surface1
+                    p.surface1
                 };
                 let painter = ui.painter();
                 painter.rect_filled(v_handle_rect, 2.0, handle_color_v.linear_multiply(0.35));
@@ -1765,12 +3767,76 @@ impl App for AudioToolkitApp {
         painter.vline(
             x,
             ctx.screen_rect().y_range(),
-            egui::Stroke { width: 1.0, color: CatppuccinTheme::FRAPPE.surface1 },
+            egui::Stroke { width: 1.0, color: p.surface1 },
         );
         
-        // Forward keyboard input to the currently focused terminal's PTY
-        if let Some(tab) = self.tabs.get_mut(self.focused_terminal) {
-            Self::handle_terminal_key_input_ctx(ctx, &mut tab.pty_writer);
+        // Ephemeral panes and overlays for the focused pane
+        self.render_ephemeral_panes(ctx);
+        self.handle_pane_controls();
+        self.render_button_confirmation(ctx);
+        self.render_control_confirmation(ctx);
+        self.render_profile_confirmation(ctx);
+        self.render_settings(ctx);
+        self.render_bookmarks(ctx);
+        self.render_scripts(ctx);
+        self.render_analytics(ctx);
+        self.render_recordings(ctx);
+        self.render_players(ctx);
+        self.render_help(ctx);
+        self.render_tools(ctx);
+        self.render_palette(ctx);
+        self.render_find_bar(ctx);
+        self.render_toasts(ctx);
+        if self.pending_paste.is_some() {
+            self.render_paste_confirmation(ctx);
+        }
+    }
+}
+
+/// Current working directory of process `pid`
+#[cfg(unix)]
+fn process_cwd(pid: i32) -> Option<std::path::PathBuf> {
+    if let Ok(path) = std::fs::read_link(format!("/proc/{}/cwd", pid)) {
+        return Some(path);
+    }
+    // No /proc on macOS; lsof reports the cwd as an `n`-prefixed field
+    let output = std::process::Command::new("lsof")
+        .args(["-a", "-p", &pid.to_string(), "-d", "cwd", "-Fn"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix('n'))
+        .map(std::path::PathBuf::from)
+}
+
+/// Command name of process `pid`
+#[cfg(unix)]
+fn process_name(pid: i32) -> Option<String> {
+    let name = match std::fs::read_to_string(format!("/proc/{}/comm", pid)) {
+        Ok(comm) => comm,
+        // No /proc on macOS
+        Err(_) => {
+            let output = std::process::Command::new("ps")
+                .args(["-o", "comm=", "-p", &pid.to_string()])
+                .output()
+                .ok()?;
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        }
+    };
+    // ps may print a full path, and login shells start with '-'
+    let name = name.trim().rsplit('/').next()?.trim_start_matches('-');
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Reads text from the system clipboard, reporting failures on stderr
+fn read_clipboard() -> Option<String> {
+    match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+        Ok(text) if !text.is_empty() => Some(text),
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("[CLIPBOARD] Failed to read clipboard: {}", e);
+            None
         }
     }
-}
\ No newline at end of file
+}
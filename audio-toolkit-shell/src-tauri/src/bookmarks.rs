@@ -0,0 +1,727 @@
+//! # Bookmarks Module
+//!
+//! This module implements the command bookmarks library opened from the
+//! "🔖 Bookmarks" button: named command snippets stored in `bookmarks.toml`,
+//! a searchable picker, and placeholder expansion.
+//!
+//! ## File Format
+//!
+//! ```toml
+//! [[bookmarks]]
+//! name = "Normalize to target loudness"
+//! description = "EBU R128 pass on one file"
+//! command = "ffmpeg -i {file} -af loudnorm=I={lufs} {dir}/normalized.wav"
+//! defaults = { lufs = "-23" }
+//! ```
+//!
+//! ## Placeholders
+//!
+//! `{name}` placeholders are filled in before the command is typed. `{dir}`
+//! defaults to the focused pane's working directory and `{file}` to the first
+//! file selected in the File Manager; both are shell-quoted when expanded. The
+//! working directory is looked up in the background and fills an empty `{dir}`
+//! field when it arrives.
+//! Other values are inserted as typed. Write `{{` and `}}` for literal braces.
+//!
+//! ## Saving
+//!
+//! Adding or deleting a bookmark in the picker edits `bookmarks.toml` in place:
+//! entries that stay keep their text and comments. A file that does not parse is
+//! never written; the picker shows the error and stays read-only until it is fixed.
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crate::theme::Palette;
+
+/// Placeholders whose values are paths and get shell-quoted
+const PATH_PLACEHOLDERS: &[&str] = &["file", "dir"];
+
+/// A named command snippet
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct Bookmark {
+    pub name: String,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// Default values for placeholders, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub defaults: BTreeMap<String, String>,
+}
+
+impl Bookmark {
+    /// True when every whitespace-separated term of `query` occurs in the
+    /// name, description or command (case-insensitive)
+    pub fn matches(&self, query: &str) -> bool {
+        matches_query(query, &[&self.name, &self.description, &self.command])
+    }
+}
+
+/// True when every whitespace-separated term of `query` occurs in one of `fields`
+/// (case-insensitive); shared by the bookmark and script searches
+pub fn matches_query(query: &str, fields: &[&str]) -> bool {
+    let haystack = fields.join("\n").to_lowercase();
+    query.split_whitespace().all(|term| haystack.contains(&term.to_lowercase()))
+}
+
+/// Defaults for `{dir}` and `{file}`: the focused pane's working directory and
+/// the first file selected in the File Manager
+pub fn path_context(dir: Option<PathBuf>, file: Option<PathBuf>) -> BTreeMap<String, String> {
+    let mut context = BTreeMap::new();
+    for (name, value) in [("dir", dir), ("file", file)] {
+        if let Some(value) = value {
+            context.insert(name.to_string(), value.to_string_lossy().into_owned());
+        }
+    }
+    context
+}
+
+/// Starts looking up the `{dir}` default; `find` runs on a background thread
+/// because looking up a process's folder can be slow (`lsof` on macOS)
+pub fn spawn_dir_lookup(find: impl FnOnce() -> Option<PathBuf> + Send + 'static) -> Receiver<Option<PathBuf>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(find());
+    });
+    rx
+}
+
+/// Takes the folder from a [`spawn_dir_lookup`] once it arrives: it becomes the
+/// `{dir}` default in `context` and fills an empty `{dir}` row of `values`
+///
+/// # Returns
+///
+/// Whether the lookup is still running
+pub fn poll_dir_lookup(
+    pending: &mut Option<Receiver<Option<PathBuf>>>,
+    context: &mut BTreeMap<String, String>,
+    values: Option<&mut Vec<(String, String)>>,
+) -> bool {
+    let Some(rx) = pending else {
+        return false;
+    };
+    let dir = match rx.try_recv() {
+        Ok(dir) => dir,
+        Err(TryRecvError::Empty) => return true,
+        Err(TryRecvError::Disconnected) => None,
+    };
+    *pending = None;
+    if let Some(dir) = dir {
+        let dir = dir.to_string_lossy().into_owned();
+        for (_, value) in values.into_iter().flatten().filter(|(name, value)| name == "dir" && value.is_empty()) {
+            *value = dir.clone();
+        }
+        context.insert("dir".to_string(), dir);
+    }
+    false
+}
+
+/// Form rows for the placeholders of `command`, prefilled from `defaults`, then `context`
+pub fn placeholder_values(
+    command: &str,
+    defaults: &BTreeMap<String, String>,
+    context: &BTreeMap<String, String>,
+) -> Vec<(String, String)> {
+    placeholders(command)
+        .into_iter()
+        .map(|name| {
+            let value = defaults.get(&name).or_else(|| context.get(&name)).cloned().unwrap_or_default();
+            (name, value)
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+struct BookmarkFile {
+    #[serde(default)]
+    bookmarks: Vec<Bookmark>,
+}
+
+/// Loads bookmarks from `path`; a missing file is an empty library
+pub fn load_bookmarks(path: &Path) -> Result<Vec<Bookmark>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => toml::from_str::<BookmarkFile>(&content)
+            .map(|file| file.bookmarks)
+            .map_err(|e| format!("Error parsing {:?}: {}", path, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read {:?}: {}", path, e)),
+    }
+}
+
+/// Writes `bookmarks` to `path`, keeping the comments of the entries already there
+///
+/// Refuses to touch a file that does not parse.
+pub fn save_bookmarks(path: &Path, bookmarks: &[Bookmark]) -> Result<(), String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {:?}: {}", path, e)),
+    };
+    let updated = update_bookmarks_text(&content, bookmarks).map_err(|e| format!("Not saving {:?}: {}", path, e))?;
+    fs::write(path, updated).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Applies `bookmarks` to the TOML text `content`
+///
+/// Entries of `content` that are still in `bookmarks` are kept as written, the
+/// others are deleted, and new bookmarks are appended. The result is checked to
+/// parse back into `bookmarks`.
+pub fn update_bookmarks_text(content: &str, bookmarks: &[Bookmark]) -> Result<String, String> {
+    toml::from_str::<BookmarkFile>(content).map_err(|e| format!("bookmarks file does not parse: {}", e))?;
+    let mut doc: toml_edit::DocumentMut = content.parse().map_err(|e| format!("bookmarks file is not valid TOML: {}", e))?;
+    let mut added: Vec<&Bookmark> = bookmarks.iter().collect();
+    if let Some(array) = doc.get_mut("bookmarks").and_then(toml_edit::Item::as_array_of_tables_mut) {
+        array.retain(|table| {
+            let existing = toml::from_str::<Bookmark>(&table.to_string()).ok();
+            match added.iter().position(|b| Some(*b) == existing.as_ref()) {
+                Some(i) => {
+                    added.remove(i);
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+    if !added.is_empty() {
+        let file = BookmarkFile { bookmarks: added.into_iter().cloned().collect() };
+        let text = toml::to_string(&file).map_err(|e| format!("failed to serialize bookmarks: {}", e))?;
+        let new_doc: toml_edit::DocumentMut = text.parse().map_err(|e| format!("failed to serialize bookmarks: {}", e))?;
+        if let Some(new_tables) = new_doc.get("bookmarks").and_then(toml_edit::Item::as_array_of_tables) {
+            match doc.get_mut("bookmarks").and_then(toml_edit::Item::as_array_of_tables_mut) {
+                Some(array) => new_tables.iter().for_each(|table| array.push(table.clone())),
+                None => {
+                    doc.insert("bookmarks", toml_edit::Item::ArrayOfTables(new_tables.clone()));
+                }
+            }
+        }
+    }
+    let updated = doc.to_string();
+    match toml::from_str::<BookmarkFile>(&updated) {
+        Ok(file) if file.bookmarks == bookmarks => Ok(updated),
+        Ok(_) => Err("bookmarks could not be updated without reordering the file".to_string()),
+        Err(e) => Err(format!("updated bookmarks would not parse: {}", e)),
+    }
+}
+
+/// Placeholder names in `command`, in order of first appearance
+pub fn placeholders(command: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for segment in parse_template(command) {
+        if let Segment::Placeholder(name) = segment {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Replaces placeholders in `command` with `values`
+///
+/// `{file}` and `{dir}` values go through `quote`; missing values expand to nothing.
+pub fn expand(command: &str, values: &BTreeMap<String, String>, quote: impl Fn(&str) -> String) -> String {
+    let mut out = String::with_capacity(command.len());
+    for segment in parse_template(command) {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Placeholder(name) => {
+                let value = values.get(name).map(String::as_str).unwrap_or("");
+                if PATH_PLACEHOLDERS.contains(&name) && !value.is_empty() {
+                    out.push_str(&quote(value));
+                } else {
+                    out.push_str(value);
+                }
+            }
+        }
+    }
+    out
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Splits a command into literal text and `{name}` placeholders
+fn parse_template(command: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = command;
+    while let Some(start) = rest.find(['{', '}']) {
+        let (before, from) = rest.split_at(start);
+        if !before.is_empty() {
+            segments.push(Segment::Text(before));
+        }
+        // Doubled braces are literal
+        if from.starts_with("{{") || from.starts_with("}}") {
+            segments.push(Segment::Text(&from[..1]));
+            rest = &from[2..];
+            continue;
+        }
+        let name_len = from[1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(from.len() - 1);
+        let name = &from[1..1 + name_len];
+        if from.starts_with('{') && !name.is_empty() && from[1 + name_len..].starts_with('}') {
+            segments.push(Segment::Placeholder(name));
+            rest = &from[name_len + 2..];
+        } else {
+            segments.push(Segment::Text(&from[..1]));
+            rest = &from[1..];
+        }
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+/// A bookmark chosen in the picker, ready to expand and type
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookmarkChoice {
+    pub command: String,
+    pub values: BTreeMap<String, String>,
+    /// Press Enter after typing
+    pub run: bool,
+}
+
+/// Placeholder form for the chosen bookmark
+struct Form {
+    index: usize,
+    values: Vec<(String, String)>,
+}
+
+/// State of the bookmarks picker window
+pub struct BookmarkPicker {
+    pub open: bool,
+    path: PathBuf,
+    bookmarks: Vec<Bookmark>,
+    query: String,
+    /// Highlighted row among the filtered bookmarks
+    cursor: usize,
+    form: Option<Form>,
+    /// Bookmark being added with "+ New"
+    draft: Option<Bookmark>,
+    /// Defaults for `{dir}` and `{file}` (see [`path_context`])
+    context: BTreeMap<String, String>,
+    /// `{dir}` default still being looked up (see [`spawn_dir_lookup`])
+    pending_dir: Option<Receiver<Option<PathBuf>>>,
+    error: Option<String>,
+    /// `bookmarks.toml` did not load; adding and deleting are disabled so it is not overwritten
+    read_only: bool,
+    focus_query: bool,
+}
+
+impl BookmarkPicker {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            path: PathBuf::new(),
+            bookmarks: Vec::new(),
+            query: String::new(),
+            cursor: 0,
+            form: None,
+            draft: None,
+            context: BTreeMap::new(),
+            pending_dir: None,
+            error: None,
+            read_only: false,
+            focus_query: false,
+        }
+    }
+
+    /// Opens the picker, reloading `path`
+    ///
+    /// `dir` and `file` become the defaults for `{dir}` and `{file}`.
+    pub fn open_with(&mut self, path: PathBuf, dir: Option<PathBuf>, file: Option<PathBuf>) {
+        match load_bookmarks(&path) {
+            Ok(bookmarks) => {
+                self.bookmarks = bookmarks;
+                self.error = None;
+                self.read_only = false;
+            }
+            Err(e) => {
+                eprintln!("[BOOKMARKS] {}", e);
+                self.bookmarks.clear();
+                self.error = Some(e);
+                self.read_only = true;
+            }
+        }
+        self.path = path;
+        self.context = path_context(dir, file);
+        self.pending_dir = None;
+        self.query.clear();
+        self.cursor = 0;
+        self.form = None;
+        self.draft = None;
+        self.focus_query = true;
+        self.open = true;
+    }
+
+    /// Opens the picker straight at the placeholder form of the bookmark named `name`
+    pub fn open_bookmark(&mut self, path: PathBuf, dir: Option<PathBuf>, file: Option<PathBuf>, name: &str) {
+        self.open_with(path, dir, file);
+        if let Some(index) = self.bookmarks.iter().position(|b| b.name == name) {
+            self.choose(index);
+        }
+    }
+
+    /// Looks up the `{dir}` default in the background, replacing the one given when opening
+    pub fn lookup_dir(&mut self, find: impl FnOnce() -> Option<PathBuf> + Send + 'static) {
+        self.pending_dir = Some(spawn_dir_lookup(find));
+    }
+
+    fn filtered(&self) -> Vec<usize> {
+        (0..self.bookmarks.len()).filter(|&i| self.bookmarks[i].matches(&self.query)).collect()
+    }
+
+    fn choose(&mut self, index: usize) {
+        let bookmark = &self.bookmarks[index];
+        let values = placeholder_values(&bookmark.command, &bookmark.defaults, &self.context);
+        self.form = Some(Form { index, values });
+        self.focus_query = true;
+    }
+
+    fn save(&mut self) {
+        if self.read_only {
+            return;
+        }
+        match save_bookmarks(&self.path, &self.bookmarks) {
+            Ok(()) => self.error = None,
+            Err(e) => {
+                eprintln!("[BOOKMARKS] {}", e);
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Draws the picker
+    ///
+    /// `quote` is used to preview the expanded command.
+    pub fn show(&mut self, ui: &mut egui::Ui, palette: &Palette, quote: impl Fn(&str) -> String) -> Option<BookmarkChoice> {
+        let values = self.form.as_mut().map(|form| &mut form.values);
+        if poll_dir_lookup(&mut self.pending_dir, &mut self.context, values) {
+            ui.ctx().request_repaint_after(Duration::from_millis(100));
+        }
+        if let Some(err) = &self.error {
+            ui.colored_label(palette.red, err);
+        }
+        if self.form.is_some() {
+            return self.show_form(ui, palette, quote);
+        }
+        if self.draft.is_some() {
+            self.show_draft(ui);
+            return None;
+        }
+
+        let filtered = self.filtered();
+        self.cursor = self.cursor.min(filtered.len().saturating_sub(1));
+        let mut chosen = None;
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text("Search bookmarks")
+                    .desired_width(ui.available_width() - 70.0),
+            );
+            if std::mem::take(&mut self.focus_query) {
+                response.request_focus();
+            }
+            if response.has_focus() {
+                ui.input(|i| {
+                    if i.key_pressed(egui::Key::ArrowDown) {
+                        self.cursor = (self.cursor + 1).min(filtered.len().saturating_sub(1));
+                    }
+                    if i.key_pressed(egui::Key::ArrowUp) {
+                        self.cursor = self.cursor.saturating_sub(1);
+                    }
+                });
+            }
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                chosen = filtered.get(self.cursor).copied();
+            }
+            let new = ui
+                .add_enabled(!self.read_only, egui::Button::new("+ New"))
+                .on_disabled_hover_text("Fix the bookmarks file first");
+            if new.clicked() {
+                self.draft = Some(Bookmark::default());
+            }
+        });
+        ui.separator();
+
+        let mut delete = None;
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            if self.bookmarks.is_empty() && !self.read_only {
+                ui.weak(format!("No bookmarks yet. Add one with \"+ New\" or edit {}", self.path.display()));
+            }
+            for (row, &index) in filtered.iter().enumerate() {
+                let bookmark = &self.bookmarks[index];
+                ui.horizontal(|ui| {
+                    let response = ui.selectable_label(row == self.cursor, egui::RichText::new(&bookmark.name).strong());
+                    if response.clicked() {
+                        chosen = Some(index);
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let delete_button = ui.add_enabled(!self.read_only, egui::Button::new("🗑").small());
+                        if delete_button.on_hover_text("Delete bookmark").clicked() {
+                            delete = Some(index);
+                        }
+                    });
+                });
+                if !bookmark.description.is_empty() {
+                    ui.label(&bookmark.description);
+                }
+                ui.weak(egui::RichText::new(&bookmark.command).monospace());
+                ui.add_space(4.0);
+            }
+        });
+
+        if let Some(index) = delete {
+            self.bookmarks.remove(index);
+            self.save();
+        } else if let Some(index) = chosen {
+            self.choose(index);
+        }
+        None
+    }
+
+    fn show_form(&mut self, ui: &mut egui::Ui, palette: &Palette, quote: impl Fn(&str) -> String) -> Option<BookmarkChoice> {
+        let form = self.form.as_mut()?;
+        let bookmark = &self.bookmarks[form.index];
+        let mut result = None;
+        let mut back = false;
+
+        ui.heading(&bookmark.name);
+        let mut submit = false;
+        egui::Grid::new("bookmark_form").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+            for (i, (name, value)) in form.values.iter_mut().enumerate() {
+                ui.label(format!("{{{}}}", name));
+                let response = ui.add(egui::TextEdit::singleline(value).desired_width(f32::INFINITY));
+                if i == 0 && std::mem::take(&mut self.focus_query) {
+                    response.request_focus();
+                }
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    submit = true;
+                }
+                ui.end_row();
+            }
+        });
+        let values: BTreeMap<String, String> = form.values.iter().cloned().collect();
+        ui.add_space(4.0);
+        ui.label(egui::RichText::new(expand(&bookmark.command, &values, quote)).monospace().color(palette.green));
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            if ui.button("Insert").on_hover_text("Type the command without pressing Enter").clicked() || submit {
+                result = Some(false);
+            }
+            if ui.button("Run").on_hover_text("Type the command and press Enter").clicked() {
+                result = Some(true);
+            }
+            if ui.button("Back").clicked() {
+                back = true;
+            }
+        });
+        if form.values.is_empty() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            result = Some(false);
+        }
+
+        let command = bookmark.command.clone();
+        if back {
+            self.form = None;
+            self.focus_query = true;
+        }
+        result.map(|run| {
+            self.open = false;
+            self.form = None;
+            BookmarkChoice { command, values, run }
+        })
+    }
+
+    fn show_draft(&mut self, ui: &mut egui::Ui) {
+        let Some(draft) = self.draft.as_mut() else {
+            return;
+        };
+        let mut save = false;
+        let mut cancel = false;
+        egui::Grid::new("bookmark_draft").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+            ui.label("Name");
+            ui.text_edit_singleline(&mut draft.name);
+            ui.end_row();
+            ui.label("Description");
+            ui.text_edit_singleline(&mut draft.description);
+            ui.end_row();
+            ui.label("Command");
+            ui.add(egui::TextEdit::singleline(&mut draft.command).hint_text("ffmpeg -i {file} {dir}/out.mp3"));
+            ui.end_row();
+        });
+        ui.horizontal(|ui| {
+            let valid = !draft.name.trim().is_empty() && !draft.command.trim().is_empty();
+            if ui.add_enabled(valid, egui::Button::new("Save")).clicked() {
+                save = true;
+            }
+            if ui.button("Cancel").clicked() {
+                cancel = true;
+            }
+        });
+        if save {
+            if let Some(draft) = self.draft.take() {
+                self.bookmarks.push(draft);
+                self.save();
+            }
+        } else if cancel {
+            self.draft = None;
+        }
+    }
+}
+
+/// Simplified shell quoting for the expected strings of tests
+#[cfg(test)]
+pub fn test_quote(s: &str) -> String {
+    format!("'{}'", s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_quote as quote;
+
+    #[test]
+    fn test_placeholders_and_expand() {
+        let command = "ffmpeg -i {file} -af loudnorm=I={lufs} {dir}/out.wav {{raw}} {file}";
+        assert_eq!(placeholders(command), vec!["file", "lufs", "dir"]);
+
+        let mut values = BTreeMap::new();
+        values.insert("file".to_string(), "/audio/my take.wav".to_string());
+        values.insert("lufs".to_string(), "-23".to_string());
+        assert_eq!(
+            expand(command, &values, quote),
+            "ffmpeg -i '/audio/my take.wav' -af loudnorm=I=-23 /out.wav {raw} '/audio/my take.wav'"
+        );
+
+        // Stray braces are kept as text
+        assert_eq!(placeholders("awk '{ print $1 }' { x}"), Vec::<String>::new());
+        assert_eq!(expand("awk '{ print $1 }'", &values, quote), "awk '{ print $1 }'");
+    }
+
+    #[test]
+    fn test_load_save_and_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bookmarks.toml");
+        assert!(load_bookmarks(&path).unwrap().is_empty());
+
+        let mut defaults = BTreeMap::new();
+        defaults.insert("lufs".to_string(), "-16".to_string());
+        let bookmarks = vec![
+            Bookmark { name: "Podcast loudness".into(), command: "norm --lufs {lufs} {file}".into(), description: String::new(), defaults },
+            Bookmark { name: "List WAVs".into(), command: "ls *.wav".into(), ..Bookmark::default() },
+        ];
+        save_bookmarks(&path, &bookmarks).unwrap();
+        let loaded = load_bookmarks(&path).unwrap();
+        assert_eq!(loaded, bookmarks);
+
+        assert!(loaded[0].matches("podcast NORM"));
+        assert!(!loaded[0].matches("podcast wav"));
+        assert!(loaded[1].matches(""));
+
+        fs::write(&path, "[[bookmarks]]\nname = 1").unwrap();
+        assert!(load_bookmarks(&path).is_err());
+    }
+
+    #[test]
+    fn test_save_keeps_comments_and_broken_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bookmarks.toml");
+        let original = r#"# Studio snippets
+
+# Loudness pass, agreed with the mastering room
+[[bookmarks]]
+name = "Loudness"
+command = "norm {file}"
+defaults = { lufs = "-23" }   # broadcast
+
+[[bookmarks]]
+name = "Old"
+command = "old-tool"
+"#;
+        fs::write(&path, original).unwrap();
+        let mut bookmarks = load_bookmarks(&path).unwrap();
+        bookmarks.remove(1);
+        let defaults = BTreeMap::from([("ext".to_string(), "wav".to_string())]);
+        bookmarks.push(Bookmark { name: "List".into(), command: "ls *.{ext}".into(), defaults, ..Bookmark::default() });
+        save_bookmarks(&path, &bookmarks).unwrap();
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(saved.starts_with("# Studio snippets\n\n# Loudness pass, agreed with the mastering room\n[[bookmarks]]"));
+        assert!(saved.contains("defaults = { lufs = \"-23\" }   # broadcast"));
+        assert!(!saved.contains("old-tool"));
+        assert_eq!(load_bookmarks(&path).unwrap(), bookmarks);
+
+        // A file that does not parse is never overwritten
+        let broken = "[[bookmarks]]\nname = \"hand-written\"\ncommand = \n";
+        fs::write(&path, broken).unwrap();
+        let mut picker = BookmarkPicker::new();
+        picker.open_with(path.clone(), None, None);
+        assert!(picker.read_only && picker.error.is_some());
+        picker.bookmarks.push(Bookmark { name: "n".into(), command: "c".into(), ..Bookmark::default() });
+        picker.save();
+        assert!(save_bookmarks(&path, &[]).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), broken);
+    }
+
+    #[test]
+    fn test_picker_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bookmarks.toml");
+        let mut defaults = BTreeMap::new();
+        defaults.insert("lufs".to_string(), "-16".to_string());
+        let bookmark = Bookmark { name: "n".into(), command: "x {dir} {file} {lufs} {out}".into(), description: String::new(), defaults };
+        save_bookmarks(&path, &[bookmark]).unwrap();
+
+        let mut picker = BookmarkPicker::new();
+        picker.open_with(path, Some(PathBuf::from("/work")), None);
+        picker.choose(0);
+        let values = &picker.form.as_ref().unwrap().values;
+        assert_eq!(
+            values,
+            &vec![
+                ("dir".to_string(), "/work".to_string()),
+                ("file".to_string(), String::new()),
+                ("lufs".to_string(), "-16".to_string()),
+                ("out".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dir_arrives_in_background() {
+        let (tx, rx) = mpsc::channel();
+        let mut pending = Some(spawn_dir_lookup(move || rx.recv().ok()));
+        let mut context = path_context(None, Some(PathBuf::from("/a.wav")));
+        let mut values = placeholder_values("x {dir} {file}", &BTreeMap::new(), &context);
+        assert!(poll_dir_lookup(&mut pending, &mut context, Some(&mut values)));
+        assert_eq!(values[0], ("dir".to_string(), String::new()));
+
+        tx.send(PathBuf::from("/work")).unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while poll_dir_lookup(&mut pending, &mut context, Some(&mut values)) {
+            assert!(std::time::Instant::now() < deadline, "lookup did not finish");
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(values[0], ("dir".to_string(), "/work".to_string()));
+        assert_eq!(context["dir"], "/work");
+        assert!(pending.is_none());
+
+        // A typed value is kept
+        let mut pending = Some(spawn_dir_lookup(|| Some(PathBuf::from("/elsewhere"))));
+        values[0].1 = "/typed".to_string();
+        while poll_dir_lookup(&mut pending, &mut context, Some(&mut values)) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(values[0].1, "/typed");
+        assert_eq!(context["dir"], "/elsewhere");
+    }
+}
@@ -0,0 +1,422 @@
+//! # Cast Module
+//!
+//! This module records a pane's output with timing in the
+//! [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/) format and
+//! plays recordings back through a [`TerminalEmulator`].
+//!
+//! ## Format
+//!
+//! A `.cast` file is newline-delimited JSON: a header object, then one
+//! `[time, code, data]` array per event, where `time` is seconds since the
+//! start. Recordings write `"o"` (output) and `"r"` (resize, `"COLSxROWS"`)
+//! events; keystrokes are not recorded. Files play in `asciinema play` and
+//! the web player as well as in the built-in player.
+//!
+//! ## Playback
+//!
+//! [`Player`] feeds output events into its own emulator, so playback never
+//! touches a running pane. Pauses longer than the header's `idle_time_limit`
+//! are shortened to it. The test-only `replay` applies a whole file at once for
+//! the emulator's regression tests.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::terminal::TerminalEmulator;
+use crate::theme::Palette;
+
+/// Speeds offered by the player
+pub const SPEEDS: [f64; 4] = [0.5, 1.0, 2.0, 4.0];
+
+/// First line of a `.cast` file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CastHeader {
+    pub version: u32,
+    pub width: usize,
+    pub height: usize,
+    /// Unix time the recording started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// Longest pause kept during playback, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_time_limit: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl CastHeader {
+    pub fn new(cols: usize, rows: usize) -> Self {
+        Self {
+            version: 2,
+            width: cols,
+            height: rows,
+            timestamp: None,
+            idle_time_limit: None,
+            title: None,
+            env: BTreeMap::new(),
+        }
+    }
+}
+
+/// One `[time, code, data]` line
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastEvent {
+    pub time: f64,
+    /// `"o"` output, `"i"` input, `"r"` resize or `"m"` marker
+    pub code: String,
+    pub data: String,
+}
+
+/// A parsed recording
+#[derive(Debug, Clone)]
+pub struct Cast {
+    pub header: CastHeader,
+    pub events: Vec<CastEvent>,
+}
+
+/// Parses the contents of a `.cast` file
+pub fn parse(content: &str) -> Result<Cast, String> {
+    let mut lines = content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let (_, first) = lines.next().ok_or("Empty recording")?;
+    let header: CastHeader = serde_json::from_str(first).map_err(|e| format!("Invalid header: {}", e))?;
+    if header.version != 2 {
+        return Err(format!("Unsupported asciicast version {}", header.version));
+    }
+    let mut events = Vec::new();
+    for (n, line) in lines {
+        let (time, code, data): (f64, String, String) =
+            serde_json::from_str(line).map_err(|e| format!("Line {}: {}", n + 1, e))?;
+        events.push(CastEvent { time, code, data });
+    }
+    Ok(Cast { header, events })
+}
+
+/// Reads and parses a `.cast` file
+pub fn load(path: &Path) -> Result<Cast, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Cannot read {:?}: {}", path, e))?;
+    parse(&content).map_err(|e| format!("{:?}: {}", path, e))
+}
+
+/// `"COLSxROWS"` of a resize event as `(rows, cols)`
+fn parse_size(data: &str) -> Option<(usize, usize)> {
+    let (cols, rows) = data.split_once('x')?;
+    let (cols, rows) = (cols.trim().parse().ok()?, rows.trim().parse().ok()?);
+    (cols > 0 && rows > 0).then_some((rows, cols))
+}
+
+/// Applies one event to `emulator`; events other than output and resize are ignored
+fn apply(event: &CastEvent, emulator: &mut TerminalEmulator) {
+    match event.code.as_str() {
+        "o" => emulator.process_ansi_data(&event.data),
+        "r" => {
+            if let Some((rows, cols)) = parse_size(&event.data) {
+                emulator.resize(rows, cols);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Plays a whole recording into `emulator` without timing (used by the emulator tests)
+#[cfg(test)]
+pub fn replay(cast: &Cast, emulator: &mut TerminalEmulator) {
+    for event in &cast.events {
+        apply(event, emulator);
+    }
+}
+
+/// Writes a pane's output to a `.cast` file as it arrives
+#[derive(Debug)]
+pub struct Recorder {
+    file: File,
+    path: PathBuf,
+    started: Instant,
+}
+
+impl Recorder {
+    /// Creates `path` (and its folder) and writes the header
+    pub fn start(path: PathBuf, header: &CastHeader) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Cannot create {:?}: {}", dir, e))?;
+        }
+        let line = serde_json::to_string(header).map_err(|e| e.to_string())?;
+        let mut file = File::create(&path).map_err(|e| format!("Cannot write {:?}: {}", path, e))?;
+        writeln!(file, "{}", line).map_err(|e| format!("Cannot write {:?}: {}", path, e))?;
+        Ok(Self { file, path, started: Instant::now() })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> Result<(), String> {
+        // Microsecond precision, as asciinema writes it
+        let time = (self.started.elapsed().as_secs_f64() * 1e6).round() / 1e6;
+        let line = serde_json::to_string(&(time, code, data)).map_err(|e| e.to_string())?;
+        writeln!(self.file, "{}", line).map_err(|e| format!("Cannot write {:?}: {}", self.path, e))
+    }
+
+    /// Records a chunk of output
+    pub fn output(&mut self, data: &str) -> Result<(), String> {
+        self.event("o", data)
+    }
+
+    /// Records a change of the pane's grid size
+    pub fn resize(&mut self, rows: usize, cols: usize) -> Result<(), String> {
+        self.event("r", &format!("{}x{}", cols, rows))
+    }
+}
+
+/// Timed playback of a recording into its own emulator
+pub struct Player {
+    pub title: String,
+    pub path: PathBuf,
+    /// Events with pauses above the idle limit shortened
+    events: Vec<CastEvent>,
+    header: CastHeader,
+    pub emulator: TerminalEmulator,
+    /// Index of the next event to apply
+    next: usize,
+    /// Playback position in seconds
+    clock: f64,
+    pub speed: f64,
+    pub paused: bool,
+}
+
+impl Player {
+    pub fn new(path: PathBuf, cast: Cast) -> Self {
+        let title = cast
+            .header
+            .title
+            .clone()
+            .unwrap_or_else(|| path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default());
+        let mut events = cast.events;
+        if let Some(limit) = cast.header.idle_time_limit.filter(|l| *l > 0.0) {
+            let mut shift = 0.0;
+            let mut last = 0.0;
+            for event in &mut events {
+                let gap = event.time - last;
+                last = event.time;
+                if gap > limit {
+                    shift += gap - limit;
+                }
+                event.time -= shift;
+            }
+        }
+        let emulator = TerminalEmulator::new(cast.header.height.max(1), cast.header.width.max(1));
+        Self { title, path, events, header: cast.header, emulator, next: 0, clock: 0.0, speed: 1.0, paused: false }
+    }
+
+    /// Length of the recording in seconds, after idle compression
+    pub fn duration(&self) -> f64 {
+        self.events.last().map(|e| e.time).unwrap_or(0.0)
+    }
+
+    pub fn position(&self) -> f64 {
+        self.clock
+    }
+
+    pub fn finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+
+    /// Moves playback forward by `dt` wall-clock seconds at the current speed
+    pub fn advance(&mut self, dt: f64) {
+        if self.paused || self.finished() {
+            return;
+        }
+        self.clock = (self.clock + dt * self.speed).min(self.duration());
+        while let Some(event) = self.events.get(self.next).filter(|e| e.time <= self.clock) {
+            apply(event, &mut self.emulator);
+            self.next += 1;
+        }
+    }
+
+    /// Starts again from a blank screen
+    pub fn rewind(&mut self) {
+        self.emulator = TerminalEmulator::new(self.header.height.max(1), self.header.width.max(1));
+        self.next = 0;
+        self.clock = 0.0;
+    }
+
+    /// Jumps to `time` seconds, replaying from the start when going back
+    pub fn seek(&mut self, time: f64) {
+        if time < self.clock {
+            self.rewind();
+        }
+        let (speed, paused) = (self.speed, self.paused);
+        self.speed = 1.0;
+        self.paused = false;
+        self.advance(time - self.clock);
+        self.speed = speed;
+        self.paused = paused;
+        self.clock = time.clamp(0.0, self.duration());
+    }
+}
+
+/// Picker listing the recordings in the log folder
+pub struct RecordingPicker {
+    pub open: bool,
+    dir: PathBuf,
+    /// `.cast` files, newest first
+    files: Vec<PathBuf>,
+    /// Path typed by hand, for recordings elsewhere
+    path: String,
+    error: Option<String>,
+}
+
+impl RecordingPicker {
+    pub fn new() -> Self {
+        Self { open: false, dir: PathBuf::new(), files: Vec::new(), path: String::new(), error: None }
+    }
+
+    /// Opens the picker, listing the `.cast` files in `dir`
+    pub fn open_in(&mut self, dir: PathBuf) {
+        let mut files: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "cast"))
+            .map(|entry| {
+                let modified = entry.metadata().and_then(|m| m.modified()).unwrap_or(std::time::UNIX_EPOCH);
+                (modified, entry.path())
+            })
+            .collect();
+        files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        self.files = files.into_iter().map(|(_, path)| path).collect();
+        self.dir = dir;
+        self.error = None;
+        self.open = true;
+    }
+
+    /// Reports a recording that failed to load
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Draws the picker; returns the recording to play
+    pub fn show(&mut self, ui: &mut egui::Ui, palette: &Palette) -> Option<PathBuf> {
+        if let Some(err) = &self.error {
+            ui.colored_label(palette.red, err);
+        }
+        let mut chosen = None;
+        ui.label(egui::RichText::new(self.dir.display().to_string()).small().color(palette.subtext0));
+        egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+            if self.files.is_empty() {
+                ui.label(egui::RichText::new("No recordings yet. Use \u{201c}Start recording\u{201d} in a pane's menu.").italics());
+            }
+            for path in &self.files {
+                let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                if ui.selectable_label(false, format!("▶ {}", name)).clicked() {
+                    chosen = Some(path.clone());
+                }
+            }
+        });
+        ui.separator();
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.path)
+                    .hint_text("Path to a .cast file")
+                    .desired_width(ui.available_width() - 60.0),
+            );
+            let enter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui.button("Play").clicked() || enter) && !self.path.trim().is_empty() {
+                chosen = Some(PathBuf::from(self.path.trim()));
+            }
+        });
+        chosen
+    }
+}
+
+/// `m:ss` for player positions
+pub fn format_position(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    format!("{}:{:02}", total / 60, total % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = concat!(
+        r#"{"version": 2, "width": 20, "height": 3, "timestamp": 1700000000, "idle_time_limit": 1.0, "title": "Mixer"}"#,
+        "\n",
+        r#"[0.5, "o", "hello "]"#,
+        "\n",
+        r#"[0.75, "i", "x"]"#,
+        "\n",
+        r#"[5.75, "o", "\u001b[31mworld\u001b[0m"]"#,
+        "\n",
+        r#"[6.0, "r", "10x2"]"#,
+        "\n",
+    );
+
+    #[test]
+    fn test_parse_and_replay() {
+        let cast = parse(SAMPLE).expect("valid cast");
+        assert_eq!(cast.header.width, 20);
+        assert_eq!(cast.header.title.as_deref(), Some("Mixer"));
+        assert_eq!(cast.events.len(), 4);
+        assert_eq!(cast.events[2].data, "\x1b[31mworld\x1b[0m");
+
+        let mut emulator = TerminalEmulator::new(3, 20);
+        replay(&cast, &mut emulator);
+        assert_eq!(emulator.size(), (2, 10));
+        assert!(emulator.screen_text().starts_with("hello worl"));
+
+        assert!(parse(r#"{"version": 1, "width": 80, "height": 24}"#).is_err());
+        assert!(parse("").is_err());
+        assert!(parse(&format!("{}[1.0, \"o\"]\n", SAMPLE)).is_err());
+    }
+
+    #[test]
+    fn test_recorder_roundtrip() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("logs").join("mixer.cast");
+        let mut header = CastHeader::new(80, 24);
+        header.title = Some("Mixer".to_string());
+        let mut recorder = Recorder::start(path.clone(), &header).expect("recorder");
+        recorder.output("line \"one\"\r\n\x1b[1mbold\x1b[0m").unwrap();
+        recorder.resize(30, 100).unwrap();
+        drop(recorder);
+
+        let cast = load(&path).expect("valid cast");
+        assert_eq!(cast.header, header);
+        assert_eq!(cast.events[0].code, "o");
+        assert_eq!(cast.events[0].data, "line \"one\"\r\n\x1b[1mbold\x1b[0m");
+        assert_eq!(cast.events[1].data, "100x30");
+        assert!(cast.events[0].time <= cast.events[1].time);
+    }
+
+    #[test]
+    fn test_player_compresses_idle_and_advances() {
+        let mut player = Player::new(PathBuf::from("mixer.cast"), parse(SAMPLE).unwrap());
+        assert_eq!(player.title, "Mixer");
+        // The 5 s gap before "world" is cut to the 1 s idle limit
+        assert_eq!(player.duration(), 2.0);
+
+        player.advance(0.6);
+        assert!(player.emulator.screen_text().starts_with("hello"));
+        assert!(!player.emulator.screen_text().contains("world"));
+
+        player.speed = 2.0;
+        player.advance(0.6);
+        assert!(player.emulator.screen_text().contains("world"));
+        assert!(!player.finished());
+
+        player.seek(0.1);
+        assert_eq!(player.position(), 0.1);
+        assert!(!player.emulator.screen_text().contains("hello"));
+        player.seek(10.0);
+        assert!(player.finished());
+        assert_eq!(player.emulator.size(), (2, 10));
+    }
+}
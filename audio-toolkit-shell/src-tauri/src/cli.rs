@@ -0,0 +1,240 @@
+//! # CLI Module
+//!
+//! This module parses the command line of the binary. Without arguments the app
+//! starts with `config.toml`; options let one machine run several differently
+//! configured instances:
+//!
+//! ```text
+//! audio-toolkit-shell --config /etc/ats/podcast.toml
+//! audio-toolkit-shell --profile dubbing --layout size=1600x900,top=0.7
+//! audio-toolkit-shell --tab "Normalizer=normalize-batch" --tab "Shell=bash"
+//! audio-toolkit-shell --config deploy.toml --validate-config
+//! ```
+//!
+//! `--tab` and `--layout` apply to this run only and are never written to the
+//! config file. `ctl` as the first argument runs the control client instead; see
+//! [`crate::control`].
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::{self, DndSettings, IdleSettings, LayoutOverride, LogMode, NotifySettings, Overrides, TabConfig, MAX_TABS};
+use crate::settings;
+use crate::theme::{self, Theme};
+
+pub const USAGE: &str = "\
+Usage: audio-toolkit-shell [options]
+       audio-toolkit-shell ctl <command> ...   (see: audio-toolkit-shell ctl --help)
+
+Options:
+  --config <file>          Use this file instead of config.toml; its folder holds logs, themes, ...
+  --profile <name>         Start with this profile ([profiles.<name>] or profiles/<name>.toml)
+  --tab \"title=command\"    Run these tabs instead of the configured ones (repeat, up to 4)
+  --layout <spec>          Window size and splits, e.g. size=1400x800,top=0.6,split=0.5
+  --validate-config        Check the configuration and exit (status 1 on errors)
+  --print-default-config   Print the first-run config.toml template and exit
+  -V, --version            Print the version and exit
+  -h, --help               Print this help and exit";
+
+/// What the binary was asked to do
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Start the app
+    Run(Overrides),
+    /// Check the configuration and exit
+    ValidateConfig(Overrides),
+    PrintDefaultConfig,
+    Version,
+    Help,
+    /// `ctl ...`: talk to a running instance
+    Ctl(Vec<String>),
+}
+
+/// Parses the arguments after the program name
+pub fn parse(args: &[String]) -> Result<Command, String> {
+    if args.first().map(String::as_str) == Some("ctl") {
+        return Ok(Command::Ctl(args[1..].to_vec()));
+    }
+    let mut overrides = Overrides::default();
+    let mut validate = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        // Both "--config file" and "--config=file"
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || inline.clone().or_else(|| args.next().cloned()).ok_or_else(|| format!("{} needs a value", flag));
+        match flag {
+            "--config" => {
+                let path = PathBuf::from(value()?);
+                // Relative paths are resolved now so the config folder does not depend on later cwd changes
+                let path = if path.is_relative() { env::current_dir().map(|d| d.join(&path)).unwrap_or(path) } else { path };
+                overrides.config_file = Some(path);
+            }
+            "--profile" => {
+                let name = value()?;
+                if name.trim().is_empty() || name.contains(['/', '\\']) {
+                    return Err(format!("invalid profile name {:?}", name));
+                }
+                overrides.profile = Some(name);
+            }
+            "--tab" => {
+                if overrides.tabs.len() == MAX_TABS {
+                    return Err(format!("at most {} --tab options", MAX_TABS));
+                }
+                overrides.tabs.push(parse_tab(&value()?)?);
+            }
+            "--layout" => overrides.layout = LayoutOverride::parse(&value()?).map_err(|e| format!("--layout: {}", e))?,
+            "--validate-config" => validate = true,
+            "--print-default-config" => return Ok(Command::PrintDefaultConfig),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-h" | "--help" => return Ok(Command::Help),
+            // Process serial number passed by older macOS when launched from Finder
+            _ if arg.starts_with("-psn_") => {}
+            _ => return Err(format!("unknown argument {:?}", arg)),
+        }
+    }
+    Ok(if validate { Command::ValidateConfig(overrides) } else { Command::Run(overrides) })
+}
+
+/// Parses `--tab "title=command"`
+fn parse_tab(spec: &str) -> Result<TabConfig, String> {
+    let (title, command) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("--tab must be \"title=command\", got {:?}", spec))?;
+    let (title, command) = (title.trim(), command.trim());
+    if title.is_empty() || command.is_empty() {
+        return Err(format!("--tab needs a title and a command, got {:?}", spec));
+    }
+    Ok(TabConfig {
+        title: title.to_string(),
+        command: command.to_string(),
+        auto_restart_on_success: false,
+        success_patterns: vec![],
+        dnd: DndSettings::default(),
+        log: LogMode::Off,
+        notify: NotifySettings::default(),
+        idle: IdleSettings::default(),
+    })
+}
+
+/// `--validate-config`: prints the problems in the configuration; returns the exit code
+pub fn validate_config() -> i32 {
+    let path = config::config_file_path();
+    let config = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| config::parse_config(&content));
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}: {}", path.display(), e);
+            return 1;
+        }
+    };
+    let mut themes = Theme::builtins();
+    themes.extend(theme::load_user_themes(&config.app.themes_path()));
+    let problems = settings::validate(&config, &themes);
+    for problem in &problems {
+        eprintln!("{}: {}", path.display(), problem);
+    }
+    if problems.is_empty() {
+        println!("{}: OK", path.display());
+        0
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_parse_run_options() {
+        let Ok(Command::Run(overrides)) = parse_args(&[
+            "--config=/etc/ats/podcast.toml",
+            "--profile",
+            "dubbing",
+            "--tab",
+            "Normalizer = normalize-batch --lufs -23",
+            "--layout",
+            "size=1600x900,top=0.7",
+            "-psn_0_12345",
+        ]) else {
+            panic!("expected run");
+        };
+        assert_eq!(overrides.config_file, Some(PathBuf::from("/etc/ats/podcast.toml")));
+        assert_eq!(overrides.profile.as_deref(), Some("dubbing"));
+        assert_eq!(overrides.tabs[0].title, "Normalizer");
+        assert_eq!(overrides.tabs[0].command, "normalize-batch --lufs -23");
+        assert_eq!(overrides.layout.window_width, Some(1600.0));
+        assert_eq!(overrides.layout.right_top_fraction, Some(0.7));
+        assert_eq!(overrides.layout.right_top_hsplit_fraction, None);
+
+        assert_eq!(parse_args(&[]), Ok(Command::Run(Overrides::default())));
+        assert!(matches!(parse_args(&["--config", "local.toml"]), Ok(Command::Run(o)) if o.config_file.as_ref().is_some_and(|p| p.is_absolute())));
+    }
+
+    #[test]
+    fn test_parse_other_commands_and_errors() {
+        assert_eq!(parse_args(&["--version"]), Ok(Command::Version));
+        assert_eq!(parse_args(&["--print-default-config"]), Ok(Command::PrintDefaultConfig));
+        assert_eq!(parse_args(&["ctl", "list", "--json"]), Ok(Command::Ctl(vec!["list".into(), "--json".into()])));
+        assert!(matches!(parse_args(&["--validate-config", "--profile", "a"]), Ok(Command::ValidateConfig(_))));
+
+        assert!(parse_args(&["--tab", "no-equals"]).is_err());
+        assert!(parse_args(&["--tab", "=bash"]).is_err());
+        assert!(parse_args(&["--tab", "a=1", "--tab", "b=2", "--tab", "c=3", "--tab", "d=4", "--tab", "e=5"]).is_err());
+        assert!(parse_args(&["--layout", "top=0.95"]).is_err());
+        assert!(parse_args(&["--layout", "size=big"]).is_err());
+        assert!(parse_args(&["--layout", "zoom=2"]).is_err());
+        assert!(parse_args(&["--profile", "../x"]).is_err());
+        assert!(parse_args(&["--config"]).is_err());
+        assert!(parse_args(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_overrides_apply_to_config() {
+        let base = format!(
+            "{}{}",
+            config::DEFAULT_CONFIG_TEMPLATE,
+            r#"
+[profiles.delivery.app]
+right_top_fraction = 0.5
+
+[[profiles.delivery.tabs]]
+title = "Delivery"
+command = "deliver"
+auto_restart_on_success = false
+success_patterns = []
+
+[profiles.broken.app]
+window_width = "wide"
+"#
+        );
+        let base = base.as_str();
+        let profile = "delivery";
+        let overrides = Overrides {
+            layout: LayoutOverride::parse("width=1000").unwrap(),
+            ..Default::default()
+        };
+        let config = config::compose_config(base, Some(profile), &overrides).expect("valid config");
+        assert_eq!(config.tabs.len(), 1);
+        assert_eq!(config.tabs[0].title, "Delivery");
+        assert_eq!(config.app.right_top_fraction, 0.5);
+        assert_eq!(config.app.window_width, 1000.0);
+        assert_eq!(config.app.name, "Audio Toolkit Shell");
+
+        let tabs = Overrides { tabs: vec![parse_tab("Shell=zsh").unwrap()], ..Default::default() };
+        let config = config::compose_config(base, Some(profile), &tabs).unwrap();
+        assert_eq!(config.tabs[0].command, "zsh");
+        assert!(config::compose_config(base, Some("broken"), &tabs).is_err());
+        assert!(config::compose_config(base, Some("missing"), &tabs).is_err());
+    }
+}
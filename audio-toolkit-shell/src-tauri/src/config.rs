@@ -11,6 +11,18 @@
 //! override the directory for development/tests by setting `ATS_CONFIG_DIR` to a
 //! folder path before launching the app.
 //!
+//! Command-line options (see [`crate::cli`]) are applied on every load through
+//! [`Overrides`]: `--config` picks another file, `--profile` picks the starting
+//! profile, and `--tab` / `--layout` replace the tabs and the window layout for
+//! that run.
+//!
+//! ## Profiles
+//!
+//! A profile is a named overlay with its own tabs, layout and buttons, either a
+//! `[profiles.<name>]` table in `config.toml` or a `profiles/<name>.toml` file
+//! next to it. The active profile (see [`set_active_profile`]) is layered over
+//! the rest of the file on every load and can be switched while the app runs.
+//!
 //! The configuration uses the following structure:
 //! 
 //! ```toml
@@ -18,6 +30,14 @@
 //! name = "Audio Toolkit Shell"
 //! window_width = 1280.0
 //! window_height = 720.0
+//! theme = "frappe"
+//!
+//! [app.font]
+//! family = "/path/to/font.ttf"
+//! size = 12.0
+//! line_height = 1.0
+//! fallbacks = ["/path/to/emoji.ttc"]
+//! zoom_scope = "pane"
 //! 
 //! [[tabs]]
 //! title = "Terminal 1"
@@ -47,7 +67,7 @@
 //! (one left, two right-top, one right-bottom).
 
 use serde::{Deserialize, Serialize};
-use std::{env, fs, path::PathBuf};
+use std::{collections::BTreeMap, env, fs, path::PathBuf, sync::{Mutex, OnceLock}};
 
 /// Main application configuration structure
 /// 
@@ -59,10 +79,45 @@ use std::{env, fs, path::PathBuf};
 /// 
 /// * `app` - Global application settings (window size, name, etc.)
 /// * `tabs` - Vector of terminal tab configurations
-#[derive(Debug, Deserialize, Serialize)]
+/// * `keybindings` - Chord to action map from `[keybindings]` (merged over the defaults)
+/// * `buttons` - Actions panel buttons from `[[buttons]]` (defaults when omitted)
+/// * `scripts` - Script catalog settings from `[scripts]`
+/// * `analytics` - Activity log settings from `[analytics]`
+/// * `logging` - Session log directory and limits from `[logging]`
+/// * `control` - Local control socket from `[control]`
+/// * `notifications` - Toasts and desktop notifications from `[notifications]`
+/// * `profiles` - Named overlays from `[profiles.<name>]` (see [`compose_config`])
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct AppConfig {
     pub app: AppSettings,
     pub tabs: Vec<TabConfig>,
+    #[serde(default)]
+    pub keybindings: BTreeMap<String, KeyBinding>,
+    #[serde(default = "default_buttons")]
+    pub buttons: Vec<ButtonConfig>,
+    #[serde(default)]
+    pub scripts: ScriptsSettings,
+    #[serde(default)]
+    pub analytics: AnalyticsSettings,
+    #[serde(default)]
+    pub logging: LoggingSettings,
+    #[serde(default)]
+    pub control: ControlSettings,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, toml::Table>,
+}
+
+/// Value of a `[keybindings]` entry
+///
+/// Either an action name (`"copy"`, `"focus_pane_2"`, `"none"`, ...) or an inline
+/// table sending a literal string to the focused pane (`{ send = "ls -la\r" }`).
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    Action(String),
+    Send { send: String },
 }
 
 /// Application-level settings
@@ -75,7 +130,7 @@ pub struct AppConfig {
 /// * `name` - The application window title
 /// * `window_width` - Initial window width in pixels
 /// * `window_height` - Initial window height in pixels
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct AppSettings {
     pub name: String,
     pub window_width: f32,
@@ -95,12 +150,112 @@ pub struct AppSettings {
     /// Fraction of the top row width given to the left terminal (of the two top). Defaults to 0.5
     #[serde(default = "default_right_top_hsplit_fraction")]
     pub right_top_hsplit_fraction: f32,
+    /// Terminal font settings (`[app.font]`). All keys are optional.
+    #[serde(default)]
+    pub font: FontSettings,
+    /// Color theme id or name (built-in or from `themes_dir`). Defaults to "frappe"
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    /// Directory containing user theme files (`*.toml`). Empty = `<config dir>/themes`
+    #[serde(default)]
+    pub themes_dir: String,
+    /// Ask before pasting text with newlines when the app has not enabled bracketed paste. Defaults to true
+    #[serde(default = "default_true")]
+    pub confirm_multiline_paste: bool,
+    /// Command bookmarks file. Empty = `<config dir>/bookmarks.toml`
+    #[serde(default)]
+    pub bookmarks_file: String,
+    /// Which pane receives dropped files: "focused" (default) or "pointer"
+    #[serde(default)]
+    pub dnd_routing: DndRouting,
+}
+
+impl AppSettings {
+    /// Directory scanned for user theme files
+    pub fn themes_path(&self) -> PathBuf {
+        let dir = self.themes_dir.trim();
+        if dir.is_empty() {
+            config_dir().join("themes")
+        } else {
+            PathBuf::from(dir)
+        }
+    }
+
+    /// File holding the command bookmarks library
+    pub fn bookmarks_path(&self) -> PathBuf {
+        let file = self.bookmarks_file.trim();
+        if file.is_empty() {
+            config_dir().join("bookmarks.toml")
+        } else {
+            PathBuf::from(file)
+        }
+    }
+}
+
+/// Terminal font settings
+///
+/// Controls the font used to render terminal panes. When `family` is empty the
+/// built-in egui monospace font is used. Fallback fonts are consulted in order for
+/// glyphs the primary font lacks (e.g., emoji or CJK).
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FontSettings {
+    /// Path to a TTF/OTF file used as the primary terminal font. Empty = built-in monospace
+    #[serde(default)]
+    pub family: String,
+    /// Base font size in points. Defaults to 12.0
+    #[serde(default = "default_font_size")]
+    pub size: f32,
+    /// Line height as a multiple of the font's natural row height. Defaults to 1.0
+    #[serde(default = "default_line_height")]
+    pub line_height: f32,
+    /// Paths to fallback fonts (TTF/OTF/TTC), consulted in order
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
+    /// Zoom scope for Ctrl +/-/0: "pane" (focused pane only) or "global". Defaults to "pane"
+    #[serde(default)]
+    pub zoom_scope: ZoomScope,
+}
+
+impl Default for FontSettings {
+    fn default() -> Self {
+        Self {
+            family: String::new(),
+            size: default_font_size(),
+            line_height: default_line_height(),
+            fallbacks: vec![],
+            zoom_scope: ZoomScope::default(),
+        }
+    }
+}
+
+/// Which panes a zoom shortcut applies to
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoomScope {
+    /// Zoom only the focused pane
+    #[default]
+    Pane,
+    /// Zoom every pane together
+    Global,
+}
+
+/// Which pane receives files dropped on the window
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DndRouting {
+    /// Always the focused pane
+    #[default]
+    Focused,
+    /// The pane under the pointer, which is then focused. Falls back to the
+    /// focused pane when the platform reports no pointer movement during the drag
+    Pointer,
 }
 
 /// Drag-and-drop behavior settings
 ///
-/// These settings control optional actions when dropping a single folder.
-/// Both keys default to false when omitted in the TOML file.
+/// These settings choose what is typed when files or folders are dropped on the
+/// pane (see [`crate::dnd`] for the placeholders). All keys are optional; by
+/// default the quoted paths are inserted without pressing Enter.
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub struct DndSettings {
     /// If true, dropping a single directory will insert `cd '<dir>'` and simulate Enter
@@ -109,20 +264,476 @@ pub struct DndSettings {
     /// If true (and auto_cd_on_folder_drop is false), insert `'<dir>'` and simulate Enter
     #[serde(default)]
     pub auto_run_on_folder_drop: bool,
+    /// Template for dropped files no rule matches, e.g. `ffmpeg -i {path} {stem}.mp3`. Empty = the quoted path
+    #[serde(default)]
+    pub on_file: String,
+    /// Press Enter after an `on_file` command
+    #[serde(default)]
+    pub on_file_enter: bool,
+    /// Template for dropped folders no rule matches, e.g. `cd {path}`. Takes precedence over the folder flags
+    #[serde(default)]
+    pub on_folder: String,
+    /// Press Enter after an `on_folder` command
+    #[serde(default)]
+    pub on_folder_enter: bool,
+    /// Templates by extension (`[[tabs.dnd.rules]]`); the first match wins
+    #[serde(default)]
+    pub rules: Vec<DropRule>,
+    /// "insert" (default) types a drop at once; "queue" runs one item at a time
+    #[serde(default)]
+    pub mode: DropMode,
+    /// How a queued item is known to be finished. Defaults to "exit"
+    #[serde(default)]
+    pub queue_wait: QueueWait,
+}
+
+/// What happens to the commands for a drop
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DropMode {
+    /// Type every command immediately
+    #[default]
+    Insert,
+    /// Run the command once per item, waiting for each to finish
+    Queue,
+}
+
+/// Completion signal for queued drop commands
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueWait {
+    /// The shell reports the command's exit status (POSIX shells)
+    #[default]
+    Exit,
+    /// Any of the tab's `success_patterns` appears in the output
+    Pattern,
+}
+
+/// Drop template for files with given extensions
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct DropRule {
+    /// Extensions with or without the dot, case-insensitive (`[".wav", "mp4"]`)
+    pub extensions: Vec<String>,
+    /// Command typed for each matching item
+    pub template: String,
+    /// Press Enter after the command. Defaults to false
+    #[serde(default)]
+    pub enter: bool,
+}
+
+/// Actions panel button (`[[buttons]]`)
+///
+/// A button can run a built-in `action` (any `[keybindings]` action name), send a
+/// `command` (followed by Enter) and/or raw `keys` to its `target` pane. All three
+/// may be combined; they run in that order.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct ButtonConfig {
+    /// Text shown on the button
+    pub label: String,
+    /// Optional icon (e.g., an emoji) shown before the label
+    #[serde(default)]
+    pub icon: String,
+    /// Accent color: a theme color name ("blue", "peach", ...) or `#rrggbb`. Empty = blue
+    #[serde(default)]
+    pub color: String,
+    /// Hover text. Empty = a description of what the button sends
+    #[serde(default)]
+    pub tooltip: String,
+    /// Built-in action to run (e.g., "restart_all", "clear")
+    #[serde(default)]
+    pub action: String,
+    /// Command line to type into the target pane, followed by Enter
+    #[serde(default)]
+    pub command: String,
+    /// Raw keystrokes to send as-is (TOML escapes such as "\u0003" work)
+    #[serde(default)]
+    pub keys: String,
+    /// "focused" (default), "new" for a new ephemeral pane, or a tab title
+    #[serde(default)]
+    pub target: String,
+    /// Ask for confirmation before running
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Script catalog settings (`[scripts]`)
+///
+/// The catalog lists `.sh`, `.py` and `.command` files and executables found in
+/// `dirs`. Optional metadata comes from a sidecar `<script>.toml`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ScriptsSettings {
+    /// Directories scanned for scripts
+    #[serde(default)]
+    pub dirs: Vec<String>,
+    /// Folder levels scanned below each directory. Defaults to 3
+    #[serde(default = "default_scripts_depth")]
+    pub max_depth: usize,
+    /// Recently launched scripts to remember. Defaults to 10
+    #[serde(default = "default_recent_limit")]
+    pub recent_limit: usize,
+}
+
+impl Default for ScriptsSettings {
+    fn default() -> Self {
+        Self {
+            dirs: vec![],
+            max_depth: default_scripts_depth(),
+            recent_limit: default_recent_limit(),
+        }
+    }
+}
+
+/// Activity analytics settings (`[analytics]`)
+///
+/// Tab events (starts, exits, restarts, pattern matches, commands) are appended
+/// to a CSV log so the dashboard can chart them across sessions.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct AnalyticsSettings {
+    /// Record events to the log file. Defaults to true
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Event log path. Empty means `analytics.csv` next to `config.toml`
+    #[serde(default)]
+    pub log_file: String,
+    /// Events older than this many days are pruned from the log when it loads.
+    /// 0 keeps everything. Defaults to 90
+    #[serde(default = "default_retention_days")]
+    pub retention_days: u64,
+}
+
+impl Default for AnalyticsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            log_file: String::new(),
+            retention_days: default_retention_days(),
+        }
+    }
+}
+
+impl AnalyticsSettings {
+    /// Resolved event log path
+    pub fn log_path(&self) -> PathBuf {
+        let file = self.log_file.trim();
+        if file.is_empty() {
+            config_dir().join("analytics.csv")
+        } else {
+            PathBuf::from(file)
+        }
+    }
+}
+
+/// Session log settings (`[logging]`)
+///
+/// Tabs with `log = "plain"` or `log = "raw"` write their output to files in
+/// `dir`. Each run starts a new file; see [`crate::session_log`].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LoggingSettings {
+    /// Log directory. Empty means `logs` next to `config.toml`
+    #[serde(default)]
+    pub dir: String,
+    /// Start a new file once a log reaches this size, in MB. Defaults to 10
+    #[serde(default = "default_max_log_mb")]
+    pub max_file_mb: u64,
+    /// Delete a tab's logs older than this many days when it starts a new file; 0 keeps them. Defaults to 30
+    #[serde(default = "default_max_log_age_days")]
+    pub max_age_days: u64,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            dir: String::new(),
+            max_file_mb: default_max_log_mb(),
+            max_age_days: default_max_log_age_days(),
+        }
+    }
+}
+
+impl LoggingSettings {
+    /// Resolved log directory
+    pub fn log_dir(&self) -> PathBuf {
+        let dir = self.dir.trim();
+        if dir.is_empty() {
+            config_dir().join("logs")
+        } else {
+            PathBuf::from(dir)
+        }
+    }
+}
+
+/// Local control socket (`[control]`)
+///
+/// When enabled, other programs can list, drive and watch the tabs through a
+/// Unix domain socket; see [`crate::control`].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ControlSettings {
+    /// Listen on the socket. Defaults to false
+    #[serde(default)]
+    pub enabled: bool,
+    /// Socket path. Empty means `control.sock` next to `config.toml`
+    #[serde(default)]
+    pub socket: String,
+    /// Lines of plain-text output kept per tab for `scrollback` requests. Defaults to 1000
+    #[serde(default = "default_scrollback_lines")]
+    pub scrollback_lines: usize,
+}
+
+impl Default for ControlSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            socket: String::new(),
+            scrollback_lines: default_scrollback_lines(),
+        }
+    }
+}
+
+impl ControlSettings {
+    /// Resolved socket path
+    pub fn socket_path(&self) -> PathBuf {
+        let socket = self.socket.trim();
+        if socket.is_empty() {
+            config_dir().join("control.sock")
+        } else {
+            PathBuf::from(socket)
+        }
+    }
+}
+
+/// How notifications are delivered (`[notifications]`)
+///
+/// Which events notify is chosen per tab in `[tabs.notify]`; see [`crate::notify`].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct NotificationSettings {
+    /// Show toasts in the corner of the window. Defaults to true
+    #[serde(default = "default_true")]
+    pub toasts: bool,
+    /// Also send native desktop notifications. Defaults to false
+    #[serde(default)]
+    pub desktop: bool,
+    /// Command used instead of the native notifier; `{title}`, `{body}` and `{tab}` are shell-quoted
+    #[serde(default)]
+    pub command: String,
+    /// Seconds a toast stays up. Defaults to 6
+    #[serde(default = "default_toast_seconds")]
+    pub toast_seconds: u64,
+    /// Notify for the focused pane too (while the window is focused). Defaults to false
+    #[serde(default)]
+    pub focused_pane: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            toasts: true,
+            desktop: false,
+            command: String::new(),
+            toast_seconds: default_toast_seconds(),
+            focused_pane: false,
+        }
+    }
+}
+
+/// Events of a tab that notify (`[tabs.notify]`); all off by default
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct NotifySettings {
+    /// The process exited with status 0
+    #[serde(default)]
+    pub on_success: bool,
+    /// The process exited with another status or was killed by a signal
+    #[serde(default)]
+    pub on_failure: bool,
+    /// Output lines containing any of these strings
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Output arriving after this many seconds without any; 0 turns it off
+    #[serde(default)]
+    pub after_silence_secs: u64,
+}
+
+/// Hang detection for a tab (keys directly in `[[tabs]]`); see [`crate::idle`]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct IdleSettings {
+    /// Seconds without output or input before a running job counts as idle; 0 turns it off
+    #[serde(default)]
+    pub idle_timeout: u64,
+    /// Text on the cursor line meaning the job waits for input; empty uses the built-in prompt check
+    #[serde(default)]
+    pub stall_pattern: String,
+    /// What happens once a job has been idle for `idle_timeout`
+    #[serde(default)]
+    pub on_idle: IdleAction,
+    /// Typed, followed by Enter, by `on_idle = "respond"` when the job waits for input
+    #[serde(default)]
+    pub idle_response: String,
+}
+
+impl IdleSettings {
+    /// Whether hang detection is on: an `idle_timeout` or a `stall_pattern` is set
+    pub fn enabled(&self) -> bool {
+        self.idle_timeout > 0 || !self.stall_pattern.trim().is_empty()
+    }
+}
+
+/// What an idle job gets (`on_idle` in `[[tabs]]`)
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IdleAction {
+    /// Only the pane header shows it
+    #[default]
+    Flag,
+    /// Send `idle_response` if the job is waiting for input
+    Respond,
+    /// Restart the tab
+    Restart,
+}
+
+/// What a tab writes to its session log (`log` in `[[tabs]]`)
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogMode {
+    /// No session log
+    #[default]
+    Off,
+    /// Output with escape sequences and control characters removed
+    Plain,
+    /// Output exactly as received
+    Raw,
+}
+
+/// Options from the command line that change what gets loaded
+///
+/// Set once at startup with [`set_overrides`]; every load of the configuration
+/// (startup and `reload_config`) applies them.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Overrides {
+    /// `--config`: file used instead of `config.toml`; its folder becomes the config directory
+    pub config_file: Option<PathBuf>,
+    /// `--profile`: profile active at startup
+    pub profile: Option<String>,
+    /// `--tab`: tabs replacing the configured ones
+    pub tabs: Vec<TabConfig>,
+    /// `--layout`: window size and split fractions
+    pub layout: LayoutOverride,
+}
+
+/// Window size and split fractions given with `--layout`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LayoutOverride {
+    pub window_width: Option<f32>,
+    pub window_height: Option<f32>,
+    pub right_top_fraction: Option<f32>,
+    pub right_top_hsplit_fraction: Option<f32>,
+}
+
+impl LayoutOverride {
+    /// Parses `size=1400x800,top=0.6,split=0.5`; any subset, in any order
+    ///
+    /// `top` is `right_top_fraction` and `split` is `right_top_hsplit_fraction`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut layout = Self::default();
+        for part in text.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(|| format!("expected key=value, got {:?}", part))?;
+            let number = |v: &str| v.trim().parse::<f32>().map_err(|_| format!("{}: not a number: {:?}", key, v));
+            let fraction = |v: &str| match number(v)? {
+                f if (0.2..=0.8).contains(&f) => Ok(f),
+                _ => Err(format!("{} must be between 0.2 and 0.8", key)),
+            };
+            match key.trim() {
+                "size" => {
+                    let (w, h) = value.split_once('x').ok_or_else(|| format!("size must be WIDTHxHEIGHT, got {:?}", value))?;
+                    layout.window_width = Some(number(w)?);
+                    layout.window_height = Some(number(h)?);
+                }
+                "width" => layout.window_width = Some(number(value)?),
+                "height" => layout.window_height = Some(number(value)?),
+                "top" => layout.right_top_fraction = Some(fraction(value)?),
+                "split" => layout.right_top_hsplit_fraction = Some(fraction(value)?),
+                other => return Err(format!("unknown layout key {:?} (size, width, height, top, split)", other)),
+            }
+        }
+        if layout.window_width.into_iter().chain(layout.window_height).any(|v| v <= 0.0) {
+            return Err("window size must be positive".to_string());
+        }
+        Ok(layout)
+    }
+
+    pub fn apply(&self, app: &mut AppSettings) {
+        let fields = [
+            (self.window_width, &mut app.window_width),
+            (self.window_height, &mut app.window_height),
+            (self.right_top_fraction, &mut app.right_top_fraction),
+            (self.right_top_hsplit_fraction, &mut app.right_top_hsplit_fraction),
+        ];
+        for (value, field) in fields {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+    }
+}
+
+static OVERRIDES: OnceLock<Overrides> = OnceLock::new();
+
+/// Installs the command-line overrides; only the first call has an effect
+pub fn set_overrides(overrides: Overrides) {
+    let _ = OVERRIDES.set(overrides);
+}
+
+/// The command-line overrides (none unless [`set_overrides`] was called)
+pub fn overrides() -> &'static Overrides {
+    OVERRIDES.get_or_init(Overrides::default)
+}
+
+static ACTIVE_PROFILE: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+
+fn active_profile_slot() -> &'static Mutex<Option<String>> {
+    ACTIVE_PROFILE.get_or_init(|| Mutex::new(overrides().profile.clone()))
+}
+
+/// Profile layered over `config.toml` on every load; `--profile` until switched
+pub fn active_profile() -> Option<String> {
+    active_profile_slot().lock().map(|p| p.clone()).unwrap_or_default()
+}
+
+/// Switches the profile used by later loads; `None` uses `config.toml` alone
+pub fn set_active_profile(name: Option<String>) {
+    if let Ok(mut profile) = active_profile_slot().lock() {
+        *profile = name;
+    }
 }
 
 /// Resolve the path to the configuration file.
 ///
 /// Order of precedence:
-/// 1. If `ATS_CONFIG_DIR` env var is set, use that directory.
-/// 2. Otherwise, use the directory containing the current executable.
-fn config_file_path() -> PathBuf {
+/// 1. The `--config` file, if given.
+/// 2. If `ATS_CONFIG_DIR` env var is set, use that directory.
+/// 3. Otherwise, use the directory containing the current executable.
+pub fn config_file_path() -> PathBuf {
+    if let Some(file) = &overrides().config_file {
+        return file.clone();
+    }
+    config_dir().join("config.toml")
+}
+
+/// Directory holding `config.toml` (and the default `themes/` folder)
+///
+/// This is the folder of the `--config` file when given, else the executable's
+/// directory unless `ATS_CONFIG_DIR` is set.
+pub fn config_dir() -> PathBuf {
+    if let Some(file) = &overrides().config_file {
+        return match file.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+    }
     if let Ok(dir_override) = env::var("ATS_CONFIG_DIR") {
-        return PathBuf::from(dir_override).join("config.toml");
+        return PathBuf::from(dir_override);
     }
     let exe = env::current_exe().unwrap_or_else(|_| PathBuf::from("."));
     let dir = exe.parent().unwrap_or_else(|| std::path::Path::new("."));
-    dir.join("config.toml")
+    dir.to_path_buf()
 }
 
 /// Default first-run configuration template written when no config exists.
@@ -130,7 +741,7 @@ fn config_file_path() -> PathBuf {
 /// The template enables only "Terminal 1" by default and includes numbered
 /// examples for Tabs 2–4, commented out. Users can uncomment exactly one
 /// additional `[[tabs]]` block (or keep Tab 1) and edit the command as needed.
-const DEFAULT_CONFIG_TEMPLATE: &str = r#"# Audio Toolkit Shell Configuration
+pub const DEFAULT_CONFIG_TEMPLATE: &str = r#"# Audio Toolkit Shell Configuration
 # First-run template
 #
 # This file lives next to the application binary. Edit it in place.
@@ -151,6 +762,24 @@ const DEFAULT_CONFIG_TEMPLATE: &str = r#"# Audio Toolkit Shell Configuration
 name = "Audio Toolkit Shell"
 window_width = 1458.0
 window_height = 713.0
+# Color theme: latte, frappe, macchiato, mocha, high-contrast-dark, high-contrast-light,
+# or the file name of a theme in themes_dir (default: a "themes" folder next to this file)
+theme = "frappe"
+# themes_dir = ""
+# Ask before pasting multiple lines into a shell (bracketed-paste apps are never asked)
+confirm_multiline_paste = true
+# Command bookmarks (🔖 Bookmarks, Ctrl+Shift+B). Default: bookmarks.toml next to this file
+# bookmarks_file = ""
+# Dropped files go to the focused pane ("focused") or the pane under the pointer ("pointer")
+dnd_routing = "focused"
+
+# Terminal font (optional). Zoom with Ctrl + / Ctrl - / Ctrl 0.
+# [app.font]
+# family = "/Library/Fonts/JetBrainsMono-Regular.ttf"   # empty = built-in monospace
+# size = 12.0
+# line_height = 1.0
+# fallbacks = ["/System/Library/Fonts/Apple Color Emoji.ttc"]
+# zoom_scope = "pane"                                   # or "global"
 
 # ===================== Terminal 1 (Left column) =====================
 [[tabs]]
@@ -158,9 +787,37 @@ title = "Terminal 1"
 command = "bash"                      # Change to your tool or script path
 auto_restart_on_success = false
 success_patterns = []
+# log = "plain"                       # session log: "off", "plain" or "raw" (see [logging])
+# Hang detection: a running job silent for idle_timeout seconds is flagged idle (0 = off).
+# One whose last line looks like a prompt is flagged "waiting for input?";
+# stall_pattern replaces the built-in prompt check. on_idle: "flag", "respond" or "restart".
+# idle_timeout = 300
+# stall_pattern = "Overwrite? [y/N]"
+# on_idle = "respond"
+# idle_response = "N"                 # typed, followed by Enter
 [tabs.dnd]
 auto_cd_on_folder_drop = false
 auto_run_on_folder_drop = false
+# Drop templates. Placeholders (shell-quoted): {path} {dir} {name} {stem} {ext} {paths}
+# on_file = "ffmpeg -i {path} {stem}.mp3"
+# on_file_enter = false
+# on_folder = "cd {path}"
+# on_folder_enter = true
+# Run the template once per dropped file, one at a time ("insert" types everything at once).
+# queue_wait: "exit" waits for the shell's exit status, "pattern" for a success pattern
+# mode = "queue"
+# queue_wait = "exit"
+# Rules by extension; the first match wins over on_file/on_folder
+# [[tabs.dnd.rules]]
+# extensions = [".mp4", ".mov"]
+# template = "extract-audio {path}"
+# enter = true
+# Notify when the process exits, when output matches, or when output resumes (see [notifications])
+# [tabs.notify]
+# on_success = true
+# on_failure = true
+# patterns = ["ERROR", "Batch complete"]
+# after_silence_secs = 120
 
 # ===================== Terminal 2 (Right top-left) ==================
 # [[tabs]]
@@ -191,6 +848,95 @@ auto_run_on_folder_drop = false
 # [tabs.dnd]
 # auto_cd_on_folder_drop = false
 # auto_run_on_folder_drop = false
+
+# ===================== Action buttons (optional) ====================
+# Defining any [[buttons]] replaces the built-in Actions panel buttons.
+# target: "focused" (default), "new" (ephemeral pane), or a tab title.
+# [[buttons]]
+# label = "Normalize"
+# icon = "🎚️"
+# color = "green"                      # theme color name or a hex value (#a6e3a1)
+# tooltip = "Normalize the files in the current folder"
+# command = "normalize --lufs -14 *.wav"
+# target = "Terminal 2"
+# confirm = true
+#
+# [[buttons]]
+# label = "Restart All"
+# action = "restart_all"               # any [keybindings] action
+
+# ===================== Script catalog (optional) ====================
+# Folders scanned by the 📜 Scripts catalog for .sh/.py/.command files and executables.
+# A sidecar "<script>.toml" may set name, description, args and interpreter.
+# [scripts]
+# dirs = ["/path/to/launchers"]
+# max_depth = 3
+# recent_limit = 10
+
+# ===================== Analytics (optional) =========================
+# Tab starts, exits, restarts, pattern matches and commands are logged for the
+# 📊 Analytics dashboard. Leave log_file empty for analytics.csv next to this file.
+# [analytics]
+# enabled = true
+# log_file = ""
+# retention_days = 90   # 0 keeps everything
+
+# ===================== Session logs (optional) ======================
+# Tabs with log = "plain" (escapes removed) or log = "raw" write their output to
+# timestamped files here, one per run. Leave dir empty for a "logs" folder next to this file.
+# [logging]
+# dir = ""
+# max_file_mb = 10     # start a new file at this size
+# max_age_days = 30    # delete older logs of the tab; 0 keeps them
+
+# ===================== Control socket (optional) ====================
+# Lets scripts drive the shell: audio-toolkit-shell ctl list / send / keys / screen ...
+# Leave socket empty for control.sock next to this file (macOS and Linux only).
+# [control]
+# enabled = true
+# socket = ""
+# scrollback_lines = 1000   # plain-text lines kept per tab for "ctl scrollback"
+
+# ===================== Notifications (optional) =====================
+# Events chosen in [tabs.notify] show as toasts in the window corner and, with
+# desktop = true, as native notifications (notify-send on Linux, Notification
+# Center on macOS). command replaces the native notifier; {title}, {body} and
+# {tab} are shell-quoted, e.g. command = "terminal-notifier -title {title} -message {body}"
+# [notifications]
+# toasts = true
+# desktop = false
+# command = ""
+# toast_seconds = 6
+# focused_pane = false   # also notify for the pane you are looking at
+
+# ===================== Profiles (optional) ==========================
+# Named setups with their own tabs, layout and buttons. Switch with the
+# Profile menu next to Actions, or start with --profile <name>. A profile
+# can also be a file: profiles/<name>.toml next to this file.
+# Tables are merged into the settings above; [[tabs]] and [[buttons]] replace them.
+# [profiles.podcast.app]
+# right_top_fraction = 0.6
+#
+# [[profiles.podcast.tabs]]
+# title = "Mastering"
+# command = "bash"
+# auto_restart_on_success = false
+# success_patterns = []
+
+# ===================== Key bindings (optional) ======================
+# Chords map to actions and are merged over the built-in defaults
+# (Shift+Tab focus_next, Ctrl+Shift+C copy, Ctrl+Shift+V paste, Ctrl+Shift+F search,
+#  Ctrl+Shift+B bookmarks, Ctrl+Shift+H help, Ctrl+Shift+P command_palette,
+#  Ctrl+Plus / Ctrl+Minus / Ctrl+0 zoom).
+# Actions: focus_pane_1..focus_pane_4, focus_next, focus_prev, restart_tab, restart_all,
+#          clear, copy, paste, search, zoom_in, zoom_out, zoom_reset, file_manager,
+#          settings, bookmarks, scripts, analytics, help, tools, command_palette,
+#          reload_config, restart_pane_1..restart_pane_4, debug_overlay, window_trace,
+#          record, recordings, interrupt, terminate, kill, reset, none (unbind)
+# [keybindings]
+# "Alt+1" = "focus_pane_1"
+# "Ctrl+Shift+R" = "restart_tab"
+# "F5" = { send = "ls -la\r" }
 "#;
 
 fn default_min_left_width() -> f32 {
@@ -209,6 +955,75 @@ fn default_right_top_hsplit_fraction() -> f32 {
     0.5
 }
 
+fn default_font_size() -> f32 {
+    12.0
+}
+
+fn default_line_height() -> f32 {
+    1.0
+}
+
+fn default_theme() -> String {
+    "frappe".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_log_mb() -> u64 {
+    10
+}
+
+fn default_max_log_age_days() -> u64 {
+    30
+}
+
+fn default_scrollback_lines() -> usize {
+    1000
+}
+
+fn default_toast_seconds() -> u64 {
+    6
+}
+
+fn default_scripts_depth() -> usize {
+    3
+}
+
+fn default_recent_limit() -> usize {
+    10
+}
+
+fn default_retention_days() -> u64 {
+    90
+}
+
+/// Built-in Actions panel buttons, used when `config.toml` has no `[[buttons]]`
+pub fn default_buttons() -> Vec<ButtonConfig> {
+    let button = |icon: &str, label: &str, color: &str, action: &str, tooltip: &str| ButtonConfig {
+        label: label.to_string(),
+        icon: icon.to_string(),
+        color: color.to_string(),
+        tooltip: tooltip.to_string(),
+        action: action.to_string(),
+        ..ButtonConfig::default()
+    };
+    vec![
+        button("🔄", "Restart All", "blue", "restart_all", "Restart all terminals"),
+        button("📁", "File Manager", "lavender", "file_manager", "Browse audio files in the focused pane's folder"),
+        button("⚙️", "Settings", "sapphire", "settings", "Edit settings and save them to config.toml"),
+        button("🔧", "Tools", "peach", "tools", "Escape-sequence inspector for the focused pane"),
+        button("📊", "Analytics", "green", "analytics", "Per-tab uptime, restarts, crashes and commands"),
+        button("🔖", "Bookmarks", "pink", "bookmarks", "Insert a saved command into the focused pane"),
+        button("📜", "Scripts", "mauve", "scripts", "Browse and launch scripts from the [scripts] folders"),
+        button("💡", "Help", "yellow", "help", "Documentation, key bindings and the active configuration"),
+    ]
+}
+
+/// Panes in the fixed layout: at most this many `[[tabs]]` are shown, and ephemeral panes come after them
+pub const MAX_TABS: usize = 4;
+
 /// Configuration for individual terminal tabs
 /// 
 /// Each tab can have its own command, title, and behavior settings.
@@ -220,7 +1035,7 @@ fn default_right_top_hsplit_fraction() -> f32 {
 /// * `command` - Command to execute (absolute path or shell command)
 /// * `auto_restart_on_success` - Whether to restart when success patterns are detected
 /// * `success_patterns` - Text patterns that indicate successful completion
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct TabConfig {
     pub title: String,
     pub command: String,
@@ -229,6 +1044,26 @@ pub struct TabConfig {
     /// Per-tab drag-and-drop behavior settings
     #[serde(default)]
     pub dnd: DndSettings,
+    /// Session log of the tab's output: "off" (default), "plain" or "raw"
+    #[serde(default)]
+    pub log: LogMode,
+    /// Events that raise a notification
+    #[serde(default)]
+    pub notify: NotifySettings,
+    /// Hang detection: `idle_timeout`, `stall_pattern`, `on_idle`, `idle_response`
+    #[serde(flatten)]
+    pub idle: IdleSettings,
+}
+
+/// How the startup configuration was obtained (shown in the help viewer)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    /// Read from `config.toml`
+    File,
+    /// `config.toml` was missing; the first-run template was written and loaded
+    Template,
+    /// `config.toml` could not be used; the built-in defaults are active
+    Defaults(String),
 }
 
 /// Loads configuration from config.toml file
@@ -239,33 +1074,276 @@ pub struct TabConfig {
 /// 
 /// # Returns
 /// 
-/// An `AppConfig` instance either loaded from file or using defaults
-pub fn load_config() -> AppConfig {
+/// An `AppConfig` instance either loaded from file or using defaults, and where it came from
+pub fn load_config() -> (AppConfig, ConfigSource) {
     let config_path = config_file_path();
-    match fs::read_to_string(&config_path) {
-        Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+    let parse = |content: &str, source: ConfigSource| match parse_config(content) {
+        Ok(config) => (config, source),
+        Err(e) => {
             eprintln!("Error parsing {:?}: {}", config_path, e);
-            default_config()
-        }),
+            (default_config(), ConfigSource::Defaults(format!("parse error: {}", e)))
+        }
+    };
+    match fs::read_to_string(&config_path) {
+        Ok(content) => parse(&content, ConfigSource::File),
         Err(err) => {
             // Create first-run template only if the file is missing
             eprintln!("Config not found at {:?} ({}). Creating template...", config_path, err);
             if let Err(write_err) = fs::write(&config_path, DEFAULT_CONFIG_TEMPLATE) {
                 eprintln!("Failed to create config at {:?}: {}", config_path, write_err);
-                return default_config();
+                return (default_config(), ConfigSource::Defaults(format!("could not create the file: {}", write_err)));
             }
             match fs::read_to_string(&config_path) {
-                Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
-                    eprintln!("Error parsing freshly written config {:?}: {}", config_path, e);
-                    default_config()
-                }),
+                Ok(content) => parse(&content, ConfigSource::Template),
                 Err(read_err) => {
                     eprintln!("Failed to read freshly written config {:?}: {}", config_path, read_err);
-                    default_config()
+                    (default_config(), ConfigSource::Defaults(format!("could not read the file: {}", read_err)))
+                }
+            }
+        }
+    }
+}
+
+/// File for a profile kept outside `config.toml`
+pub fn profile_path(name: &str) -> PathBuf {
+    config_dir().join("profiles").join(format!("{}.toml", name))
+}
+
+/// Every profile name: `[profiles.<name>]` tables in `config` and `profiles/*.toml` files, sorted
+pub fn list_profiles(config: &AppConfig) -> Vec<String> {
+    let mut names: Vec<String> = config.profiles.keys().cloned().collect();
+    if let Ok(entries) = fs::read_dir(config_dir().join("profiles")) {
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().is_some_and(|e| e == "toml") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    names.push(stem.to_string());
                 }
             }
         }
     }
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Whether `name` is a profile of the current `config.toml`
+pub fn profile_exists(name: &str) -> bool {
+    let in_config = fs::read_to_string(config_file_path())
+        .ok()
+        .and_then(|text| text.parse::<toml::Table>().ok())
+        .is_some_and(|table| table.get("profiles").and_then(|p| p.get(name)).is_some());
+    in_config || profile_path(name).is_file()
+}
+
+/// Parses `config.toml` text with the active profile and the command-line [`Overrides`] applied
+pub fn parse_config(content: &str) -> Result<AppConfig, String> {
+    compose_config(content, active_profile().as_deref(), overrides())
+}
+
+/// Loads `config.toml` with `profile` instead of the active one
+///
+/// Unlike [`load_config`] nothing is written and errors are returned, so a
+/// profile can be checked before switching to it.
+pub fn load_profile(profile: Option<&str>) -> Result<AppConfig, String> {
+    let path = config_file_path();
+    let content = fs::read_to_string(&path).map_err(|e| format!("Cannot read {:?}: {}", path, e))?;
+    compose_config(&content, profile, overrides())
+}
+
+/// Builds the configuration from `config.toml` text, an optional profile and overrides
+///
+/// The profile is `[profiles.<name>]` from `content` or else `profiles/<name>.toml`.
+/// Tables in it are merged key by key into the config (so a profile can change a
+/// single `[app]` value); anything else, such as `[[tabs]]` or `[[buttons]]`,
+/// replaces the config's value.
+pub fn compose_config(content: &str, profile: Option<&str>, overrides: &Overrides) -> Result<AppConfig, String> {
+    let mut config: AppConfig = match profile {
+        None => toml::from_str(content).map_err(|e| e.to_string())?,
+        Some(name) => {
+            let mut table: toml::Table = toml::from_str(content).map_err(|e| e.to_string())?;
+            let overlay = profile_table(&table, name)?;
+            overlay_table(&mut table, overlay);
+            toml::Value::Table(table)
+                .try_into()
+                .map_err(|e: toml::de::Error| format!("profile {:?}: {}", name, e))?
+        }
+    };
+    if !overrides.tabs.is_empty() {
+        config.tabs = overrides.tabs.clone();
+    }
+    overrides.layout.apply(&mut config.app);
+    Ok(config)
+}
+
+/// `config` with the profile `name` layered over it, for checking a profile
+pub fn apply_profile(config: &AppConfig, name: &str) -> Result<AppConfig, String> {
+    let mut table = to_toml_table(config)?;
+    let overlay = profile_table(&table, name)?;
+    overlay_table(&mut table, overlay);
+    toml::Value::Table(table).try_into().map_err(|e: toml::de::Error| e.to_string())
+}
+
+/// The overlay for profile `name`: the `[profiles.<name>]` table of `config`, else its file
+fn profile_table(config: &toml::Table, name: &str) -> Result<toml::Table, String> {
+    let mut overlay = match config.get("profiles").and_then(|p| p.get(name)) {
+        Some(toml::Value::Table(table)) => table.clone(),
+        Some(_) => return Err(format!("[profiles.{}] must be a table", name)),
+        None => {
+            let path = profile_path(name);
+            let text = fs::read_to_string(&path)
+                .map_err(|_| format!("Unknown profile {:?}: no [profiles.{}] and no {:?}", name, name, path))?;
+            toml::from_str(&text).map_err(|e| format!("profile {:?}: {}", name, e))?
+        }
+    };
+    // Profiles do not nest
+    overlay.remove("profiles");
+    Ok(overlay)
+}
+
+fn overlay_table(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => overlay_table(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Writes `new` to `config.toml`, changing only the values that differ from `old`
+///
+/// `old` must be the configuration that was loaded from the file. Comments, key
+/// order and formatting of untouched entries are preserved. Command-line
+/// overrides stay out of the file; saving fails when an edit would only apply to them.
+pub fn save_config(old: &AppConfig, new: &AppConfig) -> Result<PathBuf, String> {
+    let path = config_file_path();
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    let updated = update_config_text(&content, old, new)?;
+    fs::write(&path, updated).map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+    Ok(path)
+}
+
+/// Applies the differences between `old` and `new` to the TOML text `content`
+///
+/// Changed values are replaced in place (keeping their comments), removed keys
+/// are deleted and new keys or tables are appended. The result is checked to
+/// parse back into `new`, with the command-line overrides applied.
+pub fn update_config_text(content: &str, old: &AppConfig, new: &AppConfig) -> Result<String, String> {
+    let mut doc: toml_edit::DocumentMut = content.parse().map_err(|e| format!("config.toml is not valid TOML: {}", e))?;
+    merge_table(doc.as_table_mut(), &to_toml_table(old)?, &to_toml_table(new)?);
+    let updated = doc.to_string();
+    match parse_config(&updated) {
+        Ok(reparsed) if reparsed == *new => Ok(updated),
+        Ok(_) => Err("config.toml could not be updated without changing other settings".to_string()),
+        Err(e) => Err(format!("updated config.toml would not parse: {}", e)),
+    }
+}
+
+fn to_toml_table(config: &AppConfig) -> Result<toml::Table, String> {
+    match toml::Value::try_from(config) {
+        Ok(toml::Value::Table(table)) => Ok(table),
+        Ok(_) => Err("configuration did not serialize to a table".to_string()),
+        Err(e) => Err(format!("failed to serialize configuration: {}", e)),
+    }
+}
+
+/// Recursively applies `old` -> `new` changes to `target`
+fn merge_table(target: &mut dyn toml_edit::TableLike, old: &toml::Table, new: &toml::Table) {
+    let empty = toml::Table::new();
+    for (key, value) in new {
+        let old_value = old.get(key);
+        if old_value == Some(value) {
+            continue;
+        }
+        if let Some(item) = target.get_mut(key) {
+            match (item, value) {
+                (item, toml::Value::Table(table)) if item.is_table_like() => {
+                    let old_table = old_value.and_then(toml::Value::as_table).unwrap_or(&empty);
+                    if let Some(item) = item.as_table_like_mut() {
+                        merge_table(item, old_table, table);
+                    }
+                }
+                (toml_edit::Item::ArrayOfTables(array), toml::Value::Array(values)) => {
+                    let old_values = old_value.and_then(toml::Value::as_array).map(Vec::as_slice).unwrap_or(&[]);
+                    merge_array_of_tables(array, old_values, values);
+                }
+                (item, value) => {
+                    // Keep the comment and spacing around the old value
+                    let mut replacement = to_edit_value(value);
+                    if let Some(previous) = item.as_value() {
+                        *replacement.decor_mut() = previous.decor().clone();
+                    }
+                    *item = toml_edit::Item::Value(replacement);
+                }
+            }
+            continue;
+        }
+        target.insert(key, new_item(value));
+    }
+    for key in old.keys() {
+        if !new.contains_key(key) {
+            target.remove(key);
+        }
+    }
+}
+
+fn merge_array_of_tables(target: &mut toml_edit::ArrayOfTables, old: &[toml::Value], new: &[toml::Value]) {
+    let empty = toml::Table::new();
+    for (i, value) in new.iter().enumerate() {
+        let Some(table) = value.as_table() else {
+            continue;
+        };
+        let old_table = old.get(i).and_then(toml::Value::as_table).unwrap_or(&empty);
+        match target.get_mut(i) {
+            Some(existing) => merge_table(existing, old_table, table),
+            None => {
+                let mut created = toml_edit::Table::new();
+                merge_table(&mut created, &empty, table);
+                target.push(created);
+            }
+        }
+    }
+    while target.len() > new.len() {
+        target.remove(target.len() - 1);
+    }
+}
+
+/// Builds a new item; single-key tables such as `{ send = "..." }` stay inline
+fn new_item(value: &toml::Value) -> toml_edit::Item {
+    let empty = toml::Table::new();
+    match value {
+        toml::Value::Table(table) if table.len() > 1 || table.values().any(toml::Value::is_table) => {
+            let mut created = toml_edit::Table::new();
+            merge_table(&mut created, &empty, table);
+            toml_edit::Item::Table(created)
+        }
+        toml::Value::Array(values) if !values.is_empty() && values.iter().all(toml::Value::is_table) => {
+            let mut array = toml_edit::ArrayOfTables::new();
+            for table in values.iter().filter_map(toml::Value::as_table) {
+                let mut created = toml_edit::Table::new();
+                merge_table(&mut created, &empty, table);
+                array.push(created);
+            }
+            toml_edit::Item::ArrayOfTables(array)
+        }
+        value => toml_edit::Item::Value(to_edit_value(value)),
+    }
+}
+
+fn to_edit_value(value: &toml::Value) -> toml_edit::Value {
+    match value {
+        toml::Value::String(s) => s.as_str().into(),
+        toml::Value::Integer(i) => (*i).into(),
+        // Every float in the config is an f32; write its shortest form (0.617, not 0.6169999837875366)
+        toml::Value::Float(f) => (*f as f32).to_string().parse::<f64>().unwrap_or(*f).into(),
+        toml::Value::Boolean(b) => (*b).into(),
+        toml::Value::Datetime(d) => d.to_string().parse().unwrap_or_else(|_| d.to_string().into()),
+        toml::Value::Array(values) => toml_edit::Value::Array(values.iter().map(to_edit_value).collect()),
+        toml::Value::Table(table) => {
+            toml_edit::Value::InlineTable(table.iter().map(|(k, v)| (k.as_str(), to_edit_value(v))).collect())
+        }
+    }
 }
 
 /// Provides default application configuration
@@ -288,6 +1366,12 @@ pub fn default_config() -> AppConfig {
             allow_zero_collapse: false,
             right_top_fraction: 0.617,
             right_top_hsplit_fraction: 0.5,
+            font: FontSettings::default(),
+            theme: default_theme(),
+            themes_dir: String::new(),
+            confirm_multiline_paste: true,
+            bookmarks_file: String::new(),
+            dnd_routing: DndRouting::Focused,
         },
         tabs: vec![
             TabConfig {
@@ -296,6 +1380,9 @@ pub fn default_config() -> AppConfig {
                 auto_restart_on_success: false,
                 success_patterns: vec![],
                 dnd: DndSettings::default(),
+                log: LogMode::Off,
+                notify: NotifySettings::default(),
+                idle: IdleSettings::default(),
             },
             TabConfig {
                 title: "Terminal 2".to_string(),
@@ -303,6 +1390,9 @@ pub fn default_config() -> AppConfig {
                 auto_restart_on_success: false,
                 success_patterns: vec![],
                 dnd: DndSettings::default(),
+                log: LogMode::Off,
+                notify: NotifySettings::default(),
+                idle: IdleSettings::default(),
             },
             TabConfig {
                 title: "Terminal 3".to_string(),
@@ -310,6 +1400,9 @@ pub fn default_config() -> AppConfig {
                 auto_restart_on_success: false,
                 success_patterns: vec![],
                 dnd: DndSettings::default(),
+                log: LogMode::Off,
+                notify: NotifySettings::default(),
+                idle: IdleSettings::default(),
             },
             TabConfig {
                 title: "Terminal 4".to_string(),
@@ -317,8 +1410,19 @@ pub fn default_config() -> AppConfig {
                 auto_restart_on_success: false,
                 success_patterns: vec![],
                 dnd: DndSettings::default(),
+                log: LogMode::Off,
+                notify: NotifySettings::default(),
+                idle: IdleSettings::default(),
             },
         ],
+        keybindings: BTreeMap::new(),
+        buttons: default_buttons(),
+        scripts: ScriptsSettings::default(),
+        analytics: AnalyticsSettings::default(),
+        logging: LoggingSettings::default(),
+        control: ControlSettings::default(),
+        notifications: NotificationSettings::default(),
+        profiles: BTreeMap::new(),
     }
 }
 
@@ -327,6 +1431,20 @@ mod tests {
     use super::*;
     use std::fs;
 
+    /// A one-tab config; `app` and `tab` are appended to the `[app]` and `[[tabs]]`
+    /// tables, and `rest` follows them
+    fn config_text(app: &str, tab: &str, rest: &str) -> String {
+        format!(
+            "[app]\nname = \"Test\"\nwindow_width = 800.0\nwindow_height = 600.0\n{}\n\n\
+             [[tabs]]\ntitle = \"Tab\"\ncommand = \"bash\"\nauto_restart_on_success = false\nsuccess_patterns = []\n{}\n\n{}\n",
+            app, tab, rest
+        )
+    }
+
+    /// Parses [`config_text`]
+    fn config_with(app: &str, tab: &str, rest: &str) -> AppConfig {
+        toml::from_str(&config_text(app, tab, rest)).expect("Failed to parse TOML")
+    }
 
     #[test]
     fn test_default_config() {
@@ -386,6 +1504,37 @@ success_patterns = ["done", "complete"]
         assert_eq!(config.tabs[0].success_patterns, vec!["done", "complete"]);
     }
 
+    #[test]
+    fn test_dnd_templates_parsing() {
+        let config = config_with(
+            "",
+            r#"[tabs.dnd]
+on_file = "ffmpeg -i {path} {stem}.mp3"
+on_folder = "cd {path}"
+on_folder_enter = true
+mode = "queue"
+queue_wait = "pattern"
+[[tabs.dnd.rules]]
+extensions = [".mp4", "mov"]
+template = "extract {path}"
+enter = true"#,
+            "",
+        );
+        let dnd = &config.tabs[0].dnd;
+        assert_eq!(dnd.on_file, "ffmpeg -i {path} {stem}.mp3");
+        assert!(!dnd.on_file_enter);
+        assert!(dnd.on_folder_enter);
+        assert_eq!(dnd.rules, vec![DropRule {
+            extensions: vec![".mp4".to_string(), "mov".to_string()],
+            template: "extract {path}".to_string(),
+            enter: true,
+        }]);
+        assert!(!dnd.auto_cd_on_folder_drop);
+        assert_eq!(dnd.mode, DropMode::Queue);
+        assert_eq!(dnd.queue_wait, QueueWait::Pattern);
+        assert_eq!(DndSettings::default().mode, DropMode::Insert);
+    }
+
     #[test]
     fn test_load_config_with_missing_file() {
         // Use a temporary directory via ATS_CONFIG_DIR so we don't touch the real binary dir
@@ -394,8 +1543,9 @@ success_patterns = ["done", "complete"]
         std::env::set_var("ATS_CONFIG_DIR", temp_dir.path());
 
         // No config exists yet: load_config should create a template and then load it
-        let config = load_config();
+        let (config, source) = load_config();
 
+        assert_eq!(source, ConfigSource::Template);
         assert_eq!(config.app.name, "Audio Toolkit Shell");
         // Template enables only one tab by default (others are commented)
         assert_eq!(config.tabs.len(), 1);
@@ -444,9 +1594,10 @@ success_patterns = []
         let original = std::env::var("ATS_CONFIG_DIR").ok();
         std::env::set_var("ATS_CONFIG_DIR", temp_dir.path());
 
-        let config = load_config();
+        let (config, source) = load_config();
 
         // Should fall back to default config on parse error
+        assert!(matches!(source, ConfigSource::Defaults(_)));
         assert_eq!(config.app.name, "Audio Toolkit Shell");
         assert_eq!(config.tabs.len(), 4);
 
@@ -454,6 +1605,238 @@ success_patterns = []
         if let Some(val) = original { std::env::set_var("ATS_CONFIG_DIR", val); } else { std::env::remove_var("ATS_CONFIG_DIR"); }
     }
 
+    #[test]
+    fn test_font_settings_parsing() {
+        let config = config_with(
+            r#"
+[app.font]
+family = "/fonts/Mono.ttf"
+size = 14.5
+fallbacks = ["/fonts/Emoji.ttc", "/fonts/CJK.otf"]
+zoom_scope = "global""#,
+            "",
+            "",
+        );
+        let font = &config.app.font;
+        assert_eq!(font.family, "/fonts/Mono.ttf");
+        assert_eq!(font.size, 14.5);
+        assert_eq!(font.line_height, 1.0); // default when omitted
+        assert_eq!(font.fallbacks.len(), 2);
+        assert_eq!(font.zoom_scope, ZoomScope::Global);
+
+        // Omitting [app.font] entirely yields defaults
+        assert_eq!(config_with("", "", "").app.font, FontSettings::default());
+    }
+
+    #[test]
+    fn test_buttons_parsing() {
+        // Omitting [[buttons]] keeps the built-in buttons
+        let config = config_with("", "", "");
+        assert_eq!(config.buttons, default_buttons());
+        assert_eq!(config.buttons[0].action, "restart_all");
+
+        let config = config_with("", "", "[[buttons]]\nlabel = \"Export\"\ncommand = \"export.sh\"\ntarget = \"new\"\nconfirm = true");
+        assert_eq!(config.buttons.len(), 1);
+        let button = &config.buttons[0];
+        assert_eq!(button.label, "Export");
+        assert_eq!(button.command, "export.sh");
+        assert_eq!(button.target, "new");
+        assert!(button.confirm);
+        assert!(button.icon.is_empty() && button.keys.is_empty());
+    }
+
+    #[test]
+    fn test_keybindings_parsing() {
+        let config = config_with(
+            "",
+            "",
+            r#"[keybindings]
+"Alt+1" = "focus_pane_1"
+"F5" = { send = "ls -la\r" }"#,
+        );
+        assert_eq!(config.keybindings.len(), 2);
+        assert_eq!(config.keybindings["Alt+1"], KeyBinding::Action("focus_pane_1".to_string()));
+        assert_eq!(config.keybindings["F5"], KeyBinding::Send { send: "ls -la\r".to_string() });
+        assert!(default_config().keybindings.is_empty());
+    }
+
+    #[test]
+    fn test_theme_settings_parsing() {
+        let config = config_with("", "", "");
+        assert_eq!(config.app.theme, "frappe");
+        assert!(config.app.confirm_multiline_paste);
+        assert!(config.app.themes_path().ends_with("themes"));
+
+        let config = config_with("theme = \"mocha\"\nthemes_dir = \"/tmp/my-themes\"", "", "");
+        assert_eq!(config.app.theme, "mocha");
+        assert_eq!(config.app.themes_path(), PathBuf::from("/tmp/my-themes"));
+    }
+
+    #[test]
+    fn test_dnd_routing_parsing() {
+        assert_eq!(config_with("", "", "").app.dnd_routing, DndRouting::Focused);
+        assert_eq!(config_with("dnd_routing = \"pointer\"", "", "").app.dnd_routing, DndRouting::Pointer);
+        assert!(toml::from_str::<AppConfig>(&config_text("dnd_routing = \"nearest\"", "", "")).is_err());
+    }
+
+    #[test]
+    fn test_logging_parsing() {
+        let config = config_with(
+            "",
+            "log = \"raw\"",
+            r#"[[tabs]]
+title = "Shell"
+command = "bash"
+auto_restart_on_success = false
+success_patterns = []
+
+[logging]
+dir = "/tmp/ats-logs"
+max_age_days = 0"#,
+        );
+        assert_eq!(config.tabs[0].log, LogMode::Raw);
+        assert_eq!(config.tabs[1].log, LogMode::Off);
+        assert_eq!(config.logging.log_dir(), PathBuf::from("/tmp/ats-logs"));
+        assert_eq!(config.logging.max_file_mb, 10);
+        assert_eq!(config.logging.max_age_days, 0);
+        assert!(LoggingSettings::default().log_dir().ends_with("logs"));
+    }
+
+    #[test]
+    fn test_control_parsing() {
+        let config = config_with("", "", "[control]\nenabled = true\nsocket = \"/tmp/ats.sock\"");
+        assert!(config.control.enabled);
+        assert_eq!(config.control.socket_path(), PathBuf::from("/tmp/ats.sock"));
+        assert_eq!(config.control.scrollback_lines, 1000);
+        assert!(!ControlSettings::default().enabled);
+        assert!(ControlSettings::default().socket_path().ends_with("control.sock"));
+    }
+
+    #[test]
+    fn test_notification_parsing() {
+        let config = config_with(
+            "",
+            r#"idle_timeout = 600
+on_idle = "respond"
+idle_response = "N"
+[tabs.notify]
+on_failure = true
+patterns = ["ERROR"]
+after_silence_secs = 120"#,
+            r#"[[tabs]]
+title = "Shell"
+command = "bash"
+auto_restart_on_success = false
+success_patterns = []
+
+[notifications]
+desktop = true
+command = "terminal-notifier -title {title} -message {body}""#,
+        );
+        let notify = &config.tabs[0].notify;
+        assert!(notify.on_failure && !notify.on_success);
+        assert_eq!(notify.patterns, vec!["ERROR"]);
+        assert_eq!(notify.after_silence_secs, 120);
+        assert_eq!(config.tabs[1].notify, NotifySettings::default());
+        assert_eq!(config.tabs[0].idle.idle_timeout, 600);
+        assert_eq!(config.tabs[0].idle.on_idle, IdleAction::Respond);
+        assert_eq!(config.tabs[1].idle, IdleSettings::default());
+        assert!(config.notifications.desktop && config.notifications.toasts);
+        assert_eq!(config.notifications.toast_seconds, 6);
+        assert!(!config.notifications.focused_pane);
+    }
+
+    #[test]
+    fn test_profiles() {
+        let content = format!(
+            "{}{}",
+            DEFAULT_CONFIG_TEMPLATE,
+            r#"
+[profiles.video.app]
+right_top_fraction = 0.7
+
+[[profiles.video.tabs]]
+title = "Cleanup"
+command = "denoise"
+auto_restart_on_success = false
+success_patterns = []
+
+[[profiles.video.buttons]]
+label = "Render"
+keys = "render\r"
+"#
+        );
+        let base = compose_config(&content, None, &Overrides::default()).expect("base config");
+        assert_eq!(base.tabs[0].title, "Terminal 1");
+        assert!(base.profiles.contains_key("video"));
+        assert!(list_profiles(&base).contains(&"video".to_string()));
+
+        let video = compose_config(&content, Some("video"), &Overrides::default()).expect("profile config");
+        assert_eq!(video.tabs.len(), 1);
+        assert_eq!(video.tabs[0].command, "denoise");
+        assert_eq!(video.buttons.len(), 1);
+        assert_eq!(video.app.right_top_fraction, 0.7);
+        assert_eq!(video.app.right_top_hsplit_fraction, base.app.right_top_hsplit_fraction);
+        // The other profiles stay listed while one is active
+        assert_eq!(video.profiles, base.profiles);
+        assert_eq!(apply_profile(&base, "video"), Ok(video));
+
+        let err = compose_config(&content, Some("podcast"), &Overrides::default()).unwrap_err();
+        assert!(err.contains("Unknown profile"), "{}", err);
+    }
+
+    #[test]
+    fn test_update_config_text_preserves_comments() {
+        let original = r#"# My shell setup
+[app]
+name = "Test App"   # shown in the title bar
+window_width = 800.0
+window_height = 600.0
+
+# The main tool
+[[tabs]]
+title = "Normalizer"
+command = "/opt/normalizer"
+auto_restart_on_success = true
+success_patterns = ["Done"]
+
+[keybindings]
+"Alt+1" = "focus_pane_1"
+"#;
+        let old: AppConfig = toml::from_str(original).unwrap();
+        let mut new = old.clone();
+        new.app.name = "Renamed".to_string();
+        new.app.right_top_fraction = 0.617;
+        new.tabs[0].success_patterns.push("Finished".to_string());
+        new.tabs.push(TabConfig {
+            title: "Shell".to_string(),
+            command: "bash".to_string(),
+            auto_restart_on_success: false,
+            success_patterns: vec![],
+            dnd: DndSettings { auto_cd_on_folder_drop: true, auto_run_on_folder_drop: false, ..Default::default() },
+            log: LogMode::Off,
+            notify: NotifySettings::default(),
+            idle: IdleSettings::default(),
+        });
+        new.keybindings.remove("Alt+1");
+        new.keybindings.insert("F5".to_string(), KeyBinding::Send { send: "ls\r".to_string() });
+
+        let updated = update_config_text(original, &old, &new).unwrap();
+        assert!(updated.starts_with("# My shell setup\n[app]\n"));
+        assert!(updated.contains("name = \"Renamed\"   # shown in the title bar"));
+        assert!(updated.contains("right_top_fraction = 0.617\n"));
+        assert!(updated.contains("# The main tool\n[[tabs]]\ntitle = \"Normalizer\""));
+        assert!(updated.contains("success_patterns = [\"Done\", \"Finished\"]"));
+        assert!(updated.contains("F5 = { send = \"ls\\r\" }"));
+        assert!(!updated.contains("Alt+1"));
+        // Untouched defaults are not written out
+        assert!(!updated.contains("min_left_width"));
+        assert_eq!(toml::from_str::<AppConfig>(&updated).unwrap(), new);
+
+        // A file that no longer matches the loaded config is left alone
+        assert!(update_config_text("not = [valid", &old, &new).is_err());
+    }
+
     #[test]
     fn test_tab_config_clone() {
         let tab = TabConfig {
@@ -462,6 +1845,9 @@ success_patterns = []
             auto_restart_on_success: true,
             success_patterns: vec!["done".to_string()],
             dnd: DndSettings::default(),
+            log: LogMode::Off,
+            notify: NotifySettings::default(),
+            idle: IdleSettings::default(),
         };
         
         let cloned = tab.clone();
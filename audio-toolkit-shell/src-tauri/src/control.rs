@@ -0,0 +1,715 @@
+//! # Control Module
+//!
+//! This module lets other programs drive the running shell through a Unix
+//! domain socket (`[control] enabled = true`), and implements the
+//! `audio-toolkit-shell ctl` subcommand that talks to it.
+//!
+//! ## Protocol
+//!
+//! JSON lines in both directions. A request names a `cmd`; its optional `id`
+//! is echoed in the response:
+//!
+//! ```text
+//! → {"id": 1, "cmd": "send", "tab": "Normalizer", "text": "normalize -23 take1.wav", "enter": true}
+//! ← {"id": 1, "ok": true}
+//! ← {"id": 2, "ok": false, "error": "no tab titled \"Mixer\""}
+//! ```
+//!
+//! `tab` is a title (case-insensitive) or a pane number starting at 1.
+//!
+//! - `list`: every tab as `pane`, `title`, `command`, `running`, `focused`
+//! - `send`: writes `text` to the tab, then Enter when `enter` is true
+//! - `keys`: sends key chords such as `"Ctrl+C"`, `"Up"` or `"Enter"`
+//! - `restart`, `focus`: restart or focus the tab
+//! - `screen`: the visible screen as `text`
+//! - `scrollback`: the last `lines` lines of output as plain `text`
+//! - `subscribe`: streams events of one `tab` (or all) on this connection.
+//!   `events` picks among `output` and the analytics kinds (`start`, `exit`,
+//!   `restart`, `pattern`, `command`, ...); empty means all. Each event is a
+//!   line such as `{"event": "pattern", "pane": 1, "tab": "Normalizer", "detail": "Done"}`.
+//!
+//! The socket is created with mode 0600, so only the current user can connect.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, SyncSender, TryRecvError};
+
+use eframe::egui;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::analytics::EventKind;
+use crate::config::{self, AppConfig};
+use crate::input::{self, Chord};
+use crate::session_log::PlainText;
+
+/// Event name for output chunks in `subscribe`
+pub const OUTPUT_EVENT: &str = "output";
+
+/// Lines queued for a client before it is dropped as too slow
+const CLIENT_QUEUE: usize = 1024;
+
+/// A tab named in a request: pane number or title
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum TabRef {
+    Pane(usize),
+    Title(String),
+}
+
+impl TabRef {
+    /// Index of the tab among `titles`
+    pub fn resolve<'a>(&self, titles: impl IntoIterator<Item = &'a str>) -> Result<usize, String> {
+        let titles: Vec<&str> = titles.into_iter().collect();
+        let pane = match self {
+            TabRef::Pane(n) => *n,
+            TabRef::Title(title) => match title.trim().parse() {
+                Ok(n) => n,
+                Err(_) => {
+                    return titles
+                        .iter()
+                        .position(|t| t.eq_ignore_ascii_case(title.trim()))
+                        .ok_or_else(|| format!("no tab titled {:?}", title))
+                }
+            },
+        };
+        if (1..=titles.len()).contains(&pane) {
+            Ok(pane - 1)
+        } else {
+            Err(format!("no pane {} (there are {})", pane, titles.len()))
+        }
+    }
+}
+
+/// A request read from the socket
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    List,
+    Send {
+        tab: TabRef,
+        text: String,
+        #[serde(default)]
+        enter: bool,
+    },
+    Keys {
+        tab: TabRef,
+        keys: Vec<String>,
+    },
+    Restart {
+        tab: TabRef,
+    },
+    Focus {
+        tab: TabRef,
+    },
+    Screen {
+        tab: TabRef,
+    },
+    Scrollback {
+        tab: TabRef,
+        #[serde(default)]
+        lines: Option<usize>,
+    },
+    Subscribe {
+        #[serde(default)]
+        tab: Option<TabRef>,
+        #[serde(default)]
+        events: Vec<String>,
+    },
+}
+
+/// Parses one request line into its `id` (null when absent) and the request
+pub fn parse_request(line: &str) -> (Value, Result<Request, String>) {
+    let value: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => return (Value::Null, Err(format!("invalid JSON: {}", e))),
+    };
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    (id, serde_json::from_value(value).map_err(|e| e.to_string()))
+}
+
+/// Bytes for a list of key chords (`"Ctrl+C"`, `"Up"`, `"Shift+Tab"`, ...)
+pub fn encode_keys(keys: &[String]) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    for key in keys {
+        let chord = Chord::parse(key)?;
+        let modifiers = egui::Modifiers {
+            alt: chord.alt,
+            ctrl: chord.ctrl,
+            shift: chord.shift,
+            mac_cmd: chord.cmd,
+            command: chord.ctrl || chord.cmd,
+        };
+        let encoded = input::encode_key(chord.key, modifiers)
+            .ok_or_else(|| format!("{:?} has no terminal sequence; use send for text", key))?;
+        bytes.extend(encoded);
+    }
+    Ok(bytes)
+}
+
+/// Events one connection asked for
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subscription {
+    /// Title of the watched tab; `None` watches every tab
+    pub tab: Option<String>,
+    /// Event names; empty means all
+    pub events: Vec<String>,
+}
+
+impl Subscription {
+    pub fn new(tab: Option<String>, events: Vec<String>) -> Result<Self, String> {
+        let events: Vec<String> = events.iter().map(|e| e.trim().to_ascii_lowercase()).collect();
+        if let Some(unknown) = events.iter().find(|e| *e != OUTPUT_EVENT && EventKind::parse(e).is_none()) {
+            return Err(format!("unknown event {:?}", unknown));
+        }
+        Ok(Self { tab, events })
+    }
+
+    pub fn wants(&self, title: &str, event: &str) -> bool {
+        self.tab.as_deref().map_or(true, |t| t.eq_ignore_ascii_case(title))
+            && (self.events.is_empty() || self.events.iter().any(|e| e == event))
+    }
+}
+
+/// Recent output of a tab as plain text, for `scrollback`
+#[derive(Debug)]
+pub struct History {
+    plain: PlainText,
+    /// Complete lines followed by the line being written
+    lines: VecDeque<String>,
+    max_lines: usize,
+}
+
+impl History {
+    pub fn new(max_lines: usize) -> Self {
+        Self { plain: PlainText::default(), lines: VecDeque::from([String::new()]), max_lines }
+    }
+
+    pub fn max_lines(&self) -> usize {
+        self.max_lines
+    }
+
+    pub fn push(&mut self, data: &str) {
+        let text = self.plain.strip(data);
+        let mut parts = text.split('\n');
+        if let (Some(first), Some(current)) = (parts.next(), self.lines.back_mut()) {
+            current.push_str(first);
+        }
+        self.lines.extend(parts.map(str::to_string));
+        while self.lines.len() > self.max_lines + 1 {
+            self.lines.pop_front();
+        }
+    }
+
+    /// The last `n` lines, including an unfinished last line
+    pub fn tail(&self, n: usize) -> String {
+        let end = self.lines.len() - usize::from(self.lines.back().is_some_and(String::is_empty));
+        let start = end.saturating_sub(n);
+        self.lines.range(start..end).cloned().collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// Message from the socket threads to the app
+enum Incoming {
+    Connected(u64, SyncSender<String>),
+    Line(u64, String),
+    Closed(u64),
+}
+
+struct Client {
+    /// Lines for the client's writer thread
+    writer: SyncSender<String>,
+    subscription: Option<Subscription>,
+}
+
+/// A request waiting to be answered with [`ControlServer::reply`]
+pub struct ControlRequest {
+    pub client: u64,
+    pub id: Value,
+    pub request: Result<Request, String>,
+}
+
+/// Listening socket and its connections
+///
+/// Connections are read on background threads; the app polls for requests
+/// once per frame and answers them on the UI thread. Each connection also has a
+/// writer thread fed through a bounded queue, so replies and events never block
+/// the UI; a client whose queue fills up is disconnected.
+pub struct ControlServer {
+    path: PathBuf,
+    incoming: Receiver<Incoming>,
+    clients: HashMap<u64, Client>,
+}
+
+impl ControlServer {
+    /// Listens on `path`, replacing a stale socket file left by a crash
+    ///
+    /// Anything at `path` that is not a socket is left alone and reported.
+    #[cfg(unix)]
+    pub fn start(path: PathBuf) -> Result<Self, String> {
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        if let Ok(metadata) = fs::symlink_metadata(&path) {
+            if !metadata.file_type().is_socket() {
+                return Err(format!("{:?} exists and is not a socket", path));
+            }
+            if UnixStream::connect(&path).is_ok() {
+                return Err(format!("{:?} is in use by another instance", path));
+            }
+            fs::remove_file(&path).map_err(|e| format!("Cannot remove stale {:?}: {}", path, e))?;
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Cannot create {:?}: {}", dir, e))?;
+        }
+        let listener = UnixListener::bind(&path).map_err(|e| format!("Cannot listen on {:?}: {}", path, e))?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Cannot restrict {:?}: {}", path, e))?;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for (id, stream) in (0u64..).zip(listener.incoming()) {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let Ok(mut writer) = stream.try_clone() else {
+                    continue;
+                };
+                let (lines_tx, lines_rx) = std::sync::mpsc::sync_channel::<String>(CLIENT_QUEUE);
+                if tx.send(Incoming::Connected(id, lines_tx)).is_err() {
+                    break;
+                }
+                std::thread::spawn(move || {
+                    let _ = writer.set_write_timeout(Some(std::time::Duration::from_secs(5)));
+                    for line in lines_rx {
+                        if writeln!(writer, "{}", line).and_then(|_| writer.flush()).is_err() {
+                            break;
+                        }
+                    }
+                    // Dropped by the app or unwritable: end the reader too
+                    let _ = writer.shutdown(std::net::Shutdown::Both);
+                });
+                let tx = tx.clone();
+                std::thread::spawn(move || {
+                    for line in BufReader::new(stream).lines() {
+                        let Ok(line) = line else {
+                            break;
+                        };
+                        if tx.send(Incoming::Line(id, line)).is_err() {
+                            return;
+                        }
+                    }
+                    let _ = tx.send(Incoming::Closed(id));
+                });
+            }
+        });
+        Ok(Self { path, incoming: rx, clients: HashMap::new() })
+    }
+
+    #[cfg(not(unix))]
+    pub fn start(_path: PathBuf) -> Result<Self, String> {
+        Err("the control socket is only available on macOS and Linux".to_string())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Requests received since the last call
+    pub fn poll(&mut self) -> Vec<ControlRequest> {
+        let mut requests = Vec::new();
+        loop {
+            match self.incoming.try_recv() {
+                Ok(Incoming::Connected(client, writer)) => {
+                    self.clients.insert(client, Client { writer, subscription: None });
+                }
+                Ok(Incoming::Line(client, line)) if !line.trim().is_empty() => {
+                    let (id, request) = parse_request(&line);
+                    requests.push(ControlRequest { client, id, request });
+                }
+                Ok(Incoming::Line(..)) => {}
+                Ok(Incoming::Closed(client)) => {
+                    self.clients.remove(&client);
+                }
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+            }
+        }
+        requests
+    }
+
+    /// Answers a request; `Ok` fields are merged into `{"id": .., "ok": true}`
+    pub fn reply(&mut self, client: u64, id: Value, result: Result<Value, String>) {
+        let response = match result {
+            Ok(Value::Object(fields)) => {
+                let mut response = json!({ "id": id, "ok": true });
+                response.as_object_mut().expect("object").extend(fields);
+                response
+            }
+            Ok(_) => json!({ "id": id, "ok": true }),
+            Err(error) => json!({ "id": id, "ok": false, "error": error }),
+        };
+        self.write(client, &response);
+    }
+
+    /// Starts streaming events to `client`, replacing its previous subscription
+    pub fn subscribe(&mut self, client: u64, subscription: Subscription) {
+        if let Some(c) = self.clients.get_mut(&client) {
+            c.subscription = Some(subscription);
+        }
+    }
+
+    /// Whether anyone subscribed to the output of the tab titled `title`
+    pub fn wants_output(&self, title: &str) -> bool {
+        self.clients
+            .values()
+            .any(|c| c.subscription.as_ref().is_some_and(|s| s.wants(title, OUTPUT_EVENT)))
+    }
+
+    /// Sends `{"event": event, "pane": .., "tab": .., ...fields}` to matching subscribers
+    pub fn publish(&mut self, index: usize, title: &str, event: &str, fields: Value) {
+        let targets: Vec<u64> = self
+            .clients
+            .iter()
+            .filter(|(_, c)| c.subscription.as_ref().is_some_and(|s| s.wants(title, event)))
+            .map(|(id, _)| *id)
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+        let mut message = json!({ "event": event, "pane": index + 1, "tab": title });
+        if let (Some(message), Value::Object(fields)) = (message.as_object_mut(), fields) {
+            message.extend(fields);
+        }
+        for client in targets {
+            self.write(client, &message);
+        }
+    }
+
+    /// Queues one line, dropping the client when it is gone or its queue is full
+    fn write(&mut self, client: u64, message: &Value) {
+        let Some(c) = self.clients.get_mut(&client) else {
+            return;
+        };
+        if c.writer.try_send(message.to_string()).is_err() {
+            self.clients.remove(&client);
+        }
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+const USAGE: &str = "\
+Usage: audio-toolkit-shell ctl [--socket PATH] [--json] <command> [options]
+
+Commands:
+  list                                  List tabs
+  send --tab TAB [--enter] TEXT...      Type TEXT into a tab
+  keys --tab TAB KEY...                 Send keys such as Ctrl+C, Up, Enter
+  restart --tab TAB                     Restart a tab
+  focus --tab TAB                       Focus a pane
+  screen --tab TAB                      Print the visible screen
+  scrollback --tab TAB [--lines N]      Print recent output as plain text
+  subscribe [--tab TAB] [EVENT...]      Stream output, pattern, exit, ... events as JSON lines
+
+TAB is a tab title or a pane number. The socket defaults to [control] socket in config.toml.";
+
+/// Parsed `ctl` command line
+#[derive(Debug, PartialEq)]
+struct CliCommand {
+    socket: Option<PathBuf>,
+    /// Print responses as JSON instead of text
+    json: bool,
+    request: Value,
+}
+
+fn parse_cli(args: &[String]) -> Result<CliCommand, String> {
+    let mut socket = None;
+    let mut json_output = false;
+    let mut tab = None;
+    let mut enter = false;
+    let mut lines = None;
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().cloned().ok_or_else(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "--socket" => socket = Some(PathBuf::from(value(arg)?)),
+            "--tab" => tab = Some(value(arg)?),
+            "--lines" => {
+                let n = value(arg)?;
+                lines = Some(n.parse::<usize>().map_err(|_| format!("--lines: not a number: {:?}", n))?);
+            }
+            "--enter" => enter = true,
+            "--json" => json_output = true,
+            "-h" | "--help" => return Err(String::new()),
+            "--" => positional.extend(args.by_ref().cloned()),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}", flag)),
+            _ => positional.push(arg.clone()),
+        }
+    }
+    let Some((command, rest)) = positional.split_first() else {
+        return Err("missing command".to_string());
+    };
+    let tab = tab.map(|t| match t.parse::<usize>() {
+        Ok(n) => json!(n),
+        Err(_) => json!(t),
+    });
+    let need_tab = || tab.clone().ok_or_else(|| format!("{} needs --tab", command));
+    let request = match command.as_str() {
+        "list" => json!({ "cmd": "list" }),
+        "send" => json!({ "cmd": "send", "tab": need_tab()?, "text": rest.join(" "), "enter": enter }),
+        "keys" if rest.is_empty() => return Err("keys needs at least one key".to_string()),
+        "keys" => json!({ "cmd": "keys", "tab": need_tab()?, "keys": rest }),
+        "restart" | "focus" | "screen" => json!({ "cmd": command, "tab": need_tab()? }),
+        "scrollback" => json!({ "cmd": "scrollback", "tab": need_tab()?, "lines": lines }),
+        "subscribe" => json!({ "cmd": "subscribe", "tab": tab, "events": rest }),
+        _ => return Err(format!("unknown command {:?}", command)),
+    };
+    Ok(CliCommand { socket, json: json_output, request })
+}
+
+/// Socket path from `config.toml`, without creating the file when it is missing
+fn configured_socket() -> PathBuf {
+    fs::read_to_string(config::config_file_path())
+        .ok()
+        .and_then(|content| toml::from_str::<AppConfig>(&content).ok())
+        .map(|config| config.control)
+        .unwrap_or_default()
+        .socket_path()
+}
+
+/// Prints a successful response the way a shell script wants it
+fn print_response(request: &Value, response: &Value) {
+    match request["cmd"].as_str() {
+        Some("list") => {
+            for tab in response["tabs"].as_array().into_iter().flatten() {
+                let status = if tab["running"].as_bool() == Some(true) { "running" } else { "exited" };
+                let focused = if tab["focused"].as_bool() == Some(true) { "\tfocused" } else { "" };
+                println!(
+                    "{}\t{}\t{}\t{}{}",
+                    tab["pane"],
+                    tab["title"].as_str().unwrap_or_default(),
+                    status,
+                    tab["command"].as_str().unwrap_or_default(),
+                    focused
+                );
+            }
+        }
+        Some("screen" | "scrollback") => println!("{}", response["text"].as_str().unwrap_or_default()),
+        _ => {}
+    }
+}
+
+/// Runs `audio-toolkit-shell ctl ...`; returns the process exit code
+pub fn run_cli(args: &[String]) -> i32 {
+    let command = match parse_cli(args) {
+        Ok(command) => command,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("ctl: {}\n", e);
+            }
+            eprintln!("{}", USAGE);
+            return 2;
+        }
+    };
+    let path = command.socket.clone().unwrap_or_else(configured_socket);
+    match send_request(&path, &command) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("ctl: {}", e);
+            1
+        }
+    }
+}
+
+#[cfg(unix)]
+fn send_request(path: &Path, command: &CliCommand) -> Result<(), String> {
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(path).map_err(|e| {
+        format!("cannot connect to {:?}: {} (is the shell running with [control] enabled = true?)", path, e)
+    })?;
+    writeln!(stream, "{}", command.request).map_err(|e| e.to_string())?;
+    let mut lines = BufReader::new(stream).lines();
+    let line = lines
+        .next()
+        .ok_or("the shell closed the connection")?
+        .map_err(|e| e.to_string())?;
+    let response: Value = serde_json::from_str(&line).map_err(|e| format!("invalid response: {}", e))?;
+    if response["ok"].as_bool() != Some(true) {
+        return Err(response["error"].as_str().unwrap_or("request failed").to_string());
+    }
+    if command.json {
+        println!("{}", line);
+    } else {
+        print_response(&command.request, &response);
+    }
+    // Subscriptions stream until the shell exits or the user interrupts
+    if command.request["cmd"] == "subscribe" {
+        let mut stdout = std::io::stdout();
+        for line in lines {
+            let line = line.map_err(|e| e.to_string())?;
+            if writeln!(stdout, "{}", line).and_then(|_| stdout.flush()).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn send_request(_path: &Path, _command: &CliCommand) -> Result<(), String> {
+    Err("the control socket is only available on macOS and Linux".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_request_and_resolve_tab() {
+        let (id, request) = parse_request(r#"{"id": 7, "cmd": "send", "tab": "normalizer", "text": "ls"}"#);
+        assert_eq!(id, json!(7));
+        let Ok(Request::Send { tab, text, enter }) = request else {
+            panic!("expected send");
+        };
+        assert_eq!((text.as_str(), enter), ("ls", false));
+        let titles = ["Shell", "Normalizer"];
+        assert_eq!(tab.resolve(titles), Ok(1));
+        assert_eq!(TabRef::Pane(1).resolve(titles), Ok(0));
+        assert_eq!(TabRef::Title("2".to_string()).resolve(titles), Ok(1));
+        assert!(TabRef::Pane(0).resolve(titles).is_err());
+        assert!(TabRef::Title("Mixer".to_string()).resolve(titles).is_err());
+
+        assert_eq!(parse_request(r#"{"cmd": "list"}"#), (Value::Null, Ok(Request::List)));
+        assert!(parse_request(r#"{"cmd": "explode"}"#).1.is_err());
+        assert!(parse_request("not json").1.is_err());
+    }
+
+    #[test]
+    fn test_encode_keys_and_subscription() {
+        let keys = ["Ctrl+C", "Up", "Enter"].map(str::to_string);
+        assert_eq!(encode_keys(&keys), Ok(b"\x03\x1b[A\r".to_vec()));
+        assert!(encode_keys(&["Q".to_string()]).is_err());
+
+        let all = Subscription::new(None, vec![]).unwrap();
+        assert!(all.wants("Shell", OUTPUT_EVENT));
+        let some = Subscription::new(Some("Normalizer".to_string()), vec!["Pattern".to_string()]).unwrap();
+        assert!(some.wants("normalizer", "pattern"));
+        assert!(!some.wants("normalizer", OUTPUT_EVENT));
+        assert!(!some.wants("Shell", "pattern"));
+        assert!(Subscription::new(None, vec!["bogus".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_history_keeps_last_lines() {
+        let mut history = History::new(3);
+        history.push("\x1b[32mone\x1b[0m\r\ntwo\r\nthr");
+        assert_eq!(history.tail(10), "one\ntwo\nthr");
+        history.push("ee\r\nfour\r\n");
+        assert_eq!(history.tail(10), "two\nthree\nfour");
+        assert_eq!(history.tail(1), "four");
+    }
+
+    #[test]
+    fn test_parse_cli() {
+        let send = parse_cli(&args("send --tab Normalizer --enter normalize -23 take1.wav")).unwrap();
+        assert_eq!(
+            send.request,
+            json!({ "cmd": "send", "tab": "Normalizer", "text": "normalize -23 take1.wav", "enter": true })
+        );
+        let keys = parse_cli(&args("--socket /tmp/a.sock keys --tab 2 Ctrl+C")).unwrap();
+        assert_eq!(keys.socket, Some(PathBuf::from("/tmp/a.sock")));
+        assert_eq!(keys.request, json!({ "cmd": "keys", "tab": 2, "keys": ["Ctrl+C"] }));
+        let subscribe = parse_cli(&args("--json subscribe output pattern")).unwrap();
+        assert!(subscribe.json);
+        assert_eq!(subscribe.request, json!({ "cmd": "subscribe", "tab": null, "events": ["output", "pattern"] }));
+        // Every request the CLI builds is one the server accepts
+        for command in [send, keys, subscribe] {
+            assert!(parse_request(&command.request.to_string()).1.is_ok());
+        }
+        assert!(parse_cli(&args("screen")).is_err());
+        assert!(parse_cli(&args("keys --tab 1")).is_err());
+        assert!(parse_cli(&args("explode")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_server_roundtrip() {
+        use std::os::unix::net::UnixStream;
+        use std::time::{Duration, Instant};
+
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("control.sock");
+        let mut server = ControlServer::start(path.clone()).expect("server");
+        assert!(ControlServer::start(path.clone()).is_err());
+
+        let stream = UnixStream::connect(&path).expect("connect");
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        writeln!(writer, r#"{{"id": "a", "cmd": "subscribe", "events": ["output"]}}"#).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let request = loop {
+            if let Some(request) = server.poll().pop() {
+                break request;
+            }
+            assert!(Instant::now() < deadline, "no request received");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert!(matches!(request.request, Ok(Request::Subscribe { tab: None, .. })));
+        server.subscribe(request.client, Subscription::new(None, vec!["output".to_string()]).unwrap());
+        server.reply(request.client, request.id, Ok(json!({})));
+        assert!(server.wants_output("Shell"));
+        server.publish(0, "Shell", "exit", json!({}));
+        server.publish(0, "Shell", OUTPUT_EVENT, json!({ "data": "hi\r\n" }));
+
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&line).unwrap(), json!({ "id": "a", "ok": true }));
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&line).unwrap(),
+            json!({ "event": "output", "pane": 1, "tab": "Shell", "data": "hi\r\n" })
+        );
+
+        // A subscriber that stops reading is dropped instead of stalling the publisher
+        let started = Instant::now();
+        let data = "x".repeat(4096);
+        for _ in 0..CLIENT_QUEUE * 4 {
+            server.publish(0, "Shell", OUTPUT_EVENT, json!({ "data": data }));
+        }
+        assert!(!server.wants_output("Shell"));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        drop(server);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_start_keeps_files_that_are_not_sockets() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "keep me").unwrap();
+        let error = ControlServer::start(path.clone()).err().expect("refused");
+        assert!(error.contains("not a socket"), "{}", error);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "keep me");
+
+        // A socket left behind by a crash is replaced
+        let stale = dir.path().join("stale.sock");
+        drop(std::os::unix::net::UnixListener::bind(&stale).unwrap());
+        assert!(ControlServer::start(stale).is_ok());
+    }
+}
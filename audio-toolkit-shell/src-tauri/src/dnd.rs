@@ -0,0 +1,476 @@
+//! # Drag-and-Drop Module
+//!
+//! This module turns files and folders dropped on a pane into the text typed
+//! into it, following the pane's `[tabs.dnd]` settings.
+//!
+//! ## Templates
+//!
+//! Each dropped item uses the first of:
+//!
+//! 1. the first `[[tabs.dnd.rules]]` entry listing its extension (case-insensitive)
+//! 2. `on_folder` for folders, `on_file` for files
+//! 3. for a single dropped folder, `auto_cd_on_folder_drop` or `auto_run_on_folder_drop`
+//! 4. the quoted path
+//!
+//! ## Placeholders
+//!
+//! All values are shell-quoted: `{path}`, `{dir}` (the parent folder), `{name}`,
+//! `{stem}` (the name without extension), `{ext}` (without the dot) and `{paths}`
+//! (every item using the same template, space-separated). Quoted values may be
+//! followed by plain text, so `{stem}.mp3` becomes `'take 1'.mp3`. Write `{{`
+//! and `}}` for literal braces.
+//!
+//! A template with `{paths}` is typed once for all its items, any other once per
+//! item. Commands that press Enter are typed first, each followed by Enter; the
+//! others come after them on the prompt, joined with spaces and ending with one,
+//! ready for more input.
+//!
+//! ## Queue
+//!
+//! With `mode = "queue"` each item's command runs on its own, one after the
+//! other, and Enter is always pressed. With `queue_wait = "exit"` the command is
+//! followed by a `printf` of an `OSC 777;ats-queue;<seq>;<status>` marker that
+//! carries the exit status; with `"pattern"` an item is done when one of the
+//! tab's success patterns appears. [`DropQueue`] tracks progress and the pause,
+//! skip and cancel controls.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+use crate::bookmarks;
+use crate::config::{DndSettings, QueueWait};
+
+/// Start of the completion marker printed after queued commands
+const QUEUE_MARK: &str = "\x1b]777;ats-queue;";
+
+/// Placeholders a drop template may use
+pub const PLACEHOLDERS: &[&str] = &["path", "dir", "name", "stem", "ext", "paths"];
+
+/// A dropped file or folder
+#[derive(Debug, Clone, PartialEq)]
+pub struct DropItem {
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+impl DropItem {
+    /// Looks up whether `path` is a folder; missing paths count as files
+    pub fn new(path: PathBuf) -> Self {
+        let is_dir = std::fs::metadata(&path).map(|m| m.is_dir()).unwrap_or(false);
+        Self { path, is_dir }
+    }
+
+    /// Lower-case extension without the dot; empty when there is none
+    fn extension(&self) -> String {
+        self.path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default()
+    }
+}
+
+/// One command typed into the pane for a drop
+#[derive(Debug, Clone, PartialEq)]
+pub struct DropCommand {
+    pub text: String,
+    /// Press Enter after typing
+    pub enter: bool,
+}
+
+/// Template and Enter choice for one item
+fn template_for(settings: &DndSettings, item: &DropItem, single: bool) -> (String, bool) {
+    let ext = item.extension();
+    if !ext.is_empty() {
+        let rule = settings
+            .rules
+            .iter()
+            .find(|rule| rule.extensions.iter().any(|e| e.trim().trim_start_matches('.').eq_ignore_ascii_case(&ext)));
+        if let Some(rule) = rule {
+            return (rule.template.clone(), rule.enter);
+        }
+    }
+    if item.is_dir {
+        if !settings.on_folder.trim().is_empty() {
+            return (settings.on_folder.clone(), settings.on_folder_enter);
+        }
+        if single && settings.auto_cd_on_folder_drop {
+            return ("cd {path}".to_string(), true);
+        }
+        if single && settings.auto_run_on_folder_drop {
+            return ("{path}".to_string(), true);
+        }
+    } else if !settings.on_file.trim().is_empty() {
+        return (settings.on_file.clone(), settings.on_file_enter);
+    }
+    ("{path}".to_string(), false)
+}
+
+/// Placeholder values for `item`, already quoted
+fn values_for(item: &DropItem, quote: &impl Fn(&str) -> String) -> BTreeMap<String, String> {
+    let text = |value: Option<&std::ffi::OsStr>| value.map(|v| v.to_string_lossy().into_owned()).unwrap_or_default();
+    let dir = item.path.parent().map(Path::as_os_str);
+    [
+        ("path", item.path.to_string_lossy().into_owned()),
+        ("dir", text(dir)),
+        ("name", text(item.path.file_name())),
+        ("stem", text(item.path.file_stem())),
+        ("ext", text(item.path.extension())),
+    ]
+    .into_iter()
+    .map(|(name, value)| {
+        let value = if value.is_empty() { value } else { quote(&value) };
+        (name.to_string(), value)
+    })
+    .collect()
+}
+
+/// Commands for a drop of `items`: those pressing Enter first, then the rest,
+/// each in drop order
+pub fn plan(settings: &DndSettings, items: &[DropItem], quote: impl Fn(&str) -> String) -> Vec<DropCommand> {
+    let single = items.len() == 1;
+    // Items grouped by template, in order of first appearance
+    let mut groups: Vec<((String, bool), Vec<&DropItem>)> = Vec::new();
+    for item in items {
+        let key = template_for(settings, item, single);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, members)) => members.push(item),
+            None => groups.push((key, vec![item])),
+        }
+    }
+
+    let mut commands = Vec::new();
+    for ((template, enter), members) in groups {
+        if bookmarks::placeholders(&template).iter().any(|name| name == "paths") {
+            let mut values = values_for(members[0], &quote);
+            let paths = members.iter().map(|item| quote(&item.path.to_string_lossy())).collect::<Vec<_>>();
+            values.insert("paths".to_string(), paths.join(" "));
+            commands.push(DropCommand { text: bookmarks::expand(&template, &values, |v| v.to_string()), enter });
+        } else {
+            for item in members {
+                let values = values_for(item, &quote);
+                commands.push(DropCommand { text: bookmarks::expand(&template, &values, |v| v.to_string()), enter });
+            }
+        }
+    }
+    // Text left on the prompt must not end up in front of a command that runs
+    commands.sort_by_key(|command| !command.enter);
+    commands
+}
+
+/// Text to write to the PTY for `commands`
+pub fn to_input(commands: &[DropCommand]) -> String {
+    let mut out = String::new();
+    for command in commands {
+        out.push_str(&command.text);
+        out.push(if command.enter { '\n' } else { ' ' });
+    }
+    out
+}
+
+/// One queued item: its file name and the command typed for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueJob {
+    pub label: String,
+    pub command: String,
+}
+
+/// Queue jobs for `items`, one per item in drop order
+pub fn queue_jobs(settings: &DndSettings, items: &[DropItem], quote: impl Fn(&str) -> String) -> Vec<QueueJob> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let command = plan(settings, std::slice::from_ref(item), &quote).pop()?;
+            let label = item.path.file_name().unwrap_or(item.path.as_os_str()).to_string_lossy().into_owned();
+            Some(QueueJob { label, command: command.text })
+        })
+        .collect()
+}
+
+/// Items dropped on a pane in queue mode, run one at a time
+#[derive(Debug, Default)]
+pub struct DropQueue {
+    wait: QueueWait,
+    pending: VecDeque<QueueJob>,
+    /// Sequence number and label of the job waiting to finish
+    running: Option<(u64, String)>,
+    last_seq: u64,
+    /// Do not start further jobs
+    pub paused: bool,
+    total: usize,
+    done: usize,
+    failed: usize,
+    skipped: usize,
+    cancelled: usize,
+    /// Unterminated marker from the previous output chunk
+    partial: String,
+}
+
+impl DropQueue {
+    pub fn new(wait: QueueWait) -> Self {
+        Self { wait, ..Default::default() }
+    }
+
+    /// Adds jobs to the end of the queue
+    pub fn push(&mut self, jobs: Vec<QueueJob>) {
+        self.total += jobs.len();
+        self.pending.extend(jobs);
+    }
+
+    /// Text to type for the next job, when none is running and the queue is not paused
+    pub fn next(&mut self) -> Option<String> {
+        if self.running.is_some() || self.paused {
+            return None;
+        }
+        let job = self.pending.pop_front()?;
+        self.last_seq += 1;
+        let text = match self.wait {
+            QueueWait::Exit => format!(
+                "{}; printf '\\033]777;ats-queue;{};%d\\007' \"$?\"",
+                job.command, self.last_seq
+            ),
+            QueueWait::Pattern => job.command,
+        };
+        self.running = Some((self.last_seq, job.label));
+        Some(text)
+    }
+
+    /// Looks for the completion of the running job in a chunk of output
+    ///
+    /// `raw` is the output as received (for the exit marker), `plain` the same
+    /// without escapes (for `patterns`).
+    pub fn observe(&mut self, raw: &str, plain: &str, patterns: &[String]) {
+        match self.wait {
+            QueueWait::Exit => self.scan_markers(raw),
+            QueueWait::Pattern => {
+                if self.running.is_some() && patterns.iter().any(|p| !p.is_empty() && plain.contains(p.as_str())) {
+                    self.finish(true);
+                }
+            }
+        }
+    }
+
+    fn scan_markers(&mut self, data: &str) {
+        let mut text = std::mem::take(&mut self.partial);
+        text.push_str(data);
+        let mut rest = text.as_str();
+        while let Some(start) = rest.find(QUEUE_MARK) {
+            let body = &rest[start + QUEUE_MARK.len()..];
+            let Some(end) = body.find('\x07') else {
+                // Bounded so a stray marker cannot grow without limit
+                if body.len() < 64 {
+                    self.partial = rest[start..].to_string();
+                }
+                return;
+            };
+            let mut fields = body[..end].split(';');
+            let seq = fields.next().and_then(|f| f.parse::<u64>().ok());
+            let status = fields.next().and_then(|f| f.parse::<i32>().ok());
+            // Markers of skipped jobs arrive late and are ignored
+            if seq.is_some() && seq == self.running.as_ref().map(|(s, _)| *s) {
+                self.finish(status == Some(0));
+            }
+            rest = &body[end + 1..];
+        }
+        // A marker may be cut at the end of the chunk
+        if let Some(start) = rest.rfind('\x1b') {
+            if QUEUE_MARK.starts_with(&rest[start..]) {
+                self.partial = rest[start..].to_string();
+            }
+        }
+    }
+
+    fn finish(&mut self, success: bool) {
+        if self.running.take().is_some() {
+            if success {
+                self.done += 1;
+            } else {
+                self.failed += 1;
+            }
+        }
+    }
+
+    /// Stops waiting for the running job; returns whether there was one
+    pub fn skip(&mut self) -> bool {
+        let skipped = self.running.take().is_some();
+        if skipped {
+            self.skipped += 1;
+        }
+        skipped
+    }
+
+    /// Counts the running job as failed, e.g. when its pane restarted
+    pub fn interrupt(&mut self) {
+        self.finish(false);
+    }
+
+    /// Drops the jobs that have not started; the running one may still finish
+    pub fn cancel(&mut self) {
+        self.cancelled += self.pending.len();
+        self.pending.clear();
+    }
+
+    /// No job is running or waiting
+    pub fn is_finished(&self) -> bool {
+        self.running.is_none() && self.pending.is_empty()
+    }
+
+    /// Label of the running job
+    pub fn current(&self) -> Option<&str> {
+        self.running.as_ref().map(|(_, label)| label.as_str())
+    }
+
+    /// Progress as (jobs started, total)
+    pub fn progress(&self) -> (usize, usize) {
+        let started = self.done + self.failed + self.skipped + usize::from(self.running.is_some());
+        (started, self.total - self.cancelled)
+    }
+
+    /// Counts of finished jobs, e.g. "3 done, 1 failed"
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = [
+            (self.done, "done"),
+            (self.failed, "failed"),
+            (self.skipped, "skipped"),
+            (self.cancelled, "cancelled"),
+        ]
+        .into_iter()
+        .filter(|&(count, _)| count > 0)
+        .map(|(count, name)| format!("{} {}", count, name))
+        .collect();
+        if parts.is_empty() {
+            "nothing run".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Placeholders in `template` that drops do not fill in
+pub fn unknown_placeholders(template: &str) -> Vec<String> {
+    bookmarks::placeholders(template)
+        .into_iter()
+        .filter(|name| !PLACEHOLDERS.contains(&name.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DropRule;
+
+    use crate::bookmarks::test_quote as quote;
+
+    fn file(path: &str) -> DropItem {
+        DropItem { path: PathBuf::from(path), is_dir: false }
+    }
+
+    fn folder(path: &str) -> DropItem {
+        DropItem { path: PathBuf::from(path), is_dir: true }
+    }
+
+    #[test]
+    fn test_default_drop_types_quoted_paths() {
+        let settings = DndSettings::default();
+        let commands = plan(&settings, &[file("/a/x.wav"), file("/a/y z.wav")], quote);
+        assert_eq!(to_input(&commands), "'/a/x.wav' '/a/y z.wav' ");
+        let commands = plan(&settings, &[folder("/a/b")], quote);
+        assert_eq!(to_input(&commands), "'/a/b' ");
+    }
+
+    #[test]
+    fn test_folder_flags_only_apply_to_single_folder() {
+        let settings = DndSettings { auto_cd_on_folder_drop: true, ..Default::default() };
+        assert_eq!(to_input(&plan(&settings, &[folder("/a/b")], quote)), "cd '/a/b'\n");
+        assert_eq!(to_input(&plan(&settings, &[folder("/a/b"), folder("/a/c")], quote)), "'/a/b' '/a/c' ");
+    }
+
+    #[test]
+    fn test_rules_and_templates() {
+        let settings = DndSettings {
+            on_file: "ffmpeg -i {path} {stem}.mp3".to_string(),
+            on_folder: "cd {path}".to_string(),
+            on_folder_enter: true,
+            rules: vec![DropRule {
+                extensions: vec![".MP4".to_string(), "mov".to_string()],
+                template: "extract {name} --ext {ext} --out {dir}".to_string(),
+                enter: true,
+            }],
+            ..Default::default()
+        };
+        let commands = plan(&settings, &[file("/v/clip.mp4"), file("/s/take 1.wav"), folder("/p")], quote);
+        assert_eq!(
+            commands,
+            vec![
+                DropCommand { text: "extract 'clip.mp4' --ext 'mp4' --out '/v'".to_string(), enter: true },
+                DropCommand { text: "cd '/p'".to_string(), enter: true },
+                DropCommand { text: "ffmpeg -i '/s/take 1.wav' 'take 1'.mp3".to_string(), enter: false },
+            ]
+        );
+        // Each Enter command is on a line of its own; the rest waits on the prompt
+        assert_eq!(
+            to_input(&commands),
+            "extract 'clip.mp4' --ext 'mp4' --out '/v'\ncd '/p'\nffmpeg -i '/s/take 1.wav' 'take 1'.mp3 "
+        );
+    }
+
+    #[test]
+    fn test_paths_placeholder_expands_once() {
+        let settings = DndSettings {
+            rules: vec![DropRule { extensions: vec!["wav".to_string()], template: "sox {paths} mix.wav".to_string(), enter: true }],
+            ..Default::default()
+        };
+        let commands = plan(&settings, &[file("/b.txt"), file("/a.wav"), file("/c.WAV")], quote);
+        assert_eq!(to_input(&commands), "sox '/a.wav' '/c.WAV' mix.wav\n'/b.txt' ");
+    }
+
+    #[test]
+    fn test_queue_waits_for_exit_markers() {
+        let settings = DndSettings { on_file: "conv {path}".to_string(), ..Default::default() };
+        let mut queue = DropQueue::new(QueueWait::Exit);
+        queue.push(queue_jobs(&settings, &[file("/a.wav"), file("/b.wav"), file("/c.wav")], quote));
+        assert_eq!(queue.next().as_deref(), Some("conv '/a.wav'; printf '\\033]777;ats-queue;1;%d\\007' \"$?\""));
+        assert_eq!(queue.current(), Some("a.wav"));
+        assert_eq!(queue.next(), None);
+
+        // The echoed command line does not count; the marker may be split across chunks
+        queue.observe("conv '/a.wav'; printf '\\033]777;ats-queue;1;%d\\007'\r\n\x1b]77", "", &[]);
+        assert_eq!(queue.current(), Some("a.wav"));
+        queue.observe("7;ats-queue;1;0\x07$ ", "", &[]);
+        assert_eq!(queue.progress(), (1, 3));
+
+        assert!(queue.next().is_some());
+        assert!(queue.skip());
+        // The skipped job's late marker is ignored
+        assert!(queue.next().is_some());
+        queue.observe("\x1b]777;ats-queue;2;130\x07", "", &[]);
+        assert_eq!(queue.current(), Some("c.wav"));
+        queue.observe("\x1b]777;ats-queue;3;1\x07", "", &[]);
+        assert!(queue.is_finished());
+        assert_eq!(queue.summary(), "1 done, 1 failed, 1 skipped");
+    }
+
+    #[test]
+    fn test_queue_pattern_pause_and_cancel() {
+        let mut queue = DropQueue::new(QueueWait::Pattern);
+        let job = |label: &str| QueueJob { label: label.to_string(), command: format!("'{}'", label) };
+        queue.push(vec![job("a"), job("b"), job("c")]);
+        assert_eq!(queue.next().as_deref(), Some("'a'"));
+        let patterns = vec!["Completed".to_string()];
+        queue.observe("", "working", &patterns);
+        assert_eq!(queue.current(), Some("a"));
+        queue.observe("", "Completed successfully", &patterns);
+        queue.paused = true;
+        assert_eq!(queue.next(), None);
+        queue.paused = false;
+        assert_eq!(queue.next().as_deref(), Some("'b'"));
+        queue.cancel();
+        assert_eq!(queue.progress(), (2, 2));
+        assert!(!queue.is_finished());
+        queue.interrupt();
+        assert!(queue.is_finished());
+        assert_eq!(queue.summary(), "1 done, 1 failed, 1 cancelled");
+    }
+
+    #[test]
+    fn test_unknown_placeholders() {
+        assert!(unknown_placeholders("cp {path} {dir}/{{x}}").is_empty());
+        assert_eq!(unknown_placeholders("cp {file} {out}"), vec!["file", "out"]);
+    }
+}
@@ -0,0 +1,720 @@
+//! # File Manager Module
+//!
+//! This module implements the File Manager side panel: a directory browser
+//! limited to audio files, header details read from WAV/AIFF files, extension
+//! filtering and multi-selection. Selected files are handed back to the app,
+//! which types them into the focused pane as shell-quoted arguments.
+//!
+//! ## Header Support
+//!
+//! | Format          | Details read                                       |
+//! |-----------------|----------------------------------------------------|
+//! | WAV (RIFF/RF64) | `fmt ` and `data` chunks (`ds64` sizes for RF64)   |
+//! | AIFF / AIFF-C   | `COMM` chunk (80-bit extended sample rate)         |
+//!
+//! Other audio types (FLAC, MP3, ...) are listed with their size only.
+//!
+//! Headers are read on a background thread after a folder is listed, so a folder
+//! with thousands of takes opens at once and the details fill in as they arrive.
+//! The extension filter works on the cached listing without touching the disk.
+
+use eframe::egui;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crate::theme::Palette;
+
+/// Extensions listed when the filter is empty
+pub const AUDIO_EXTENSIONS: &[&str] = &["wav", "wave", "bwf", "aif", "aiff", "aifc", "flac", "mp3", "ogg", "m4a"];
+
+/// Extensions whose headers [`read_audio_info`] understands
+const HEADER_EXTENSIONS: &[&str] = &["wav", "wave", "bwf", "rf64", "aif", "aiff", "aifc"];
+
+/// Format details read from an audio file header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioInfo {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+    /// Sample frames (samples per channel)
+    pub frames: u64,
+}
+
+impl AudioInfo {
+    /// Duration in seconds
+    pub fn duration(&self) -> f64 {
+        if self.sample_rate == 0 {
+            0.0
+        } else {
+            self.frames as f64 / self.sample_rate as f64
+        }
+    }
+}
+
+/// Reads format details from a WAV or AIFF file
+///
+/// # Returns
+///
+/// `None` for other formats and for files whose header cannot be parsed
+pub fn read_audio_info(path: &Path) -> Option<AudioInfo> {
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    let mut magic = [0u8; 12];
+    reader.read_exact(&mut magic).ok()?;
+    match (&magic[0..4], &magic[8..12]) {
+        (b"RIFF" | b"RF64", b"WAVE") => read_wav_chunks(&mut reader),
+        (b"FORM", b"AIFF" | b"AIFC") => read_aiff_chunks(&mut reader),
+        _ => None,
+    }
+}
+
+/// Walks RIFF chunks after the `WAVE` tag until both `fmt ` and `data` are seen
+fn read_wav_chunks<R: Read + Seek>(reader: &mut R) -> Option<AudioInfo> {
+    let mut format: Option<(u16, u32, u16, u16)> = None; // channels, rate, block align, bits
+    let mut data_size: Option<u64> = None;
+    let mut ds64_data_size: Option<u64> = None;
+    loop {
+        let mut header = [0u8; 8];
+        if reader.read_exact(&mut header).is_err() {
+            break;
+        }
+        let id = [header[0], header[1], header[2], header[3]];
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as u64;
+        match &id {
+            b"fmt " => {
+                let mut body = [0u8; 16];
+                reader.read_exact(&mut body).ok()?;
+                format = Some((
+                    u16::from_le_bytes([body[2], body[3]]),
+                    u32::from_le_bytes([body[4], body[5], body[6], body[7]]),
+                    u16::from_le_bytes([body[12], body[13]]),
+                    u16::from_le_bytes([body[14], body[15]]),
+                ));
+                skip(reader, size.checked_sub(16)? + size % 2)?;
+            }
+            b"ds64" => {
+                let mut body = [0u8; 16];
+                reader.read_exact(&mut body).ok()?;
+                ds64_data_size = Some(u64::from_le_bytes(body[8..16].try_into().ok()?));
+                skip(reader, size.checked_sub(16)? + size % 2)?;
+            }
+            b"data" => {
+                // RF64 stores 0xFFFFFFFF here and the real size in ds64
+                data_size = Some(if size == u32::MAX as u64 { ds64_data_size? } else { size });
+                if format.is_some() {
+                    break;
+                }
+                skip(reader, size + size % 2)?;
+            }
+            _ => skip(reader, size + size % 2)?,
+        }
+        if format.is_some() && data_size.is_some() {
+            break;
+        }
+    }
+    let (channels, sample_rate, block_align, bits_per_sample) = format?;
+    let frames = if block_align == 0 { 0 } else { data_size.unwrap_or(0) / block_align as u64 };
+    Some(AudioInfo { sample_rate, channels, bits_per_sample, frames })
+}
+
+/// Walks IFF chunks after the `AIFF`/`AIFC` tag until `COMM` is seen
+fn read_aiff_chunks<R: Read + Seek>(reader: &mut R) -> Option<AudioInfo> {
+    loop {
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header).ok()?;
+        let size = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as u64;
+        if &header[0..4] == b"COMM" {
+            let mut body = [0u8; 18];
+            reader.read_exact(&mut body).ok()?;
+            return Some(AudioInfo {
+                channels: u16::from_be_bytes([body[0], body[1]]),
+                frames: u32::from_be_bytes([body[2], body[3], body[4], body[5]]) as u64,
+                bits_per_sample: u16::from_be_bytes([body[6], body[7]]),
+                sample_rate: extended_to_f64(body[8..18].try_into().ok()?).round() as u32,
+            });
+        }
+        skip(reader, size + size % 2)?;
+    }
+}
+
+/// Converts an IEEE 754 80-bit extended float (as used by AIFF) to `f64`
+fn extended_to_f64(bytes: [u8; 10]) -> f64 {
+    let sign_exp = u16::from_be_bytes([bytes[0], bytes[1]]);
+    let mantissa = u64::from_be_bytes(bytes[2..10].try_into().unwrap_or_default());
+    if mantissa == 0 {
+        return 0.0;
+    }
+    let exponent = (sign_exp & 0x7FFF) as i32 - 16383 - 63;
+    let value = mantissa as f64 * 2f64.powi(exponent);
+    if sign_exp & 0x8000 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+fn skip<R: Seek>(reader: &mut R, bytes: u64) -> Option<()> {
+    reader.seek(SeekFrom::Current(i64::try_from(bytes).ok()?)).ok().map(|_| ())
+}
+
+/// Parses a comma/space separated extension filter such as `"wav, .aiff"`
+///
+/// An empty filter means [`AUDIO_EXTENSIONS`]; `"*"` matches every file.
+pub fn parse_extension_filter(filter: &str) -> Vec<String> {
+    let exts: Vec<String> = filter
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|e| e.trim().trim_start_matches("*.").trim_start_matches('.').to_ascii_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect();
+    if exts.is_empty() {
+        AUDIO_EXTENSIONS.iter().map(|e| e.to_string()).collect()
+    } else {
+        exts
+    }
+}
+
+/// One row of the listing
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub is_dir: bool,
+    pub size: u64,
+    /// Lower-case extension, empty for folders
+    pub ext: String,
+}
+
+/// Lists `dir`: subdirectories first, then files
+///
+/// Hidden entries (leading `.`) are skipped and names sort case-insensitively.
+/// Headers are not read here; see [`read_audio_info`].
+pub fn list_dir(dir: &Path) -> io::Result<Vec<FileEntry>> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        // Follow symlinks so linked folders and files behave like the real thing
+        let Ok(meta) = std::fs::metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            entries.push(FileEntry { path, name, is_dir: true, size: 0, ext: String::new() });
+            continue;
+        }
+        let ext = path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+        entries.push(FileEntry { path, name, is_dir: false, size: meta.len(), ext });
+    }
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    Ok(entries)
+}
+
+/// Folders, and the files whose extension is in `extensions` (`"*"` keeps every file)
+pub fn filter_entries(entries: &[FileEntry], extensions: &[String]) -> Vec<FileEntry> {
+    let all = extensions.iter().any(|e| e == "*");
+    entries.iter().filter(|e| e.is_dir || all || extensions.contains(&e.ext)).cloned().collect()
+}
+
+/// Reads the headers of the WAV/AIFF files among `entries` on a background thread
+///
+/// Results arrive one file at a time; the thread stops early once the receiver is dropped.
+fn read_headers(entries: &[FileEntry]) -> Receiver<(PathBuf, Option<AudioInfo>)> {
+    let paths: Vec<PathBuf> = entries
+        .iter()
+        .filter(|e| !e.is_dir && HEADER_EXTENSIONS.contains(&e.ext.as_str()))
+        .map(|e| e.path.clone())
+        .collect();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for path in paths {
+            let info = read_audio_info(&path);
+            if tx.send((path, info)).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Human-readable byte size (`"1.4 MB"`)
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Duration as `m:ss.s`, or `h:mm:ss` from one hour up
+pub fn format_duration(seconds: f64) -> String {
+    let tenths = (seconds * 10.0).round() as u64;
+    let (h, m, s) = (tenths / 36000, tenths / 600 % 60, tenths % 600);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s / 10)
+    } else {
+        format!("{}:{:02}.{}", m, s / 10, s % 10)
+    }
+}
+
+/// Something the panel asks the app to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileManagerEvent {
+    /// Type these paths into the focused pane
+    Send(Vec<PathBuf>),
+    /// Re-root the browser at the focused pane's working directory
+    FollowPane,
+}
+
+/// State of the File Manager panel
+pub struct FileManager {
+    pub open: bool,
+    dir: PathBuf,
+    /// Text of the path field; navigates on Enter
+    path_input: String,
+    /// Extension filter as typed (see [`parse_extension_filter`])
+    filter: String,
+    /// Everything in `dir`, as last read from disk
+    listing: Vec<FileEntry>,
+    /// `listing` after the filter; rows of the panel
+    entries: Vec<FileEntry>,
+    /// Headers read so far for the current listing
+    infos: HashMap<PathBuf, AudioInfo>,
+    /// Headers still being read
+    headers: Option<Receiver<(PathBuf, Option<AudioInfo>)>>,
+    /// Folder still being looked up (see [`FileManager::navigate_with`])
+    pending_dir: Option<Receiver<PathBuf>>,
+    selected: BTreeSet<PathBuf>,
+    /// Row index that Shift+click extends from
+    anchor: Option<usize>,
+    error: Option<String>,
+}
+
+impl FileManager {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            dir: PathBuf::new(),
+            path_input: String::new(),
+            filter: String::new(),
+            listing: Vec::new(),
+            entries: Vec::new(),
+            infos: HashMap::new(),
+            headers: None,
+            pending_dir: None,
+            selected: BTreeSet::new(),
+            anchor: None,
+            error: None,
+        }
+    }
+
+    /// Opens `dir`, clearing the selection
+    pub fn navigate(&mut self, dir: PathBuf) {
+        self.dir = dir;
+        self.path_input = self.dir.to_string_lossy().into_owned();
+        self.selected.clear();
+        self.anchor = None;
+        self.refresh();
+    }
+
+    /// Opens the folder `find` returns; `find` runs on a background thread
+    /// because looking up a process's folder can be slow (`lsof` on macOS)
+    pub fn navigate_with(&mut self, find: impl FnOnce() -> PathBuf + Send + 'static) {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(find());
+        });
+        self.pending_dir = Some(rx);
+    }
+
+    /// Re-reads the current directory, keeping selected paths that still exist
+    pub fn refresh(&mut self) {
+        match list_dir(&self.dir) {
+            Ok(listing) => {
+                self.listing = listing;
+                self.error = None;
+            }
+            Err(e) => {
+                self.listing.clear();
+                self.error = Some(format!("{}: {}", self.dir.display(), e));
+            }
+        }
+        self.infos.clear();
+        self.headers = Some(read_headers(&self.listing));
+        self.apply_filter();
+    }
+
+    /// Filters the cached listing again, keeping selected paths that are still shown
+    fn apply_filter(&mut self) {
+        self.entries = filter_entries(&self.listing, &parse_extension_filter(&self.filter));
+        let present: BTreeSet<PathBuf> = self.entries.iter().map(|e| e.path.clone()).collect();
+        self.selected.retain(|p| present.contains(p));
+        self.anchor = None;
+    }
+
+    /// Takes the headers read so far and a looked-up folder, if any
+    ///
+    /// # Returns
+    ///
+    /// Whether anything is still pending
+    pub fn poll(&mut self) -> bool {
+        if let Some(rx) = &self.pending_dir {
+            match rx.try_recv() {
+                Ok(dir) => {
+                    self.pending_dir = None;
+                    self.navigate(dir);
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.pending_dir = None,
+            }
+        }
+        if let Some(rx) = &self.headers {
+            loop {
+                match rx.try_recv() {
+                    Ok((path, Some(info))) => {
+                        self.infos.insert(path, info);
+                    }
+                    Ok((_, None)) => {}
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        self.headers = None;
+                        break;
+                    }
+                }
+            }
+        }
+        self.pending_dir.is_some() || self.headers.is_some()
+    }
+
+    /// Header details of `path`, once read
+    pub fn info(&self, path: &Path) -> Option<AudioInfo> {
+        self.infos.get(path).copied()
+    }
+
+    /// Selected paths in listing order
+    pub fn selection(&self) -> Vec<PathBuf> {
+        self.entries.iter().filter(|e| self.selected.contains(&e.path)).map(|e| e.path.clone()).collect()
+    }
+
+    /// Applies a click on row `index`: plain click selects only that row,
+    /// `toggle` (Ctrl/Cmd) flips it, `extend` (Shift) selects the range from the anchor
+    pub fn click(&mut self, index: usize, toggle: bool, extend: bool) {
+        let Some(entry) = self.entries.get(index) else {
+            return;
+        };
+        match (extend, self.anchor) {
+            (true, Some(anchor)) => {
+                let (lo, hi) = (anchor.min(index), anchor.max(index));
+                if !toggle {
+                    self.selected.clear();
+                }
+                for e in &self.entries[lo..=hi] {
+                    self.selected.insert(e.path.clone());
+                }
+                return;
+            }
+            _ if toggle => {
+                if !self.selected.remove(&entry.path) {
+                    self.selected.insert(entry.path.clone());
+                }
+            }
+            _ => {
+                self.selected.clear();
+                self.selected.insert(entry.path.clone());
+            }
+        }
+        self.anchor = Some(index);
+    }
+
+    /// Draws the panel contents
+    pub fn show(&mut self, ui: &mut egui::Ui, palette: &Palette) -> Option<FileManagerEvent> {
+        let mut event = None;
+        let mut go_to: Option<PathBuf> = None;
+        if self.poll() {
+            ui.ctx().request_repaint_after(Duration::from_millis(100));
+        }
+
+        ui.horizontal(|ui| {
+            ui.heading("📁 Files");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button("✖").on_hover_text("Close").clicked() {
+                    self.open = false;
+                }
+            });
+        });
+        ui.horizontal(|ui| {
+            if ui.small_button("⬆").on_hover_text("Parent folder").clicked() {
+                go_to = self.dir.parent().map(Path::to_path_buf);
+            }
+            if ui.small_button("⟳").on_hover_text("Refresh").clicked() {
+                self.refresh();
+            }
+            if ui.small_button("🖥").on_hover_text("Go to the focused pane's folder").clicked() {
+                event = Some(FileManagerEvent::FollowPane);
+            }
+            let response = ui.add(egui::TextEdit::singleline(&mut self.path_input).desired_width(f32::INFINITY));
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                go_to = Some(PathBuf::from(self.path_input.trim()));
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.filter)
+                    .hint_text("wav, aiff (empty = all audio, * = all files)")
+                    .desired_width(f32::INFINITY),
+            );
+            if response.changed() {
+                self.apply_filter();
+            }
+        });
+        if let Some(err) = &self.error {
+            ui.colored_label(palette.red, err);
+        }
+        ui.separator();
+
+        // Footer first so the list can take the remaining height
+        egui::TopBottomPanel::bottom("file_manager_footer")
+            .frame(egui::Frame::none())
+            .show_inside(ui, |ui| {
+                ui.add_space(4.0);
+                let count = self.selected.len();
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} selected", count));
+                    if ui.small_button("All").clicked() {
+                        self.selected = self.entries.iter().filter(|e| !e.is_dir).map(|e| e.path.clone()).collect();
+                    }
+                    if ui.small_button("None").clicked() {
+                        self.selected.clear();
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let send = ui
+                            .add_enabled(count > 0, egui::Button::new("Send to pane"))
+                            .on_hover_text("Type the selected paths into the focused pane");
+                        if send.clicked() {
+                            event = Some(FileManagerEvent::Send(self.selection()));
+                        }
+                    });
+                });
+            });
+
+        let mut clicked: Option<(usize, bool, bool)> = None;
+        let mut activated: Option<usize> = None;
+        egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+            egui::Grid::new("file_manager_rows").striped(true).num_columns(5).show(ui, |ui| {
+                ui.strong("Name");
+                ui.strong("Size");
+                ui.strong("Format");
+                ui.strong("Duration");
+                ui.end_row();
+                for (i, entry) in self.entries.iter().enumerate() {
+                    let label = if entry.is_dir { format!("📁 {}", entry.name) } else { format!("🎵 {}", entry.name) };
+                    let response = ui.selectable_label(self.selected.contains(&entry.path), label);
+                    if response.double_clicked() {
+                        activated = Some(i);
+                    } else if response.clicked() {
+                        let m = ui.input(|i| i.modifiers);
+                        clicked = Some((i, m.command || m.ctrl, m.shift));
+                    }
+                    if entry.is_dir {
+                        ui.label("");
+                        ui.label("");
+                        ui.label("");
+                    } else {
+                        ui.label(format_size(entry.size));
+                        match self.info(&entry.path) {
+                            Some(info) => {
+                                let channels = match info.channels {
+                                    1 => "mono".to_string(),
+                                    2 => "stereo".to_string(),
+                                    n => format!("{} ch", n),
+                                };
+                                ui.label(format!("{} Hz · {} · {}-bit", info.sample_rate, channels, info.bits_per_sample));
+                                ui.label(format_duration(info.duration()));
+                            }
+                            None if self.headers.is_some() && HEADER_EXTENSIONS.contains(&entry.ext.as_str()) => {
+                                ui.weak("…");
+                                ui.label("");
+                            }
+                            None => {
+                                ui.label("");
+                                ui.label("");
+                            }
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+
+        if let Some((index, toggle, extend)) = clicked {
+            self.click(index, toggle, extend);
+        }
+        if let Some(entry) = activated.and_then(|i| self.entries.get(i)) {
+            if entry.is_dir {
+                go_to = Some(entry.path.clone());
+            } else {
+                event = Some(FileManagerEvent::Send(vec![entry.path.clone()]));
+            }
+        }
+        if let Some(dir) = go_to {
+            self.navigate(dir);
+        }
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn wav_bytes(channels: u16, rate: u32, bits: u16, frames: u32) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let data_len = frames * block_align as u32;
+        let mut b = Vec::new();
+        b.extend_from_slice(b"RIFF");
+        b.extend_from_slice(&(36 + 10 + data_len).to_le_bytes());
+        b.extend_from_slice(b"WAVE");
+        // An unrelated chunk before fmt must be skipped
+        b.extend_from_slice(b"LIST");
+        b.extend_from_slice(&2u32.to_le_bytes());
+        b.extend_from_slice(b"ab");
+        b.extend_from_slice(b"fmt ");
+        b.extend_from_slice(&16u32.to_le_bytes());
+        b.extend_from_slice(&1u16.to_le_bytes());
+        b.extend_from_slice(&channels.to_le_bytes());
+        b.extend_from_slice(&rate.to_le_bytes());
+        b.extend_from_slice(&(rate * block_align as u32).to_le_bytes());
+        b.extend_from_slice(&block_align.to_le_bytes());
+        b.extend_from_slice(&bits.to_le_bytes());
+        b.extend_from_slice(b"data");
+        b.extend_from_slice(&data_len.to_le_bytes());
+        b.resize(b.len() + data_len as usize, 0);
+        b
+    }
+
+    fn aiff_bytes(channels: u16, frames: u32, bits: u16) -> Vec<u8> {
+        let mut b = Vec::new();
+        b.extend_from_slice(b"FORM");
+        b.extend_from_slice(&(4 + 8 + 18u32).to_be_bytes());
+        b.extend_from_slice(b"AIFF");
+        b.extend_from_slice(b"COMM");
+        b.extend_from_slice(&18u32.to_be_bytes());
+        b.extend_from_slice(&channels.to_be_bytes());
+        b.extend_from_slice(&frames.to_be_bytes());
+        b.extend_from_slice(&bits.to_be_bytes());
+        // 48000.0 as an 80-bit extended float
+        b.extend_from_slice(&[0x40, 0x0E, 0xBB, 0x80, 0, 0, 0, 0, 0, 0]);
+        b
+    }
+
+    #[test]
+    fn test_read_wav_and_aiff_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let wav = dir.path().join("a.wav");
+        fs::write(&wav, wav_bytes(2, 44100, 16, 44100)).unwrap();
+        let info = read_audio_info(&wav).unwrap();
+        assert_eq!(info, AudioInfo { sample_rate: 44100, channels: 2, bits_per_sample: 16, frames: 44100 });
+        assert!((info.duration() - 1.0).abs() < 1e-9);
+
+        let aiff = dir.path().join("b.aiff");
+        fs::write(&aiff, aiff_bytes(1, 96000, 24)).unwrap();
+        let info = read_audio_info(&aiff).unwrap();
+        assert_eq!(info, AudioInfo { sample_rate: 48000, channels: 1, bits_per_sample: 24, frames: 96000 });
+
+        let junk = dir.path().join("c.wav");
+        fs::write(&junk, b"not audio at all").unwrap();
+        assert!(read_audio_info(&junk).is_none());
+    }
+
+    #[test]
+    fn test_extension_filter_and_listing() {
+        assert_eq!(parse_extension_filter(" .WAV, *.aiff mp3"), vec!["wav", "aiff", "mp3"]);
+        assert_eq!(parse_extension_filter("").len(), AUDIO_EXTENSIONS.len());
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("Stems")).unwrap();
+        fs::write(dir.path().join("b.WAV"), wav_bytes(1, 8000, 8, 10)).unwrap();
+        fs::write(dir.path().join("a.aif"), aiff_bytes(2, 10, 16)).unwrap();
+        fs::write(dir.path().join("notes.txt"), "x").unwrap();
+        fs::write(dir.path().join(".hidden.wav"), "x").unwrap();
+
+        let listing = list_dir(dir.path()).unwrap();
+        let names = |exts: &[String]| -> Vec<String> {
+            filter_entries(&listing, exts).into_iter().map(|e| e.name).collect()
+        };
+        assert_eq!(names(&parse_extension_filter("")), vec!["Stems", "a.aif", "b.WAV"]);
+        assert_eq!(names(&parse_extension_filter("wav")), vec!["Stems", "b.WAV"]);
+        assert_eq!(names(&parse_extension_filter("*")), vec!["Stems", "a.aif", "b.WAV", "notes.txt"]);
+    }
+
+    #[test]
+    fn test_multi_select() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["1.wav", "2.wav", "3.wav", "4.wav"] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        let mut fm = FileManager::new();
+        fm.navigate(dir.path().to_path_buf());
+        let names = |fm: &FileManager| -> Vec<String> {
+            fm.selection().iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect()
+        };
+
+        fm.click(1, false, false);
+        fm.click(3, false, true);
+        assert_eq!(names(&fm), vec!["2.wav", "3.wav", "4.wav"]);
+        fm.click(2, true, false);
+        assert_eq!(names(&fm), vec!["2.wav", "4.wav"]);
+        fm.click(0, false, false);
+        assert_eq!(names(&fm), vec!["1.wav"]);
+
+        // Filtering keeps the cached listing and drops hidden rows from the selection
+        fm.filter = "aiff".to_string();
+        fm.apply_filter();
+        assert!(fm.entries.is_empty() && fm.selection().is_empty());
+        fm.filter.clear();
+        fm.apply_filter();
+        assert_eq!(fm.entries.len(), 4);
+    }
+
+    #[test]
+    fn test_headers_and_folder_arrive_in_background() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("take.wav"), wav_bytes(2, 48000, 24, 4800)).unwrap();
+        let mut fm = FileManager::new();
+        let found = dir.path().to_path_buf();
+        fm.navigate_with(move || found);
+        let start = std::time::Instant::now();
+        while fm.poll() {
+            assert!(start.elapsed() < Duration::from_secs(5), "background work did not finish");
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(fm.dir, dir.path());
+        let info = fm.info(&dir.path().join("take.wav")).expect("header read");
+        assert_eq!((info.sample_rate, info.frames), (48000, 4800));
+    }
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_duration(65.25), "1:05.3");
+        assert_eq!(format_duration(3725.0), "1:02:05");
+    }
+}
@@ -0,0 +1,234 @@
+//! # Fonts Module
+//!
+//! This module installs the configured terminal font (`[app.font]`) into egui and
+//! provides helpers for the zoom level and the per-cell metrics used by the
+//! terminal renderer.
+//!
+//! ## Font Loading
+//!
+//! The primary font and any fallback fonts are read from disk once at startup and
+//! inserted at the front of egui's `Monospace` family, in order. Fallbacks are also
+//! appended to the `Proportional` family so emoji and CJK render in the UI chrome.
+//! Fonts that cannot be read or parsed are reported on stderr and skipped, so a
+//! bad path or a corrupt file never prevents the application from starting.
+
+use eframe::egui;
+use std::fs;
+
+use crate::config::FontSettings;
+
+/// Smallest zoom factor reachable with Ctrl+-
+pub const MIN_ZOOM: f32 = 0.5;
+/// Largest zoom factor reachable with Ctrl++
+pub const MAX_ZOOM: f32 = 3.0;
+/// Multiplicative step applied per zoom in/out
+pub const ZOOM_STEP: f32 = 1.1;
+
+/// Grid metrics for rendering terminal cells at a given font size
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellMetrics {
+    pub font_id: egui::FontId,
+    /// Width of one terminal column in points
+    pub cell_w: f32,
+    /// Height of one terminal row in points (includes line height)
+    pub cell_h: f32,
+    /// Vertical offset that centers the glyph row within the cell
+    pub text_offset_y: f32,
+}
+
+impl CellMetrics {
+    /// Measures the monospace cell size for `font_size`, scaled by `line_height`.
+    pub fn measure(ctx: &egui::Context, font_size: f32, line_height: f32) -> Self {
+        let font_id = egui::FontId::monospace(font_size);
+        let (glyph_w, row_h) = ctx.fonts(|f| (f.glyph_width(&font_id, 'M'), f.row_height(&font_id)));
+        let cell_h = (row_h * line_height.max(0.5)).max(1.0);
+        Self {
+            font_id,
+            cell_w: glyph_w.max(1.0),
+            cell_h,
+            text_offset_y: ((cell_h - row_h) * 0.5).round(),
+        }
+    }
+
+    /// Number of whole (rows, cols) that fit in `size`, never less than 1x1.
+    pub fn grid_size(&self, size: egui::Vec2) -> (usize, usize) {
+        let rows = (size.y / self.cell_h).floor().max(1.0) as usize;
+        let cols = (size.x / self.cell_w).floor().max(1.0) as usize;
+        (rows, cols)
+    }
+
+    /// Cell under `pos` for a grid of `(rows, cols)` drawn at `origin`, clamped to the grid.
+    pub fn cell_at(&self, origin: egui::Pos2, pos: egui::Pos2, (rows, cols): (usize, usize)) -> (usize, usize) {
+        let col = ((pos.x - origin.x) / self.cell_w).floor().max(0.0) as usize;
+        let row = ((pos.y - origin.y) / self.cell_h).floor().max(0.0) as usize;
+        (row.min(rows.saturating_sub(1)), col.min(cols.saturating_sub(1)))
+    }
+}
+
+/// Applies one zoom step (`+1` in, `-1` out) to `zoom`, clamped to the supported range.
+pub fn step_zoom(zoom: f32, direction: i32) -> f32 {
+    let next = if direction > 0 {
+        zoom * ZOOM_STEP
+    } else if direction < 0 {
+        zoom / ZOOM_STEP
+    } else {
+        zoom
+    };
+    next.clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+/// Builds egui font definitions from the configured font files.
+///
+/// Returns `None` when no font files are configured, leaving egui's defaults untouched.
+pub fn build_font_definitions(settings: &FontSettings) -> Option<egui::FontDefinitions> {
+    let primary = settings.family.trim();
+    if primary.is_empty() && settings.fallbacks.is_empty() {
+        return None;
+    }
+
+    let mut fonts = egui::FontDefinitions::default();
+    let mut mono_front: Vec<String> = Vec::new();
+    let mut fallback_names: Vec<String> = Vec::new();
+
+    if !primary.is_empty() {
+        if let Some(data) = read_font(primary) {
+            let name = "ats-primary".to_string();
+            fonts.font_data.insert(name.clone(), data);
+            mono_front.push(name);
+        }
+    }
+    for (i, path) in settings.fallbacks.iter().enumerate() {
+        if let Some(data) = read_font(path.trim()) {
+            let name = format!("ats-fallback-{}", i);
+            fonts.font_data.insert(name.clone(), data);
+            fallback_names.push(name);
+        }
+    }
+
+    let mono = fonts.families.entry(egui::FontFamily::Monospace).or_default();
+    // Primary goes first; fallbacks follow the built-in fonts so they only fill gaps
+    for (i, name) in mono_front.into_iter().enumerate() {
+        mono.insert(i, name);
+    }
+    mono.extend(fallback_names.iter().cloned());
+    fonts
+        .families
+        .entry(egui::FontFamily::Proportional)
+        .or_default()
+        .extend(fallback_names);
+
+    Some(fonts)
+}
+
+/// Installs the configured fonts into the egui context (no-op when none are configured).
+pub fn install_fonts(ctx: &egui::Context, settings: &FontSettings) {
+    if let Some(fonts) = build_font_definitions(settings) {
+        ctx.set_fonts(fonts);
+    }
+}
+
+/// Parses the font the same way egui does, so a file egui would panic on is skipped instead.
+fn parse_font(bytes: &[u8]) -> Result<(), ab_glyph::InvalidFont> {
+    ab_glyph::FontRef::try_from_slice_and_index(bytes, 0).map(|_| ())
+}
+
+fn read_font(path: &str) -> Option<egui::FontData> {
+    match fs::read(path) {
+        Ok(bytes) => match parse_font(&bytes) {
+            Ok(()) => Some(egui::FontData::from_owned(bytes)),
+            Err(e) => {
+                eprintln!("[FONT] Skipping {:?}: {}", path, e);
+                None
+            }
+        },
+        Err(e) => {
+            eprintln!("[FONT] Failed to load font {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_zoom_clamps() {
+        assert!(step_zoom(1.0, 1) > 1.0);
+        assert!(step_zoom(1.0, -1) < 1.0);
+        assert_eq!(step_zoom(1.0, 0), 1.0);
+        assert_eq!(step_zoom(MAX_ZOOM, 1), MAX_ZOOM);
+        assert_eq!(step_zoom(MIN_ZOOM, -1), MIN_ZOOM);
+    }
+
+    #[test]
+    fn test_grid_size_never_zero() {
+        let metrics = CellMetrics {
+            font_id: egui::FontId::monospace(12.0),
+            cell_w: 7.0,
+            cell_h: 14.0,
+            text_offset_y: 0.0,
+        };
+        assert_eq!(metrics.grid_size(egui::vec2(700.0, 140.0)), (10, 100));
+        assert_eq!(metrics.grid_size(egui::vec2(0.0, 0.0)), (1, 1));
+        let origin = egui::pos2(10.0, 10.0);
+        assert_eq!(metrics.cell_at(origin, egui::pos2(24.0, 38.0), (10, 100)), (2, 2));
+        assert_eq!(metrics.cell_at(origin, egui::pos2(0.0, 999.0), (10, 100)), (9, 0));
+    }
+
+    #[test]
+    fn test_build_font_definitions_defaults() {
+        assert!(build_font_definitions(&FontSettings::default()).is_none());
+    }
+
+    #[test]
+    fn test_build_font_definitions_skips_missing_files() {
+        let settings = FontSettings {
+            family: "/nonexistent/font.ttf".to_string(),
+            fallbacks: vec!["/nonexistent/emoji.ttc".to_string()],
+            ..FontSettings::default()
+        };
+        let fonts = build_font_definitions(&settings).expect("definitions built");
+        assert!(!fonts.font_data.contains_key("ats-primary"));
+        assert!(!fonts.font_data.contains_key("ats-fallback-0"));
+    }
+
+    /// A real TTF: the monospace font bundled with egui
+    fn hack_font() -> Vec<u8> {
+        egui::FontDefinitions::default().font_data["Hack"].font.to_vec()
+    }
+
+    #[test]
+    fn test_parse_font() {
+        let font = hack_font();
+        assert!(parse_font(&font).is_ok());
+        // Right signature, but truncated or corrupt
+        assert!(parse_font(&font[..64]).is_err());
+        assert!(parse_font(b"OTTO-header-only").is_err());
+        assert!(parse_font(b"<html>").is_err());
+        assert!(parse_font(b"").is_err());
+    }
+
+    #[test]
+    fn test_build_font_definitions_orders_families() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let primary = dir.path().join("primary.ttf");
+        let fallback = dir.path().join("fallback.ttf");
+        let corrupt = dir.path().join("corrupt.ttf");
+        fs::write(&primary, hack_font()).unwrap();
+        fs::write(&fallback, hack_font()).unwrap();
+        fs::write(&corrupt, &hack_font()[..64]).unwrap();
+        let settings = FontSettings {
+            family: primary.to_string_lossy().to_string(),
+            fallbacks: vec![fallback.to_string_lossy().to_string(), corrupt.to_string_lossy().to_string()],
+            ..FontSettings::default()
+        };
+        let fonts = build_font_definitions(&settings).expect("definitions built");
+        assert!(!fonts.font_data.contains_key("ats-fallback-1"));
+        let mono = &fonts.families[&egui::FontFamily::Monospace];
+        assert_eq!(mono.first().map(String::as_str), Some("ats-primary"));
+        assert_eq!(mono.last().map(String::as_str), Some("ats-fallback-0"));
+        let prop = &fonts.families[&egui::FontFamily::Proportional];
+        assert_eq!(prop.last().map(String::as_str), Some("ats-fallback-0"));
+    }
+}
@@ -0,0 +1,667 @@
+//! # Help Module
+//!
+//! This module implements the help viewer opened from the "💡 Help" button
+//! (`help` action, `Ctrl+Shift+H` by default).
+//!
+//! ## Pages
+//!
+//! - **Overview**: generated from the running app — the config file that was
+//!   loaded, live key bindings, Actions panel buttons and the `ATS_*`
+//!   environment variables with their current values.
+//! - **Docs**: README, configuration guide, changelog and technical notes,
+//!   compiled into the binary with `include_str!` and rendered from a small
+//!   Markdown subset (headings, paragraphs, lists, tables, code blocks,
+//!   `inline code` and **bold**).
+//!
+//! Typing in the search box shows every matching binding and doc section, with
+//! the matches highlighted.
+
+use eframe::egui;
+use egui::text::{LayoutJob, TextFormat};
+
+use crate::config::{self, AppConfig, ConfigSource};
+use crate::input::Keymap;
+use crate::theme::Palette;
+
+/// Bundled documents as (tab label, Markdown source)
+const DOCS: [(&str, &str); 4] = [
+    ("README", include_str!("../../README.md")),
+    ("Configuration", include_str!("../../CONFIGURATION.md")),
+    ("Changelog", include_str!("../../CHANGELOG.md")),
+    ("Technical", include_str!("../../TECHNICAL.md")),
+];
+
+/// Environment variables read at startup, with what they do
+const ENV_VARS: [(&str, &str); 4] = [
+    ("ATS_CONFIG_DIR", "Directory holding config.toml (default: next to the executable)"),
+    ("ATS_DEBUG_OVERLAY", "1 draws pane, splitter and seam guides and logs focus and resizes (toggle: debug_overlay)"),
+    ("ATS_WINDOW_TRACE", "1 logs window sizes and suggests [app] values (toggle: window_trace)"),
+    ("ATS_BTN_ROW_PREPASS", "0 switches the Actions grid to the legacy per-cell background"),
+];
+
+/// A rendered piece of a Markdown document
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Heading(usize, String),
+    Text(String),
+    /// List item as (nesting level, marker, text)
+    Item(usize, String, String),
+    Code(String),
+    Table(Vec<Vec<String>>),
+    Rule,
+}
+
+/// A heading and the blocks up to the next heading
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub title: String,
+    pub level: usize,
+    pub blocks: Vec<Block>,
+    /// Lowercased text used for search
+    haystack: String,
+}
+
+impl Section {
+    fn new(title: String, level: usize) -> Self {
+        Self { title, level, blocks: Vec::new(), haystack: String::new() }
+    }
+
+    /// True when every term of `query` occurs in the section
+    pub fn matches(&self, query: &str) -> bool {
+        query.split_whitespace().all(|term| self.haystack.contains(&term.to_lowercase()))
+    }
+}
+
+/// Splits a Markdown document into sections at each heading
+pub fn parse(markdown: &str) -> Vec<Section> {
+    let mut sections = vec![Section::new(String::new(), 0)];
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<Vec<&str>> = None;
+    let mut table: Vec<Vec<String>> = Vec::new();
+
+    fn flush(section: &mut Section, paragraph: &mut Vec<&str>, table: &mut Vec<Vec<String>>) {
+        if !paragraph.is_empty() {
+            section.blocks.push(Block::Text(paragraph.join(" ")));
+            paragraph.clear();
+        }
+        if !table.is_empty() {
+            section.blocks.push(Block::Table(std::mem::take(table)));
+        }
+    }
+
+    for line in markdown.lines() {
+        let section = sections.last_mut().unwrap();
+        let trimmed = line.trim();
+        if let Some(lines) = code.as_mut() {
+            if trimmed.starts_with("```") {
+                section.blocks.push(Block::Code(lines.join("\n")));
+                code = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            flush(section, &mut paragraph, &mut table);
+            code = Some(Vec::new());
+            continue;
+        }
+        if trimmed.starts_with('|') {
+            if !paragraph.is_empty() {
+                flush(section, &mut paragraph, &mut Vec::new());
+            }
+            let cells: Vec<String> = trimmed.trim_matches('|').split('|').map(|c| c.trim().to_string()).collect();
+            if !cells.iter().all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':'))) {
+                table.push(cells);
+            }
+            continue;
+        }
+
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        let heading = (1..=6).contains(&level) && trimmed[level..].starts_with(' ');
+        let item = list_item(trimmed);
+        let rule = matches!(trimmed, "---" | "***" | "___");
+        if trimmed.is_empty() || heading || item.is_some() || rule {
+            flush(section, &mut paragraph, &mut table);
+        } else if !table.is_empty() {
+            flush(section, &mut Vec::new(), &mut table);
+        }
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if heading {
+            let title = strip_inline(trimmed[level..].trim());
+            let mut section = Section::new(title.clone(), level);
+            section.blocks.push(Block::Heading(level, title));
+            sections.push(section);
+        } else if rule {
+            section.blocks.push(Block::Rule);
+        } else if let Some((marker, text)) = item {
+            let indent = line.len() - line.trim_start().len();
+            section.blocks.push(Block::Item(indent / 2, marker, text.to_string()));
+        } else if line.starts_with("  ") && paragraph.is_empty() && matches!(section.blocks.last(), Some(Block::Item(..))) {
+            // Continuation of the previous list item
+            if let Some(Block::Item(_, _, text)) = section.blocks.last_mut() {
+                text.push(' ');
+                text.push_str(trimmed);
+            }
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+    let section = sections.last_mut().unwrap();
+    if let Some(lines) = code {
+        section.blocks.push(Block::Code(lines.join("\n")));
+    }
+    flush(section, &mut paragraph, &mut table);
+
+    sections.retain(|s| !s.blocks.is_empty());
+    for section in &mut sections {
+        section.haystack = section
+            .blocks
+            .iter()
+            .map(|block| match block {
+                Block::Heading(_, text) | Block::Text(text) | Block::Code(text) => text.clone(),
+                Block::Item(_, _, text) => text.clone(),
+                Block::Table(rows) => rows.iter().map(|r| r.join(" ")).collect::<Vec<_>>().join("\n"),
+                Block::Rule => String::new(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+            .to_lowercase();
+    }
+    sections
+}
+
+/// `- text`, `* text` or `1. text` as (marker, text)
+fn list_item(line: &str) -> Option<(String, &str)> {
+    if let Some(text) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some(("•".to_string(), text));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 && line[digits..].starts_with(". ") {
+        return Some((line[..digits + 1].to_string(), &line[digits + 2..]));
+    }
+    None
+}
+
+/// Inline span styles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Span {
+    Plain,
+    Bold,
+    Code,
+}
+
+/// Splits inline Markdown into styled spans; links keep only their text
+pub fn spans(text: &str) -> Vec<(Span, String)> {
+    let mut out: Vec<(Span, String)> = Vec::new();
+    let mut push = |style: Span, piece: &str| {
+        if piece.is_empty() {
+            return;
+        }
+        match out.last_mut() {
+            Some((last, text)) if *last == style => text.push_str(piece),
+            _ => out.push((style, piece.to_string())),
+        }
+    };
+    let mut bold = false;
+    let mut rest = text;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('`') {
+            if let Some(end) = after.find('`') {
+                push(Span::Code, &after[..end]);
+                rest = &after[end + 1..];
+                continue;
+            }
+        }
+        if let Some(after) = rest.strip_prefix("**") {
+            bold = !bold;
+            rest = after;
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix('[') {
+            if let Some((label, tail)) = after.split_once("](") {
+                if let Some(end) = tail.find(')') {
+                    if !label.contains(']') {
+                        push(if bold { Span::Bold } else { Span::Plain }, label);
+                        rest = &tail[end + 1..];
+                        continue;
+                    }
+                }
+            }
+        }
+        let first = rest.chars().next().map_or(1, char::len_utf8);
+        let next = rest[first..].find(['`', '*', '[']).map_or(rest.len(), |i| i + first);
+        push(if bold { Span::Bold } else { Span::Plain }, &rest[..next]);
+        rest = &rest[next..];
+    }
+    out
+}
+
+/// Heading text without inline markup
+fn strip_inline(text: &str) -> String {
+    spans(text).into_iter().map(|(_, t)| t).collect::<String>().trim().to_string()
+}
+
+/// What the viewer is showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Page {
+    Overview,
+    Doc(usize),
+}
+
+/// Live app state shown on the Overview page
+pub struct HelpContext<'a> {
+    pub config: &'a AppConfig,
+    pub source: &'a ConfigSource,
+    pub keymap: &'a Keymap,
+}
+
+/// State of the help viewer window
+pub struct HelpViewer {
+    pub open: bool,
+    docs: Vec<Vec<Section>>,
+    page: Page,
+    query: String,
+    /// Section to scroll to on the next frame
+    scroll_to: Option<usize>,
+    focus_query: bool,
+}
+
+impl HelpViewer {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            docs: DOCS.iter().map(|(_, markdown)| parse(markdown)).collect(),
+            page: Page::Overview,
+            query: String::new(),
+            scroll_to: None,
+            focus_query: false,
+        }
+    }
+
+    /// Opens the viewer with the search box focused
+    pub fn show_window(&mut self) {
+        self.open = true;
+        self.focus_query = true;
+    }
+
+    /// Draws the viewer
+    pub fn show(&mut self, ui: &mut egui::Ui, palette: &Palette, live: &HelpContext) {
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text("Search help, docs and key bindings")
+                    .desired_width(260.0),
+            );
+            if std::mem::take(&mut self.focus_query) {
+                response.request_focus();
+            }
+            ui.separator();
+            ui.selectable_value(&mut self.page, Page::Overview, "Overview");
+            for (i, (label, _)) in DOCS.iter().enumerate() {
+                if ui.selectable_value(&mut self.page, Page::Doc(i), *label).clicked() {
+                    self.query.clear();
+                }
+            }
+        });
+        ui.separator();
+
+        let query = self.query.trim().to_string();
+        if !query.is_empty() {
+            self.search_results(ui, palette, live, &query);
+            return;
+        }
+        match self.page {
+            Page::Overview => {
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| overview(ui, palette, live, ""));
+            }
+            Page::Doc(doc) => self.document(ui, palette, doc),
+        }
+    }
+
+    fn document(&mut self, ui: &mut egui::Ui, palette: &Palette, doc: usize) {
+        let sections = &self.docs[doc];
+        let mut jump = None;
+        egui::SidePanel::left("help_toc")
+            .resizable(true)
+            .default_width(190.0)
+            .show_inside(ui, |ui| {
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                    for (i, section) in sections.iter().enumerate().filter(|(_, s)| (1..=3).contains(&s.level)) {
+                        let indent = (section.level - 1) as f32 * 10.0;
+                        ui.horizontal(|ui| {
+                            ui.add_space(indent);
+                            if ui.selectable_label(false, &section.title).clicked() {
+                                jump = Some(i);
+                            }
+                        });
+                    }
+                });
+            });
+        if jump.is_some() {
+            self.scroll_to = jump;
+        }
+        let scroll_to = self.scroll_to.take();
+        egui::ScrollArea::vertical().id_source(("help_doc", doc)).auto_shrink([false, false]).show(ui, |ui| {
+            for (i, section) in sections.iter().enumerate() {
+                let top = ui.cursor();
+                render_section(ui, palette, section, "");
+                if scroll_to == Some(i) {
+                    ui.scroll_to_rect(top, Some(egui::Align::TOP));
+                }
+            }
+        });
+    }
+
+    fn search_results(&mut self, ui: &mut egui::Ui, palette: &Palette, live: &HelpContext, query: &str) {
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            let mut found = overview(ui, palette, live, query);
+            for (doc, sections) in self.docs.iter().enumerate() {
+                for (i, section) in sections.iter().enumerate().filter(|(_, s)| s.matches(query)) {
+                    found = true;
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(format!("{} › {}", DOCS[doc].0, section.title)).color(palette.subtext0));
+                        if ui.small_button("Open").clicked() {
+                            self.page = Page::Doc(doc);
+                            self.scroll_to = Some(i);
+                            self.query.clear();
+                        }
+                    });
+                    render_section(ui, palette, section, query);
+                    ui.separator();
+                }
+            }
+            if !found {
+                ui.weak(format!("Nothing matches {:?}.", query));
+            }
+        });
+    }
+}
+
+/// Draws the live Overview; with a query, only the matching rows
+///
+/// # Returns
+///
+/// Whether anything was drawn
+fn overview(ui: &mut egui::Ui, palette: &Palette, live: &HelpContext, query: &str) -> bool {
+    let matches = |texts: &[&str]| {
+        let text = texts.join(" ").to_lowercase();
+        query.split_whitespace().all(|term| text.contains(&term.to_lowercase()))
+    };
+    let mut drawn = false;
+
+    let path = config::config_file_path();
+    let (status, color) = match live.source {
+        ConfigSource::File => ("loaded".to_string(), palette.green),
+        ConfigSource::Template => ("created from the first-run template".to_string(), palette.yellow),
+        ConfigSource::Defaults(reason) => (format!("not used, built-in defaults active ({})", reason), palette.red),
+    };
+    let app = &live.config.app;
+    let config_rows = [
+        ("Config file", path.display().to_string()),
+        ("Config directory", config::config_dir().display().to_string()),
+        ("Theme", app.theme.clone()),
+        ("Themes folder", app.themes_path().display().to_string()),
+        ("Font", if app.font.family.is_empty() { "built-in monospace".to_string() } else { app.font.family.clone() }),
+        ("Bookmarks file", app.bookmarks_path().display().to_string()),
+        ("Analytics log", live.config.analytics.log_path().display().to_string()),
+        ("Session logs", live.config.logging.log_dir().display().to_string()),
+        (
+            "Control socket",
+            if live.config.control.enabled { live.config.control.socket_path().display().to_string() } else { "off".to_string() },
+        ),
+        (
+            "Notifications",
+            match (live.config.notifications.toasts, live.config.notifications.desktop) {
+                (true, true) => "toasts and desktop",
+                (true, false) => "toasts",
+                (false, true) => "desktop",
+                (false, false) => "off",
+            }
+            .to_string(),
+        ),
+        ("Profile", config::active_profile().unwrap_or_else(|| "none".to_string())),
+        ("Script folders", live.config.scripts.dirs.join(", ")),
+    ];
+    let config_rows: Vec<_> = config_rows.iter().filter(|(k, v)| matches(&[k, v])).collect();
+    if !config_rows.is_empty() || matches(&["config", &status]) {
+        drawn = true;
+        ui.heading("Active configuration");
+        ui.colored_label(color, format!("config.toml {}", status));
+        egui::Grid::new("help_config").num_columns(2).spacing([16.0, 4.0]).show(ui, |ui| {
+            for (key, value) in &config_rows {
+                ui.label(*key);
+                ui.monospace(value);
+                ui.end_row();
+            }
+        });
+        if query.is_empty() {
+            egui::CollapsingHeader::new("Effective settings (TOML)").show(ui, |ui| {
+                let text = toml::to_string_pretty(live.config).unwrap_or_else(|e| e.to_string());
+                ui.add(egui::Label::new(egui::RichText::new(text).monospace()).selectable(true));
+            });
+        }
+        ui.add_space(8.0);
+    }
+
+    let bindings: Vec<(String, String)> = live
+        .keymap
+        .bindings()
+        .iter()
+        .map(|(chord, action)| (chord.to_string(), action.to_string()))
+        .filter(|(chord, action)| matches(&[chord, action, "key binding shortcut"]))
+        .collect();
+    if !bindings.is_empty() {
+        drawn = true;
+        ui.heading("Key bindings");
+        egui::Grid::new("help_keys").num_columns(2).striped(true).spacing([16.0, 4.0]).show(ui, |ui| {
+            for (chord, action) in &bindings {
+                ui.monospace(chord);
+                ui.label(action);
+                ui.end_row();
+            }
+        });
+        ui.weak("Unbound keys go to the focused pane. Hold Shift to keep mouse input local when an app uses the mouse.");
+        ui.add_space(8.0);
+    }
+
+    let buttons: Vec<_> = live
+        .config
+        .buttons
+        .iter()
+        .map(|b| {
+            let name = format!("{} {}", b.icon, b.label).trim().to_string();
+            let does = if !b.action.trim().is_empty() {
+                b.action.clone()
+            } else if !b.command.is_empty() {
+                format!("runs {}", b.command)
+            } else {
+                b.tooltip.clone()
+            };
+            (name, does)
+        })
+        .filter(|(name, does)| matches(&[name, does, "button"]))
+        .collect();
+    if !buttons.is_empty() {
+        drawn = true;
+        ui.heading("Actions panel");
+        egui::Grid::new("help_buttons").num_columns(2).spacing([16.0, 4.0]).show(ui, |ui| {
+            for (name, does) in &buttons {
+                ui.label(name);
+                ui.label(does);
+                ui.end_row();
+            }
+        });
+        ui.add_space(8.0);
+    }
+
+    let vars: Vec<_> = ENV_VARS.iter().filter(|(name, about)| matches(&[name, about, "environment"])).collect();
+    if !vars.is_empty() {
+        drawn = true;
+        ui.heading("Environment variables");
+        egui::Grid::new("help_env").num_columns(3).spacing([16.0, 4.0]).show(ui, |ui| {
+            for (name, about) in vars {
+                ui.monospace(*name);
+                match std::env::var(name) {
+                    Ok(value) => ui.colored_label(palette.green, format!("= {}", value)),
+                    Err(_) => ui.weak("unset"),
+                };
+                ui.label(*about);
+                ui.end_row();
+            }
+        });
+        ui.add_space(8.0);
+    }
+    drawn
+}
+
+/// Draws one section, highlighting the terms of `query`
+fn render_section(ui: &mut egui::Ui, palette: &Palette, section: &Section, query: &str) {
+    for block in &section.blocks {
+        match block {
+            Block::Heading(level, text) => {
+                ui.add_space(if *level <= 2 { 10.0 } else { 6.0 });
+                let size = match level {
+                    1 => 22.0,
+                    2 => 18.0,
+                    3 => 15.5,
+                    _ => 14.0,
+                };
+                ui.label(rich(text, palette, query, egui::FontId::proportional(size), palette.blue));
+            }
+            Block::Text(text) => {
+                ui.label(rich(text, palette, query, egui::FontId::proportional(13.5), palette.text));
+            }
+            Block::Item(level, marker, text) => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.add_space(8.0 + *level as f32 * 16.0);
+                    ui.label(egui::RichText::new(marker).color(palette.subtext0));
+                    ui.label(rich(text, palette, query, egui::FontId::proportional(13.5), palette.text));
+                });
+            }
+            Block::Code(code) => {
+                egui::Frame::none().fill(palette.mantle).inner_margin(6.0).rounding(4.0).show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    ui.label(highlighted(code, palette, query, egui::FontId::monospace(12.5), palette.subtext1));
+                });
+            }
+            Block::Table(rows) => {
+                egui::Grid::new(("help_table", rows.as_ptr())).striped(true).spacing([14.0, 4.0]).show(ui, |ui| {
+                    for (r, row) in rows.iter().enumerate() {
+                        for cell in row {
+                            let color = if r == 0 { palette.lavender } else { palette.text };
+                            ui.label(rich(cell, palette, query, egui::FontId::proportional(13.0), color));
+                        }
+                        ui.end_row();
+                    }
+                });
+            }
+            Block::Rule => {
+                ui.separator();
+            }
+        }
+    }
+}
+
+/// Lays out inline Markdown with code spans, bold and query highlights
+fn rich(text: &str, palette: &Palette, query: &str, font: egui::FontId, color: egui::Color32) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    for (style, piece) in spans(text) {
+        let (font, color, background) = match style {
+            Span::Code => (egui::FontId::monospace(font.size * 0.92), palette.peach, palette.surface0),
+            Span::Bold => (font.clone(), palette.sky, egui::Color32::TRANSPARENT),
+            Span::Plain => (font.clone(), color, egui::Color32::TRANSPARENT),
+        };
+        append_highlighted(&mut job, &piece, query, TextFormat { font_id: font, color, background, ..Default::default() }, palette);
+    }
+    job
+}
+
+/// Plain text with query highlights
+fn highlighted(text: &str, palette: &Palette, query: &str, font: egui::FontId, color: egui::Color32) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    append_highlighted(&mut job, text, query, TextFormat { font_id: font, color, ..Default::default() }, palette);
+    job
+}
+
+/// Appends `text` to `job`, giving occurrences of the query terms a highlight background
+fn append_highlighted(job: &mut LayoutJob, text: &str, query: &str, format: TextFormat, palette: &Palette) {
+    let lower = text.to_lowercase();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    // Byte offsets only line up when lowercasing kept the length (true for ASCII and most text)
+    if lower.len() == text.len() {
+        for term in query.split_whitespace().map(str::to_lowercase) {
+            let mut from = 0;
+            while let Some(i) = lower[from..].find(&term) {
+                ranges.push((from + i, from + i + term.len()));
+                from += i + term.len();
+            }
+        }
+    }
+    ranges.sort();
+    let mut at = 0;
+    for (start, end) in ranges {
+        if start < at || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+            continue;
+        }
+        job.append(&text[at..start], 0.0, format.clone());
+        job.append(&text[start..end], 0.0, TextFormat { background: palette.yellow, color: palette.base, ..format.clone() });
+        at = end;
+    }
+    job.append(&text[at..], 0.0, format);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sections_and_blocks() {
+        let markdown = "# Title\nIntro line one\nline two\n\n## Keys\n- **Bold** item\n  continued\n  - nested `code`\n1. first\n\n| Chord | Action |\n|---|---|\n| `F1` | help |\n\n```toml\n# not a heading\nkey = 1\n```\n---\n";
+        let sections = parse(markdown);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].blocks, vec![Block::Heading(1, "Title".into()), Block::Text("Intro line one line two".into())]);
+        assert_eq!(
+            sections[1].blocks,
+            vec![
+                Block::Heading(2, "Keys".into()),
+                Block::Item(0, "•".into(), "**Bold** item continued".into()),
+                Block::Item(1, "•".into(), "nested `code`".into()),
+                Block::Item(0, "1.".into(), "first".into()),
+                Block::Table(vec![vec!["Chord".into(), "Action".into()], vec!["`F1`".into(), "help".into()]]),
+                Block::Code("# not a heading\nkey = 1".into()),
+                Block::Rule,
+            ]
+        );
+        assert!(sections[1].matches("NOT a"));
+        assert!(!sections[0].matches("nested"));
+    }
+
+    #[test]
+    fn test_inline_spans() {
+        assert_eq!(
+            spans("Use `ATS_CONFIG_DIR` for **custom [dirs](#x)** now"),
+            vec![
+                (Span::Plain, "Use ".to_string()),
+                (Span::Code, "ATS_CONFIG_DIR".to_string()),
+                (Span::Plain, " for ".to_string()),
+                (Span::Bold, "custom dirs".to_string()),
+                (Span::Plain, " now".to_string()),
+            ]
+        );
+        assert_eq!(strip_inline("🚀 **Key Features**"), "🚀 Key Features");
+        assert_eq!(spans("a * b [c"), vec![(Span::Plain, "a * b [c".to_string())]);
+    }
+
+    #[test]
+    fn test_bundled_docs_document_env_vars() {
+        let viewer = HelpViewer::new();
+        let all: Vec<&Section> = viewer.docs.iter().flatten().collect();
+        for (name, _) in ENV_VARS {
+            assert!(all.iter().any(|s| s.matches(name)), "{} is not documented", name);
+        }
+    }
+}
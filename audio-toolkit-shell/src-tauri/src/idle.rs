@@ -0,0 +1,240 @@
+//! # Idle Module
+//!
+//! This module tells a job doing quiet work apart from one that is stuck or
+//! waiting on a prompt nobody sees. An [`IdleWatch`] per tab tracks the time
+//! since the last output and the last input, and the app checks it about once a
+//! second while a job runs in the foreground (a pane sitting at its shell prompt
+//! is never flagged). Tabs with neither `idle_timeout` nor `stall_pattern` are
+//! not checked at all.
+//!
+//! ## States
+//!
+//! - **waiting for input?**: nothing happened for a couple of seconds and the
+//!   cursor line looks like a prompt (`Overwrite? [y/N]`, `Password:`, ...), or
+//!   contains `stall_pattern` when one is set
+//! - **idle**: nothing happened for `idle_timeout` seconds
+//!
+//! ## Actions
+//!
+//! Once a job has been idle for `idle_timeout`, `on_idle` decides what happens,
+//! once per quiet period: `"flag"` only marks the pane header, `"respond"` types
+//! `idle_response` and Enter if the job is waiting for input, and `"restart"`
+//! restarts the tab.
+
+use std::time::{Duration, Instant};
+
+use crate::config::{IdleAction, IdleSettings};
+
+/// Quiet time before a prompt-like cursor line counts as waiting for input
+const PROMPT_QUIET: Duration = Duration::from_secs(2);
+
+/// Endings and phrases of lines asking for input (matched in lower case)
+const PROMPT_ENDINGS: &[char] = &['?', ':', '>'];
+const PROMPT_PHRASES: &[&str] = &[
+    "[y/n]",
+    "(y/n)",
+    "[yes/no]",
+    "(yes/no)",
+    "password",
+    "passphrase",
+    "press enter",
+    "press return",
+    "press any key",
+];
+
+/// What the pane header shows about a running job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleState {
+    Active,
+    /// No output or input for this long, at least `idle_timeout`
+    Idle(Duration),
+    /// The cursor line looks like a prompt; quiet for this long
+    Waiting(Duration),
+}
+
+/// What the tab should do about an idle job
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdleEvent {
+    /// Type this followed by Enter
+    Respond(String),
+    Restart,
+}
+
+/// Whether `line` looks like a program asking a question
+pub fn looks_like_prompt(line: &str) -> bool {
+    let line = line.trim().to_lowercase();
+    !line.is_empty() && (line.ends_with(PROMPT_ENDINGS) || PROMPT_PHRASES.iter().any(|p| line.contains(p)))
+}
+
+/// Output and input times of one tab, with its idle settings
+#[derive(Debug)]
+pub struct IdleWatch {
+    settings: IdleSettings,
+    last_output: Instant,
+    last_input: Instant,
+    /// `on_idle` already ran in this quiet period
+    acted: bool,
+    state: IdleState,
+}
+
+impl IdleWatch {
+    pub fn new(settings: IdleSettings, now: Instant) -> Self {
+        Self { settings, last_output: now, last_input: now, acted: false, state: IdleState::Active }
+    }
+
+    pub fn configure(&mut self, settings: IdleSettings) {
+        if !settings.enabled() {
+            self.state = IdleState::Active;
+        }
+        self.settings = settings;
+    }
+
+    /// Whether the tab should be checked at all (see [`IdleSettings::enabled`])
+    pub fn enabled(&self) -> bool {
+        self.settings.enabled()
+    }
+
+    /// Starts over for a new run of the process
+    pub fn restart(&mut self, now: Instant) {
+        self.last_output = now;
+        self.acted = false;
+        self.state = IdleState::Active;
+    }
+
+    pub fn output(&mut self, now: Instant) {
+        self.last_output = now;
+        self.acted = false;
+        self.state = IdleState::Active;
+    }
+
+    pub fn input(&mut self, now: Instant) {
+        self.last_input = now;
+        self.acted = false;
+        self.state = IdleState::Active;
+    }
+
+    pub fn state(&self) -> IdleState {
+        self.state
+    }
+
+    /// Time since the last output and since the last input
+    pub fn since(&self, now: Instant) -> (Duration, Duration) {
+        (now.saturating_duration_since(self.last_output), now.saturating_duration_since(self.last_input))
+    }
+
+    /// Updates the state from the cursor line; `job_running` is false while the shell waits for commands
+    ///
+    /// # Returns
+    ///
+    /// What to do about the job, once per quiet period
+    pub fn check(&mut self, cursor_line: &str, job_running: bool, now: Instant) -> Option<IdleEvent> {
+        if !job_running {
+            self.state = IdleState::Active;
+            return None;
+        }
+        let (since_output, since_input) = self.since(now);
+        let quiet = since_output.min(since_input);
+        let pattern = self.settings.stall_pattern.trim();
+        let prompt = if pattern.is_empty() { looks_like_prompt(cursor_line) } else { cursor_line.contains(pattern) };
+        let waiting = prompt && quiet >= PROMPT_QUIET;
+        let timeout = self.settings.idle_timeout;
+        let idle = timeout > 0 && quiet.as_secs() >= timeout;
+        self.state = match (waiting, idle) {
+            (true, _) => IdleState::Waiting(quiet),
+            (false, true) => IdleState::Idle(quiet),
+            (false, false) => IdleState::Active,
+        };
+        if !idle || self.acted {
+            return None;
+        }
+        let event = match self.settings.on_idle {
+            IdleAction::Flag => None,
+            IdleAction::Respond if waiting => Some(IdleEvent::Respond(self.settings.idle_response.clone())),
+            IdleAction::Respond => None,
+            IdleAction::Restart => Some(IdleEvent::Restart),
+        };
+        self.acted = event.is_some();
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(timeout: u64, on_idle: IdleAction) -> IdleSettings {
+        IdleSettings { idle_timeout: timeout, on_idle, idle_response: "N".to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_looks_like_prompt() {
+        assert!(looks_like_prompt("File 'take1.wav' already exists. Overwrite? [y/N] "));
+        assert!(looks_like_prompt("Password:"));
+        assert!(looks_like_prompt("Press ENTER to continue"));
+        assert!(looks_like_prompt("Select a preset >"));
+        assert!(!looks_like_prompt("size=  1024kB time=00:00:05.12 bitrate= 1411.2kbits/s"));
+        assert!(!looks_like_prompt("   "));
+    }
+
+    #[test]
+    fn test_waiting_and_respond() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut watch = IdleWatch::new(settings(30, IdleAction::Respond), start);
+        let prompt = "Overwrite? [y/N]";
+
+        // Just printed: not waiting yet
+        assert_eq!(watch.check(prompt, true, at(1)), None);
+        assert_eq!(watch.state(), IdleState::Active);
+        assert_eq!(watch.check(prompt, true, at(5)), None);
+        assert_eq!(watch.state(), IdleState::Waiting(Duration::from_secs(5)));
+        // A shell at its prompt is never flagged
+        assert_eq!(watch.check("$ ", false, at(40)), None);
+        assert_eq!(watch.state(), IdleState::Active);
+
+        assert_eq!(watch.check(prompt, true, at(30)), Some(IdleEvent::Respond("N".to_string())));
+        assert_eq!(watch.check(prompt, true, at(31)), None);
+        // Input starts a new quiet period
+        watch.input(at(32));
+        assert_eq!(watch.check(prompt, true, at(50)), None);
+        assert_eq!(watch.check(prompt, true, at(62)), Some(IdleEvent::Respond("N".to_string())));
+
+        // Not a prompt: idle, but there is nothing to respond to
+        let mut watch = IdleWatch::new(settings(30, IdleAction::Respond), start);
+        assert_eq!(watch.check("frame= 120 fps=0.0", true, at(60)), None);
+        assert_eq!(watch.state(), IdleState::Idle(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_stall_pattern_and_restart() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut restart = IdleSettings { stall_pattern: "Waiting for device".to_string(), ..settings(10, IdleAction::Restart) };
+        let mut watch = IdleWatch::new(restart.clone(), start);
+
+        // The pattern replaces the built-in check
+        assert_eq!(watch.check("Continue? [y/N]", true, at(3)), None);
+        assert_eq!(watch.state(), IdleState::Active);
+        assert_eq!(watch.check("Waiting for device hw:1", true, at(4)), None);
+        assert_eq!(watch.state(), IdleState::Waiting(Duration::from_secs(4)));
+
+        assert_eq!(watch.check("", true, at(12)), Some(IdleEvent::Restart));
+        assert_eq!(watch.check("", true, at(20)), None);
+        watch.restart(at(21));
+        watch.output(at(22));
+        assert_eq!(watch.check("", true, at(33)), Some(IdleEvent::Restart));
+
+        // Flag only marks the state
+        restart.on_idle = IdleAction::Flag;
+        watch.configure(restart);
+        watch.output(at(40));
+        assert_eq!(watch.check("", true, at(60)), None);
+        assert_eq!(watch.state(), IdleState::Idle(Duration::from_secs(20)));
+
+        // Neither a timeout nor a pattern: off, and the badge goes away
+        assert!(watch.enabled());
+        watch.configure(settings(0, IdleAction::Flag));
+        assert!(!watch.enabled());
+        assert_eq!(watch.state(), IdleState::Active);
+    }
+}
@@ -0,0 +1,526 @@
+//! # Input Module
+//!
+//! This module defines the keyboard pipeline shared by all terminal panes:
+//! key chords, the actions they can be bound to, the keymap built from the
+//! `[keybindings]` config section, and the encoding of unbound keys into the
+//! byte sequences sent to the PTY.
+//!
+//! ## Chord Syntax
+//!
+//! Chords are written as modifiers and a key joined by `+`, case-insensitive:
+//! `"Ctrl+Shift+C"`, `"Alt+1"`, `"Shift+Tab"`, `"F5"`. Modifiers are `Ctrl`,
+//! `Shift`, `Alt` (or `Option`) and `Cmd` (or `Super`/`Meta`). Key names follow
+//! egui (`Enter`, `Escape`, `PageUp`, `Plus`, `Minus`, `A`-`Z`, `0`-`9`, ...).
+//!
+//! ## Actions
+//!
+//! `focus_pane_1`..`focus_pane_4`, `focus_next`, `focus_prev`, `restart_tab`,
+//! `restart_all`, `clear`, `copy`, `paste`, `search`, `zoom_in`, `zoom_out`,
+//! `zoom_reset`, `file_manager`, `settings`, `bookmarks`, `scripts`, `analytics`,
+//! `help`, `tools`, `command_palette`, `reload_config`, `debug_overlay`,
+//! `window_trace`, `record`, `recordings`, `restart_pane_1`..`restart_pane_4`, `interrupt`, `terminate`,
+//! `kill`, `reset`, `none` (removes a default binding), or `{ send = "..." }` to
+//! write a literal string to the focused pane.
+
+use eframe::egui;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::config::KeyBinding;
+
+/// A key plus the exact set of modifiers that must be held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// The macOS Command key
+    pub cmd: bool,
+    pub key: egui::Key,
+}
+
+impl Chord {
+    /// Parses chord syntax such as `"Ctrl+Shift+C"`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut chord = Chord { ctrl: false, shift: false, alt: false, cmd: false, key: egui::Key::Escape };
+        let mut key = None;
+        // "Ctrl++" means Ctrl and the plus key
+        let normalized = text.trim().replace("++", "+Plus");
+        for part in normalized.split('+').map(str::trim) {
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.ctrl = true,
+                "shift" => chord.shift = true,
+                "alt" | "option" => chord.alt = true,
+                "cmd" | "command" | "super" | "meta" => chord.cmd = true,
+                "" => return Err(format!("empty key in {:?}", text)),
+                _ if key.is_some() => return Err(format!("more than one key in {:?}", text)),
+                _ => {
+                    key = Some(
+                        key_from_name(part).ok_or_else(|| format!("unknown key {:?} in {:?}", part, text))?,
+                    )
+                }
+            }
+        }
+        chord.key = key.ok_or_else(|| format!("no key in {:?}", text))?;
+        Ok(chord)
+    }
+
+    /// Whether a key event with `modifiers` triggers this chord
+    ///
+    /// Symbols that need Shift on common layouts (`Plus`, `Colon`, ...) match with
+    /// or without Shift so `Ctrl+Plus` works whether the layout reports it shifted.
+    pub fn matches(&self, key: egui::Key, modifiers: egui::Modifiers) -> bool {
+        let shifted_symbol = matches!(
+            self.key,
+            egui::Key::Plus | egui::Key::Colon | egui::Key::Questionmark | egui::Key::Pipe
+        );
+        self.key == key
+            && self.ctrl == modifiers.ctrl
+            && self.alt == modifiers.alt
+            && self.cmd == modifiers.mac_cmd
+            && (self.shift == modifiers.shift || (shifted_symbol && !self.shift))
+    }
+
+    /// Whether this chord takes a key the terminal normally receives
+    /// (Ctrl+letter control codes, or an unmodified key)
+    fn shadows_terminal_input(&self) -> bool {
+        let ctrl_letter = self.ctrl
+            && !self.shift
+            && !self.alt
+            && !self.cmd
+            && key_letter(self.key).is_some();
+        let bare = !self.ctrl && !self.alt && !self.cmd && !is_function_key(self.key);
+        ctrl_letter || bare
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (on, name) in [(self.ctrl, "Ctrl"), (self.shift, "Shift"), (self.alt, "Alt"), (self.cmd, "Cmd")] {
+            if on {
+                write!(f, "{}+", name)?;
+            }
+        }
+        write!(f, "{}", self.key.name())
+    }
+}
+
+/// Something a key chord can do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Focus pane N (0-based)
+    FocusPane(usize),
+    FocusNext,
+    FocusPrev,
+    /// Restart the focused tab's command
+    RestartTab,
+    /// Restart pane N's command (0-based)
+    RestartPane(usize),
+    /// Send SIGINT to the focused pane's foreground job
+    Interrupt,
+    /// Send SIGTERM to the focused pane's foreground job
+    Terminate,
+    /// Send SIGKILL to the focused pane's foreground job
+    ForceKill,
+    /// Reset the focused pane's terminal state (RIS)
+    Reset,
+    RestartAll,
+    /// Clear the focused pane and ask the shell to redraw (form feed)
+    Clear,
+    /// Copy the focused pane's visible text
+    Copy,
+    Paste,
+    /// Open the find bar for the focused pane
+    Search,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    /// Show or hide the File Manager panel
+    FileManager,
+    /// Open the settings editor
+    Settings,
+    /// Open the bookmarks picker
+    Bookmarks,
+    /// Open the script catalog
+    Scripts,
+    /// Open the analytics dashboard
+    Analytics,
+    /// Open the help viewer
+    Help,
+    /// Show or hide the developer tools (escape-sequence inspector)
+    Tools,
+    /// Open the command palette
+    CommandPalette,
+    /// Re-read `config.toml` and apply it
+    ReloadConfig,
+    /// Toggle the layout debug overlay (initially `ATS_DEBUG_OVERLAY`)
+    DebugOverlay,
+    /// Toggle window resize tracing (initially `ATS_WINDOW_TRACE`)
+    WindowTrace,
+    /// Start or stop an asciicast recording of the focused pane
+    Record,
+    /// Open the recording picker to play a `.cast` file
+    Recordings,
+    /// Write a literal string to the focused pane
+    Send(String),
+}
+
+/// Every action without arguments: config name, action, command palette title
+///
+/// Adding a row makes the action available to `[keybindings]`, `[[buttons]]` and
+/// the command palette. `focus_pane_N`, `restart_pane_N` and `send` take arguments
+/// and are handled separately.
+const ACTIONS: &[(&str, Action, &str)] = &[
+    ("focus_next", Action::FocusNext, "Focus next pane"),
+    ("focus_prev", Action::FocusPrev, "Focus previous pane"),
+    ("restart_tab", Action::RestartTab, "Restart focused tab"),
+    ("restart_all", Action::RestartAll, "Restart all tabs"),
+    ("interrupt", Action::Interrupt, "Interrupt focused job (SIGINT)"),
+    ("terminate", Action::Terminate, "Terminate focused job (SIGTERM)"),
+    ("kill", Action::ForceKill, "Force kill focused job (SIGKILL)"),
+    ("clear", Action::Clear, "Clear focused pane"),
+    ("reset", Action::Reset, "Reset focused terminal"),
+    ("copy", Action::Copy, "Copy visible text"),
+    ("paste", Action::Paste, "Paste"),
+    ("search", Action::Search, "Find in pane"),
+    ("zoom_in", Action::ZoomIn, "Zoom in"),
+    ("zoom_out", Action::ZoomOut, "Zoom out"),
+    ("zoom_reset", Action::ZoomReset, "Reset zoom"),
+    ("file_manager", Action::FileManager, "Toggle File Manager"),
+    ("settings", Action::Settings, "Open settings"),
+    ("bookmarks", Action::Bookmarks, "Open bookmarks"),
+    ("scripts", Action::Scripts, "Open script catalog"),
+    ("analytics", Action::Analytics, "Toggle analytics dashboard"),
+    ("help", Action::Help, "Open help"),
+    ("tools", Action::Tools, "Toggle developer tools"),
+    ("command_palette", Action::CommandPalette, "Show command palette"),
+    ("reload_config", Action::ReloadConfig, "Reload config.toml"),
+    ("debug_overlay", Action::DebugOverlay, "Toggle debug overlay"),
+    ("window_trace", Action::WindowTrace, "Toggle window resize trace"),
+    ("record", Action::Record, "Start or stop recording the focused pane"),
+    ("recordings", Action::Recordings, "Play a recording"),
+];
+
+impl Action {
+    /// Parses an action name; `Ok(None)` means `none` (unbind)
+    pub fn parse(name: &str) -> Result<Option<Self>, String> {
+        let name = name.trim().to_ascii_lowercase();
+        if name == "none" {
+            return Ok(None);
+        }
+        if let Some((_, action, _)) = ACTIONS.iter().find(|(n, _, _)| *n == name) {
+            return Ok(Some(action.clone()));
+        }
+        let pane = |prefix: &str| match name.strip_prefix(prefix).and_then(|n| n.parse::<usize>().ok()) {
+            Some(n @ 1..=4) => Some(n - 1),
+            _ => None,
+        };
+        if let Some(n) = pane("focus_pane_") {
+            Ok(Some(Action::FocusPane(n)))
+        } else if let Some(n) = pane("restart_pane_") {
+            Ok(Some(Action::RestartPane(n)))
+        } else {
+            Err(format!("unknown action {:?}", name))
+        }
+    }
+
+    /// Argument-free actions with their command palette titles, in menu order
+    pub fn palette() -> impl Iterator<Item = (Action, &'static str)> {
+        ACTIONS.iter().map(|(_, action, title)| (action.clone(), *title))
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::FocusPane(n) => write!(f, "focus_pane_{}", n + 1),
+            Action::RestartPane(n) => write!(f, "restart_pane_{}", n + 1),
+            Action::Send(text) => write!(f, "send {:?}", text),
+            action => {
+                let name = ACTIONS.iter().find(|(_, a, _)| a == action).map_or("?", |(n, _, _)| n);
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+/// Built-in bindings, applied before `[keybindings]`
+const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    ("Shift+Tab", "focus_next"),
+    ("Ctrl+Plus", "zoom_in"),
+    ("Ctrl+Equals", "zoom_in"),
+    ("Ctrl+Minus", "zoom_out"),
+    ("Ctrl+0", "zoom_reset"),
+    ("Ctrl+Shift+C", "copy"),
+    ("Ctrl+Shift+V", "paste"),
+    ("Ctrl+Shift+F", "search"),
+    ("Ctrl+Shift+B", "bookmarks"),
+    ("Ctrl+Shift+H", "help"),
+    ("Ctrl+Shift+P", "command_palette"),
+];
+
+/// Resolved chord → action bindings
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: Vec<(Chord, Action)>,
+}
+
+impl Keymap {
+    /// Builds the keymap from the defaults plus the `[keybindings]` section
+    ///
+    /// # Returns
+    ///
+    /// The keymap and a list of problems to report: unparseable chords or actions,
+    /// entries that resolve to the same chord, and chords that shadow terminal input.
+    /// Invalid entries are skipped; for duplicates the last entry wins.
+    pub fn from_config(config: &BTreeMap<String, KeyBinding>) -> (Self, Vec<String>) {
+        let mut keymap = Keymap::default();
+        for (chord, action) in DEFAULT_BINDINGS {
+            if let (Ok(chord), Ok(Some(action))) = (Chord::parse(chord), Action::parse(action)) {
+                keymap.bind(chord, Some(action));
+            }
+        }
+
+        let mut problems = Vec::new();
+        let mut seen: Vec<(Chord, &str)> = Vec::new();
+        for (text, binding) in config {
+            let chord = match Chord::parse(text) {
+                Ok(chord) => chord,
+                Err(e) => {
+                    problems.push(format!("[keybindings] {}", e));
+                    continue;
+                }
+            };
+            let action = match binding {
+                KeyBinding::Send { send } => Some(Action::Send(send.clone())),
+                KeyBinding::Action(name) => match Action::parse(name) {
+                    Ok(action) => action,
+                    Err(e) => {
+                        problems.push(format!("[keybindings] {:?}: {}", text, e));
+                        continue;
+                    }
+                },
+            };
+            if let Some((_, first)) = seen.iter().find(|(c, _)| *c == chord) {
+                problems.push(format!(
+                    "[keybindings] {:?} and {:?} are the same chord ({}); using {:?}",
+                    first, text, chord, text
+                ));
+            }
+            if action.is_some() && chord.shadows_terminal_input() {
+                problems.push(format!(
+                    "[keybindings] {:?} takes a key the terminal normally receives",
+                    text
+                ));
+            }
+            seen.push((chord, text));
+            keymap.bind(chord, action);
+        }
+        (keymap, problems)
+    }
+
+    /// Binds (or with `None`, unbinds) a chord, replacing any previous binding
+    fn bind(&mut self, chord: Chord, action: Option<Action>) {
+        self.bindings.retain(|(c, _)| *c != chord);
+        if let Some(action) = action {
+            self.bindings.push((chord, action));
+        }
+    }
+
+    /// Action bound to a key event, if any
+    pub fn lookup(&self, key: egui::Key, modifiers: egui::Modifiers) -> Option<&Action> {
+        self.bindings
+            .iter()
+            .find(|(chord, _)| chord.matches(key, modifiers))
+            .map(|(_, action)| action)
+    }
+
+    /// All bindings in the order they were defined
+    pub fn bindings(&self) -> &[(Chord, Action)] {
+        &self.bindings
+    }
+
+    /// Chords bound to `action`, joined for display (e.g. "Ctrl+Plus, Ctrl+Equals")
+    pub fn keys_for(&self, action: &Action) -> String {
+        let chords: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(_, a)| a == action)
+            .map(|(chord, _)| chord.to_string())
+            .collect();
+        chords.join(", ")
+    }
+}
+
+/// Encodes an unbound key press for the PTY
+///
+/// Ctrl+letter becomes the matching control code (Ctrl+C → 0x03). Enter sends CR
+/// and Backspace sends DEL, as xterm does. Keys with no terminal meaning return `None`.
+pub fn encode_key(key: egui::Key, modifiers: egui::Modifiers) -> Option<Vec<u8>> {
+    use egui::Key;
+
+    if modifiers.ctrl && !modifiers.alt {
+        if let Some(letter) = key_letter(key) {
+            return Some(vec![(letter as u8) & 0x1F]);
+        }
+    }
+    let seq: &[u8] = match key {
+        Key::Enter => b"\r",
+        Key::Backspace => &[0x7F],
+        Key::Tab if modifiers.shift => b"\x1b[Z",
+        Key::Tab => b"\t",
+        Key::Escape => b"\x1b",
+        Key::ArrowUp => b"\x1b[A",
+        Key::ArrowDown => b"\x1b[B",
+        Key::ArrowRight => b"\x1b[C",
+        Key::ArrowLeft => b"\x1b[D",
+        Key::Home => b"\x1b[H",
+        Key::End => b"\x1b[F",
+        Key::Insert => b"\x1b[2~",
+        Key::Delete => b"\x1b[3~",
+        Key::PageUp => b"\x1b[5~",
+        Key::PageDown => b"\x1b[6~",
+        Key::F1 => b"\x1bOP",
+        Key::F2 => b"\x1bOQ",
+        Key::F3 => b"\x1bOR",
+        Key::F4 => b"\x1bOS",
+        Key::F5 => b"\x1b[15~",
+        Key::F6 => b"\x1b[17~",
+        Key::F7 => b"\x1b[18~",
+        Key::F8 => b"\x1b[19~",
+        Key::F9 => b"\x1b[20~",
+        Key::F10 => b"\x1b[21~",
+        Key::F11 => b"\x1b[23~",
+        Key::F12 => b"\x1b[24~",
+        _ => return None,
+    };
+    Some(seq.to_vec())
+}
+
+fn key_from_name(name: &str) -> Option<egui::Key> {
+    egui::Key::from_name(name).or_else(|| {
+        // Accept any capitalization for named keys ("pageup", "ESC")
+        egui::Key::ALL
+            .iter()
+            .copied()
+            .find(|k| k.name().eq_ignore_ascii_case(name))
+    })
+}
+
+fn key_letter(key: egui::Key) -> Option<char> {
+    let name = key.name();
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_uppercase() => Some(c),
+        _ => None,
+    }
+}
+
+fn is_function_key(key: egui::Key) -> bool {
+    let name = key.name();
+    name.len() > 1 && name.starts_with('F') && name[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mods(ctrl: bool, shift: bool, alt: bool) -> egui::Modifiers {
+        egui::Modifiers { ctrl, shift, alt, mac_cmd: false, command: ctrl }
+    }
+
+    #[test]
+    fn test_chord_parse_and_display() {
+        let chord = Chord::parse("ctrl+shift+c").unwrap();
+        assert!(chord.ctrl && chord.shift && !chord.alt);
+        assert_eq!(chord.key, egui::Key::C);
+        assert_eq!(chord.to_string(), "Ctrl+Shift+C");
+
+        assert_eq!(Chord::parse("Ctrl++").unwrap().key, egui::Key::Plus);
+        assert_eq!(Chord::parse("pageup").unwrap().key, egui::Key::PageUp);
+        assert!(Chord::parse("Ctrl+Nope").is_err());
+        assert!(Chord::parse("Ctrl+A+B").is_err());
+        assert!(Chord::parse("Ctrl").is_err());
+    }
+
+    #[test]
+    fn test_chord_matching_is_exact() {
+        let chord = Chord::parse("Ctrl+Shift+C").unwrap();
+        assert!(chord.matches(egui::Key::C, mods(true, true, false)));
+        assert!(!chord.matches(egui::Key::C, mods(true, false, false)));
+        assert!(!chord.matches(egui::Key::C, mods(true, true, true)));
+
+        // Shifted symbols match either way
+        let plus = Chord::parse("Ctrl+Plus").unwrap();
+        assert!(plus.matches(egui::Key::Plus, mods(true, true, false)));
+        assert!(plus.matches(egui::Key::Plus, mods(true, false, false)));
+    }
+
+    #[test]
+    fn test_action_parse() {
+        assert_eq!(Action::parse("focus_pane_2").unwrap(), Some(Action::FocusPane(1)));
+        assert_eq!(Action::parse("COPY").unwrap(), Some(Action::Copy));
+        assert_eq!(Action::parse("none").unwrap(), None);
+        assert!(Action::parse("focus_pane_5").is_err());
+        assert!(Action::parse("explode").is_err());
+        assert_eq!(Action::parse("restart_pane_3").unwrap(), Some(Action::RestartPane(2)));
+    }
+
+    #[test]
+    fn test_action_names_round_trip() {
+        for (action, _) in Action::palette() {
+            assert_eq!(Action::parse(&action.to_string()).unwrap(), Some(action));
+        }
+    }
+
+    #[test]
+    fn test_keymap_defaults_and_overrides() {
+        let mut config = BTreeMap::new();
+        config.insert("Alt+1".to_string(), KeyBinding::Action("focus_pane_1".to_string()));
+        config.insert("Ctrl+Shift+F".to_string(), KeyBinding::Action("none".to_string()));
+        config.insert("F5".to_string(), KeyBinding::Send { send: "ls\r".to_string() });
+        let (keymap, problems) = Keymap::from_config(&config);
+        assert!(problems.is_empty(), "{:?}", problems);
+
+        assert_eq!(keymap.lookup(egui::Key::Tab, mods(false, true, false)), Some(&Action::FocusNext));
+        assert_eq!(keymap.lookup(egui::Key::Num1, mods(false, false, true)), Some(&Action::FocusPane(0)));
+        assert_eq!(keymap.lookup(egui::Key::F, mods(true, true, false)), None);
+        assert_eq!(
+            keymap.lookup(egui::Key::F5, egui::Modifiers::NONE),
+            Some(&Action::Send("ls\r".to_string()))
+        );
+        // Plain Tab is not bound
+        assert_eq!(keymap.lookup(egui::Key::Tab, egui::Modifiers::NONE), None);
+        assert_eq!(keymap.keys_for(&Action::ZoomIn), "Ctrl+Plus, Ctrl+Equals");
+        assert_eq!(keymap.keys_for(&Action::Search), "");
+    }
+
+    #[test]
+    fn test_keymap_reports_conflicts() {
+        let mut config = BTreeMap::new();
+        config.insert("Ctrl+Alt+K".to_string(), KeyBinding::Action("clear".to_string()));
+        config.insert("alt+ctrl+k".to_string(), KeyBinding::Action("copy".to_string()));
+        config.insert("Ctrl+C".to_string(), KeyBinding::Action("copy".to_string()));
+        config.insert("Ctrl+Q".to_string(), KeyBinding::Action("explode".to_string()));
+        config.insert("Hyper+Q".to_string(), KeyBinding::Action("copy".to_string()));
+        let (keymap, problems) = Keymap::from_config(&config);
+
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(problems.iter().any(|p| p.contains("same chord")));
+        assert!(problems.iter().any(|p| p.contains("terminal normally receives")));
+        // Later entry wins for the duplicated chord (BTreeMap order: "Ctrl+Alt+K" < "alt+ctrl+k")
+        assert_eq!(keymap.lookup(egui::Key::K, mods(true, false, true)), Some(&Action::Copy));
+    }
+
+    #[test]
+    fn test_encode_key() {
+        assert_eq!(encode_key(egui::Key::C, mods(true, false, false)), Some(vec![0x03]));
+        assert_eq!(encode_key(egui::Key::Enter, egui::Modifiers::NONE), Some(b"\r".to_vec()));
+        assert_eq!(encode_key(egui::Key::Backspace, egui::Modifiers::NONE), Some(vec![0x7F]));
+        assert_eq!(encode_key(egui::Key::Tab, mods(false, true, false)), Some(b"\x1b[Z".to_vec()));
+        assert_eq!(encode_key(egui::Key::ArrowLeft, egui::Modifiers::NONE), Some(b"\x1b[D".to_vec()));
+        assert_eq!(encode_key(egui::Key::F5, egui::Modifiers::NONE), Some(b"\x1b[15~".to_vec()));
+        // Printable keys arrive as text events, not here
+        assert_eq!(encode_key(egui::Key::A, egui::Modifiers::NONE), None);
+    }
+}
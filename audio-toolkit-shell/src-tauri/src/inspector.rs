@@ -0,0 +1,318 @@
+//! # Inspector Module
+//!
+//! This module draws the "🔧 Tools" developer panel: a live view of what the
+//! focused pane's program writes and what the emulator makes of it.
+//!
+//! ## Views
+//!
+//! - **Raw**: the output as received, with escapes and control characters made
+//!   visible (`␛[31m`, `\r`, `\n`, `\x7f`)
+//! - **Parsed**: one entry per text run, control character, CSI and OSC
+//!   sequence. Sequences the emulator ignores or prints as text are shown in red
+//!   with the reason (see [`crate::terminal::csi_support`])
+//! - **State**: cursor, SGR color and bold, mouse and paste modes, wrap state
+//! - **Cell**: the character, code point, width and attributes of the cell
+//!   under the pointer, which is outlined in the pane
+//!
+//! Capture is only active while the panel is open, and only for the focused pane.
+
+use eframe::egui;
+use std::collections::VecDeque;
+use unicode_width::UnicodeWidthChar;
+
+use crate::terminal::{csi_support, TerminalCell, TerminalEmulator, TraceEvent};
+use crate::theme::{CellColor, Theme};
+
+/// Raw output kept for display, in bytes
+const MAX_RAW: usize = 32 * 1024;
+/// Parsed entries kept for display
+const MAX_EVENTS: usize = 2000;
+/// Longest text run shown in the parsed view
+const MAX_TEXT_PREVIEW: usize = 80;
+
+/// Makes escapes and control characters in `raw` visible
+///
+/// Line feeds are kept after their `\n` marker so the output still reads line by line.
+pub fn escape_raw(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for ch in raw.chars() {
+        match ch {
+            '\u{1b}' => out.push('␛'),
+            '\r' => out.push_str("\\r"),
+            '\n' => out.push_str("\\n\n"),
+            '\t' => out.push_str("\\t"),
+            '\u{07}' => out.push_str("\\a"),
+            '\u{08}' => out.push_str("\\b"),
+            c if c.is_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Name of a C0 control character
+fn control_name(ch: char) -> String {
+    match ch {
+        '\r' => "CR carriage return".to_string(),
+        '\n' => "LF line feed".to_string(),
+        '\t' => "HT tab".to_string(),
+        '\u{07}' => "BEL bell".to_string(),
+        '\u{08}' => "BS backspace".to_string(),
+        '\u{0e}' => "SO shift out".to_string(),
+        '\u{0f}' => "SI shift in".to_string(),
+        '\u{7f}' => "DEL".to_string(),
+        c => format!("control \\x{:02x}", c as u32),
+    }
+}
+
+/// Meaning of an OSC payload and whether the app uses it
+fn osc_name(payload: &str) -> (&'static str, bool) {
+    match payload.split(';').next().unwrap_or("") {
+        "0" | "1" | "2" => ("window title", false),
+        "4" | "10" | "11" | "12" | "104" => ("color query/change", false),
+        "7" => ("working directory", false),
+        "8" => ("hyperlink", false),
+        "52" => ("clipboard", false),
+        "133" | "633" => ("shell integration (analytics)", true),
+        _ => ("unknown", false),
+    }
+}
+
+/// One line of the parsed view and whether it is handled
+pub fn describe(event: &TraceEvent) -> (String, bool) {
+    match event {
+        TraceEvent::Text(text) => {
+            let preview: String = text.chars().take(MAX_TEXT_PREVIEW).collect();
+            let more = if text.chars().count() > MAX_TEXT_PREVIEW { "…" } else { "" };
+            (format!("text \"{}{}\"", preview, more), true)
+        }
+        TraceEvent::Control(ch, true) => (control_name(*ch), true),
+        TraceEvent::Control(ch, false) => (format!("{} — ignored", control_name(*ch)), false),
+        TraceEvent::Csi(body, _) => match csi_support(body) {
+            Ok(name) => (format!("␛[{}  {}", body, name), true),
+            Err(reason) => (format!("␛[{}  {}", body, reason), false),
+        },
+        TraceEvent::Osc(payload) => {
+            let (name, used) = osc_name(payload);
+            let note = if used { "" } else { " — ignored" };
+            (format!("␛]{}  OSC {}{}", escape_raw(payload), name, note), used)
+        }
+        TraceEvent::Esc('c') => ("␛c  RIS full reset".to_string(), true),
+        TraceEvent::Esc(ch) => (format!("␛{}", ch), true),
+        TraceEvent::Invalid(seq) => (format!("{}  unsupported escape — printed as text", escape_raw(seq)), false),
+    }
+}
+
+/// Describes a cell color, e.g. "palette 9" or "#1e66f5"
+fn color_name(color: CellColor) -> String {
+    match color {
+        CellColor::Default => "default".to_string(),
+        CellColor::Indexed(index) => format!("palette {}", index),
+        CellColor::Rgb(c) => format!("#{:02x}{:02x}{:02x}", c.r(), c.g(), c.b()),
+    }
+}
+
+/// Developer tools window state
+#[derive(Default)]
+pub struct Inspector {
+    pub open: bool,
+    /// Stop appending captured output
+    paused: bool,
+    /// Show only sequences the emulator ignores or prints as text
+    only_unsupported: bool,
+    /// Pane the captured streams belong to
+    tab: Option<usize>,
+    raw: String,
+    events: VecDeque<TraceEvent>,
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends what the focused pane captured this frame; switching panes starts over
+    pub fn collect(&mut self, tab: usize, raw: String, events: Vec<TraceEvent>) {
+        if self.tab != Some(tab) {
+            self.tab = Some(tab);
+            self.clear();
+        }
+        if self.paused {
+            return;
+        }
+        self.raw.push_str(&raw);
+        if self.raw.len() > MAX_RAW {
+            let mut cut = self.raw.len() - MAX_RAW;
+            while !self.raw.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.raw.drain(..cut);
+        }
+        self.events.extend(events);
+        while self.events.len() > MAX_EVENTS {
+            self.events.pop_front();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.raw.clear();
+        self.events.clear();
+    }
+
+    /// Draws the panel for the focused pane
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - Title of the focused pane
+    /// * `emulator` - The pane's emulator, for modes and cell contents
+    /// * `hover` - Cell under the pointer in that pane, if any
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        theme: &Theme,
+        title: &str,
+        emulator: &TerminalEmulator,
+        hover: Option<(usize, usize)>,
+    ) {
+        let palette = &theme.palette;
+        ui.horizontal(|ui| {
+            ui.strong(format!("Pane: {}", title));
+            ui.separator();
+            ui.checkbox(&mut self.paused, "Pause");
+            ui.checkbox(&mut self.only_unsupported, "Only unsupported");
+            if ui.button("Clear").clicked() {
+                self.clear();
+            }
+        });
+        ui.separator();
+
+        egui::Grid::new("inspector_state").num_columns(4).spacing([14.0, 4.0]).show(ui, |ui| {
+            let (row, col) = emulator.cursor();
+            let (rows, cols) = emulator.size();
+            let (color, bold) = emulator.sgr();
+            let (tracking, encoding) = emulator.mouse_mode();
+            let on_off = |on: bool| if on { "on" } else { "off" };
+
+            ui.label("Cursor");
+            ui.monospace(format!("row {}, col {}", row + 1, col + 1));
+            ui.label("Size");
+            ui.monospace(format!("{}×{}", cols, rows));
+            ui.end_row();
+
+            ui.label("SGR");
+            ui.horizontal(|ui| {
+                swatch(ui, theme.resolve(color));
+                ui.monospace(format!("{}{}", color_name(color), if bold { ", bold" } else { "" }));
+            });
+            ui.label("Wrap pending");
+            ui.monospace(on_off(emulator.wrap_pending()));
+            ui.end_row();
+
+            ui.label("Mouse");
+            ui.monospace(format!("{:?} / {:?}", tracking, encoding));
+            ui.label("Bracketed paste");
+            ui.monospace(on_off(emulator.bracketed_paste()));
+            ui.end_row();
+
+            ui.label("Cell");
+            match hover.and_then(|(r, c)| emulator.buffer.get(r)?.get(c).map(|cell| (r, c, cell))) {
+                Some((r, c, cell)) => {
+                    ui.horizontal(|ui| {
+                        swatch(ui, theme.resolve(cell.color));
+                        ui.monospace(format!("({}, {}) {}", r + 1, c + 1, cell_description(cell)));
+                    });
+                }
+                None => {
+                    ui.weak("hover over the pane");
+                }
+            }
+            ui.end_row();
+        });
+        ui.separator();
+
+        let height = (ui.available_height() - 24.0).max(80.0);
+        ui.columns(2, |columns| {
+            columns[0].strong("Raw");
+            egui::ScrollArea::vertical()
+                .id_source("inspector_raw")
+                .max_height(height)
+                .auto_shrink([false, false])
+                .stick_to_bottom(true)
+                .show(&mut columns[0], |ui| {
+                    ui.add(egui::Label::new(egui::RichText::new(escape_raw(&self.raw)).monospace()).wrap(true));
+                });
+
+            columns[1].strong("Parsed");
+            let lines: Vec<(String, bool)> = self
+                .events
+                .iter()
+                .map(describe)
+                .filter(|(_, ok)| !self.only_unsupported || !ok)
+                .collect();
+            let row_h = columns[1].text_style_height(&egui::TextStyle::Monospace);
+            egui::ScrollArea::vertical()
+                .id_source("inspector_parsed")
+                .max_height(height)
+                .auto_shrink([false, false])
+                .stick_to_bottom(true)
+                .show_rows(&mut columns[1], row_h, lines.len(), |ui, range| {
+                    for (text, ok) in &lines[range] {
+                        let text = egui::RichText::new(text).monospace();
+                        let text = if *ok { text } else { text.color(palette.red) };
+                        ui.add(egui::Label::new(text).truncate(true));
+                    }
+                });
+        });
+    }
+}
+
+/// Character, code point, width and attributes of a cell
+fn cell_description(cell: &TerminalCell) -> String {
+    if cell.character == '\0' {
+        return "right half of a wide character".to_string();
+    }
+    let width = cell.character.width().unwrap_or(0);
+    format!(
+        "'{}' U+{:04X} width {}, {}{}",
+        escape_raw(&cell.character.to_string()),
+        cell.character as u32,
+        width,
+        color_name(cell.color),
+        if cell.bold { ", bold" } else { "" }
+    )
+}
+
+/// Small filled square showing a resolved color
+fn swatch(ui: &mut egui::Ui, color: egui::Color32) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+    ui.painter().rect_filled(rect, 2.0, color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_raw_shows_controls() {
+        assert_eq!(escape_raw("a\x1b[31mb\r\n\x07\x7f"), "a␛[31mb\\r\\n\n\\a\\x7f");
+    }
+
+    #[test]
+    fn test_describe_flags_unsupported() {
+        assert_eq!(describe(&TraceEvent::Csi("2J".into(), true)), ("␛[2J  ED erase display".to_string(), true));
+        assert!(!describe(&TraceEvent::Csi("1;24r".into(), false)).1);
+        assert!(describe(&TraceEvent::Osc("133;C".into())).1);
+        assert!(!describe(&TraceEvent::Osc("0;title".into())).1);
+        assert!(!describe(&TraceEvent::Invalid("\x1b7".into())).1);
+    }
+
+    #[test]
+    fn test_collect_resets_on_pane_switch_and_bounds_raw() {
+        let mut inspector = Inspector::new();
+        inspector.collect(0, "x".repeat(MAX_RAW + 10), vec![TraceEvent::Text("x".into())]);
+        assert_eq!(inspector.raw.len(), MAX_RAW);
+        inspector.collect(1, "y".into(), Vec::new());
+        assert_eq!(inspector.raw, "y");
+        assert!(inspector.events.is_empty());
+    }
+}
@@ -11,9 +11,27 @@
 //! The application is structured into focused modules:
 //! - `main.rs` - Application entry point and initialization
 //! - `app.rs` - Main application logic and UI rendering
+//! - `settings.rs` - In-app settings editor and validation
 //! - `terminal.rs` - Terminal emulation and ANSI processing
 //! - `theme.rs` - Catppuccin color theme definitions
+//! - `cli.rs` - Command-line options: config file, profile, ad-hoc tabs, layout, validation
 //! - `config.rs` - Configuration loading and management
+//! - `control.rs` - Control socket for scripting the shell, and the `ctl` subcommand
+//! - `dnd.rs` - Drag-and-drop templates, extension rules and placeholders
+//! - `analytics.rs` - Per-tab activity log, dashboard and CSV export
+//! - `bookmarks.rs` - Command bookmarks library, picker and placeholder expansion
+//! - `cast.rs` - asciicast v2 recording of panes and playback of `.cast` files
+//! - `scripts.rs` - Script catalog: folder scan, sidecar metadata and recent scripts
+//! - `session_log.rs` - Per-tab session logs: plain or raw output, rotation and pruning
+//! - `file_manager.rs` - File Manager panel: audio file listing and WAV/AIFF headers
+//! - `help.rs` - Help viewer: bundled docs, live key bindings and active configuration
+//! - `idle.rs` - Hang detection: idle jobs, prompts waiting for input, automatic response or restart
+//! - `inspector.rs` - Developer tools: escape-sequence inspector for the focused pane
+//! - `palette.rs` - Command palette: fuzzy search over actions, panes, themes and bookmarks
+//! - `fonts.rs` - Terminal font loading, zoom and cell metrics
+//! - `input.rs` - Key chords, bindable actions and key-to-PTY encoding
+//! - `mouse.rs` - Mouse reporting modes and PTY encodings
+//! - `notify.rs` - Per-tab notifications: exit, pattern and silence alerts, toasts, desktop notifier
 //! 
 //! ## Usage
 //! 
@@ -25,27 +43,85 @@
 
 use eframe::egui;
 
+mod analytics;
 mod app;
+mod bookmarks;
+mod cast;
+mod cli;
 mod config;
+mod control;
+mod dnd;
+mod file_manager;
+mod fonts;
+mod help;
+mod idle;
+mod input;
+mod inspector;
+mod mouse;
+mod notify;
+mod palette;
+mod scripts;
+mod session_log;
+mod settings;
 mod terminal;
 mod theme;
 
 use app::AudioToolkitApp;
+use cli::Command;
 use config::load_config;
 
 /// Application entry point
 /// 
 /// Initializes the application by:
-/// 1. Loading configuration from `config.toml` or using defaults
-/// 2. Setting up the egui native window with configured dimensions
-/// 3. Creating and running the main application instance
+/// 1. Parsing the command line (see [`cli`]) and installing its overrides
+/// 2. Loading configuration from `config.toml` or using defaults
+/// 3. Setting up the egui native window with configured dimensions and fonts
+/// 4. Creating and running the main application instance
+///
+/// `--validate-config`, `--print-default-config`, `--version` and `--help` exit
+/// without opening a window, and `audio-toolkit-shell ctl ...` talks to a running
+/// instance instead; see [`control`].
 /// 
 /// # Returns
 /// 
 /// Returns `Ok(())` on successful execution, or an `eframe::Error` if the
 /// application fails to initialize or run.
 fn main() -> Result<(), eframe::Error> {
-    let config = load_config();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = cli::parse(&args).unwrap_or_else(|e| {
+        eprintln!("audio-toolkit-shell: {}\n\n{}", e, cli::USAGE);
+        std::process::exit(2);
+    });
+    let (overrides, validate) = match command {
+        Command::Run(overrides) => (overrides, false),
+        Command::ValidateConfig(overrides) => (overrides, true),
+        Command::PrintDefaultConfig => {
+            print!("{}", config::DEFAULT_CONFIG_TEMPLATE);
+            return Ok(());
+        }
+        Command::Version => {
+            println!("audio-toolkit-shell {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Command::Ctl(args) => std::process::exit(control::run_cli(&args)),
+    };
+    config::set_overrides(overrides);
+    if let Some(name) = &config::overrides().profile {
+        if !config::profile_exists(name) {
+            let path = config::profile_path(name);
+            eprintln!("audio-toolkit-shell: no profile {:?} in config.toml or at {}", name, path.display());
+            std::process::exit(2);
+        }
+    }
+    if validate {
+        std::process::exit(cli::validate_config());
+    }
+
+    let (config, source) = load_config();
     let app_name = config.app.name.clone();
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -56,6 +132,9 @@ fn main() -> Result<(), eframe::Error> {
     eframe::run_native(
         &app_name,
         options,
-        Box::new(move |_cc| Box::new(AudioToolkitApp::new(config))),
+        Box::new(move |cc| {
+            fonts::install_fonts(&cc.egui_ctx, &config.app.font);
+            Box::new(AudioToolkitApp::new(config, source))
+        }),
     )
 }
\ No newline at end of file
@@ -0,0 +1,184 @@
+//! # Mouse Module
+//!
+//! This module implements xterm-style mouse reporting: the tracking and encoding
+//! modes an application enables with DEC private modes, and the byte sequences
+//! sent to the PTY for clicks, drags, wheel steps and motion.
+//!
+//! ## Modes
+//!
+//! | DEC mode | Meaning                                          |
+//! |----------|--------------------------------------------------|
+//! | 1000     | Report button presses and releases (and wheel)   |
+//! | 1002     | Also report motion while a button is held        |
+//! | 1003     | Also report motion with no button held           |
+//! | 1006     | SGR encoding: `ESC [ < b ; x ; y M/m`            |
+//! | 1015     | urxvt encoding: `ESC [ b ; x ; y M`              |
+//!
+//! Without 1006/1015 the legacy X10 encoding (`ESC [ M b x y`) is used, which
+//! cannot address columns or rows beyond 223.
+
+/// Which mouse events the application asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MouseTracking {
+    /// No reporting; the pane handles the mouse locally
+    #[default]
+    Off,
+    /// Presses, releases and wheel (DEC 1000)
+    Click,
+    /// Click tracking plus motion while a button is held (DEC 1002)
+    Drag,
+    /// Click tracking plus all motion (DEC 1003)
+    Any,
+}
+
+/// How mouse events are encoded for the PTY
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MouseEncoding {
+    /// Legacy `ESC [ M` with coordinates offset by 32 (limited to 223)
+    #[default]
+    X10,
+    /// SGR extended encoding (DEC 1006)
+    Sgr,
+    /// urxvt extended encoding (DEC 1015)
+    Urxvt,
+}
+
+/// Mouse button as reported to the application
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+/// Kind of mouse event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseEventKind {
+    Press(MouseButton),
+    Release(MouseButton),
+    /// Motion with the given button held
+    Drag(MouseButton),
+    /// Motion with no button held
+    Motion,
+    WheelUp,
+    WheelDown,
+}
+
+/// A mouse event at a 0-based cell position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    pub row: usize,
+    pub col: usize,
+    pub alt: bool,
+    pub ctrl: bool,
+}
+
+/// Encodes `event` for the PTY under the given modes
+///
+/// # Returns
+///
+/// The bytes to write, or `None` when the current tracking mode does not report
+/// this kind of event (or X10 cannot represent the position)
+pub fn encode(tracking: MouseTracking, encoding: MouseEncoding, event: &MouseEvent) -> Option<Vec<u8>> {
+    let wanted = match event.kind {
+        MouseEventKind::Press(_) | MouseEventKind::Release(_) => tracking != MouseTracking::Off,
+        MouseEventKind::WheelUp | MouseEventKind::WheelDown => tracking != MouseTracking::Off,
+        MouseEventKind::Drag(_) => matches!(tracking, MouseTracking::Drag | MouseTracking::Any),
+        MouseEventKind::Motion => tracking == MouseTracking::Any,
+    };
+    if !wanted {
+        return None;
+    }
+
+    let button_code = |b: MouseButton| match b {
+        MouseButton::Left => 0u32,
+        MouseButton::Middle => 1,
+        MouseButton::Right => 2,
+    };
+    let mut code = match event.kind {
+        MouseEventKind::Press(b) => button_code(b),
+        // X10 and urxvt cannot say which button was released
+        MouseEventKind::Release(b) if encoding == MouseEncoding::Sgr => button_code(b),
+        MouseEventKind::Release(_) => 3,
+        MouseEventKind::Drag(b) => button_code(b) + 32,
+        MouseEventKind::Motion => 3 + 32,
+        MouseEventKind::WheelUp => 64,
+        MouseEventKind::WheelDown => 65,
+    };
+    if event.alt {
+        code += 8;
+    }
+    if event.ctrl {
+        code += 16;
+    }
+
+    // Terminal coordinates are 1-based
+    let x = event.col + 1;
+    let y = event.row + 1;
+    match encoding {
+        MouseEncoding::Sgr => {
+            let final_char = if matches!(event.kind, MouseEventKind::Release(_)) { 'm' } else { 'M' };
+            Some(format!("\x1b[<{};{};{}{}", code, x, y, final_char).into_bytes())
+        }
+        MouseEncoding::Urxvt => Some(format!("\x1b[{};{};{}M", code + 32, x, y).into_bytes()),
+        MouseEncoding::X10 => {
+            let byte = |v: usize| u8::try_from(v + 32).ok();
+            Some(vec![0x1b, b'[', b'M', byte(code as usize)?, byte(x)?, byte(y)?])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: MouseEventKind, row: usize, col: usize) -> MouseEvent {
+        MouseEvent { kind, row, col, alt: false, ctrl: false }
+    }
+
+    #[test]
+    fn test_tracking_filters_events() {
+        let press = event(MouseEventKind::Press(MouseButton::Left), 0, 0);
+        let drag = event(MouseEventKind::Drag(MouseButton::Left), 0, 0);
+        let motion = event(MouseEventKind::Motion, 0, 0);
+
+        assert!(encode(MouseTracking::Off, MouseEncoding::Sgr, &press).is_none());
+        assert!(encode(MouseTracking::Click, MouseEncoding::Sgr, &press).is_some());
+        assert!(encode(MouseTracking::Click, MouseEncoding::Sgr, &drag).is_none());
+        assert!(encode(MouseTracking::Drag, MouseEncoding::Sgr, &drag).is_some());
+        assert!(encode(MouseTracking::Drag, MouseEncoding::Sgr, &motion).is_none());
+        assert!(encode(MouseTracking::Any, MouseEncoding::Sgr, &motion).is_some());
+    }
+
+    #[test]
+    fn test_sgr_encoding() {
+        let press = event(MouseEventKind::Press(MouseButton::Right), 4, 9);
+        assert_eq!(encode(MouseTracking::Click, MouseEncoding::Sgr, &press).unwrap(), b"\x1b[<2;10;5M");
+
+        let release = event(MouseEventKind::Release(MouseButton::Right), 4, 9);
+        assert_eq!(encode(MouseTracking::Click, MouseEncoding::Sgr, &release).unwrap(), b"\x1b[<2;10;5m");
+
+        let mut wheel = event(MouseEventKind::WheelDown, 0, 0);
+        wheel.ctrl = true;
+        assert_eq!(encode(MouseTracking::Click, MouseEncoding::Sgr, &wheel).unwrap(), b"\x1b[<81;1;1M");
+
+        let drag = event(MouseEventKind::Drag(MouseButton::Left), 1, 1);
+        assert_eq!(encode(MouseTracking::Drag, MouseEncoding::Sgr, &drag).unwrap(), b"\x1b[<32;2;2M");
+    }
+
+    #[test]
+    fn test_x10_and_urxvt_encoding() {
+        let press = event(MouseEventKind::Press(MouseButton::Left), 0, 0);
+        assert_eq!(encode(MouseTracking::Click, MouseEncoding::X10, &press).unwrap(), b"\x1b[M !!");
+
+        let release = event(MouseEventKind::Release(MouseButton::Left), 2, 3);
+        assert_eq!(encode(MouseTracking::Click, MouseEncoding::X10, &release).unwrap(), b"\x1b[M#$#");
+        assert_eq!(encode(MouseTracking::Click, MouseEncoding::Urxvt, &release).unwrap(), b"\x1b[35;4;3M");
+
+        // X10 cannot encode positions past 223
+        let far = event(MouseEventKind::Press(MouseButton::Left), 0, 300);
+        assert!(encode(MouseTracking::Click, MouseEncoding::X10, &far).is_none());
+        assert!(encode(MouseTracking::Click, MouseEncoding::Sgr, &far).is_some());
+    }
+}
@@ -0,0 +1,524 @@
+//! # Notify Module
+//!
+//! This module tells the user about tab events that are easy to miss: a batch in
+//! a background pane finishing or failing, an error message scrolling by, or
+//! output resuming after a quiet period.
+//!
+//! ## Events
+//!
+//! Each tab picks its events in `[tabs.notify]`, and a [`Watch`] per tab turns
+//! its output and exit into [`Alert`]s:
+//!
+//! - `on_success` / `on_failure`: the process exited with status 0 / anything else
+//! - `patterns`: an output line contains one of the strings (at most one alert
+//!   every few seconds, so a flood of matching lines raises one)
+//! - `after_silence_secs`: output arrives after that many seconds without any
+//!
+//! ## Delivery
+//!
+//! [`Notifications`] shows alerts as toasts in the bottom-right corner and, with
+//! `[notifications] desktop = true`, hands them to a [`Notifier`]. The default
+//! [`CommandNotifier`] runs `[notifications] command`, or `notify-send` on Linux
+//! and `osascript` on macOS. Tests plug in a notifier that only records, so no
+//! desktop session is needed. Alerts for the focused pane are skipped while the
+//! window has focus, unless `focused_pane` is set.
+
+use std::collections::BTreeMap;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+
+use crate::bookmarks;
+use crate::config::{NotificationSettings, NotifySettings};
+use crate::theme::Palette;
+
+/// Placeholders available in `[notifications] command`
+const PLACEHOLDERS: &[&str] = &["title", "body", "tab"];
+
+/// Toasts shown at once; older ones are dropped first
+const MAX_TOASTS: usize = 5;
+
+/// Pattern alerts of one tab closer together than this are dropped
+const PATTERN_COOLDOWN: Duration = Duration::from_secs(5);
+
+/// Longest partial line kept while waiting for its end
+const MAX_LINE: usize = 4096;
+
+/// Why a tab asks for attention
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    /// Exited with status 0
+    Success,
+    /// Exited with another status or a signal
+    Failure,
+    /// An output line matched a `[tabs.notify]` pattern
+    Pattern,
+    /// Output after `after_silence_secs` without any
+    Activity,
+}
+
+/// An event of one tab worth notifying about
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub kind: AlertKind,
+    /// Exit status, matching line, or how long the tab was quiet
+    pub detail: String,
+}
+
+impl Alert {
+    fn new(kind: AlertKind, detail: impl Into<String>) -> Self {
+        Self { kind, detail: detail.into() }
+    }
+
+    /// Notification title for the tab `tab`
+    pub fn title(&self, tab: &str) -> String {
+        match self.kind {
+            AlertKind::Success => format!("{} finished", tab),
+            AlertKind::Failure => format!("{} failed", tab),
+            AlertKind::Pattern => format!("{}: match", tab),
+            AlertKind::Activity => format!("{} is active again", tab),
+        }
+    }
+
+    /// Notification text
+    pub fn body(&self) -> String {
+        match self.kind {
+            AlertKind::Success => "Exited with status 0".to_string(),
+            AlertKind::Failure => format!("Exited with {}", self.detail),
+            AlertKind::Pattern => self.detail.clone(),
+            AlertKind::Activity => format!("New output after {} of silence", self.detail),
+        }
+    }
+}
+
+/// Turns the output and exit of one tab into alerts, following its `[tabs.notify]`
+#[derive(Debug)]
+pub struct Watch {
+    settings: NotifySettings,
+    last_output: Instant,
+    last_pattern: Option<Instant>,
+    /// Output after the last line break
+    line: String,
+}
+
+impl Watch {
+    pub fn new(settings: NotifySettings, now: Instant) -> Self {
+        Self { settings, last_output: now, last_pattern: None, line: String::new() }
+    }
+
+    pub fn configure(&mut self, settings: NotifySettings) {
+        self.settings = settings;
+    }
+
+    /// Starts over for a new run of the process
+    pub fn restart(&mut self, now: Instant) {
+        self.last_output = now;
+        self.last_pattern = None;
+        self.line.clear();
+    }
+
+    /// Alerts raised by `plain` (output with escape sequences removed) arriving at `now`
+    pub fn output(&mut self, plain: &str, now: Instant) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        let silence = self.settings.after_silence_secs;
+        let quiet = now.saturating_duration_since(self.last_output);
+        if silence > 0 && quiet.as_secs() >= silence && !plain.trim().is_empty() {
+            alerts.push(Alert::new(AlertKind::Activity, format_quiet(quiet)));
+        }
+        if !plain.trim().is_empty() {
+            self.last_output = now;
+        }
+        if self.settings.patterns.iter().all(|p| p.is_empty()) {
+            return alerts;
+        }
+
+        self.line.push_str(plain);
+        let Some(end) = self.line.rfind(['\n', '\r']) else {
+            if self.line.len() > MAX_LINE {
+                self.line.clear();
+            }
+            return alerts;
+        };
+        let complete: String = self.line.drain(..=end).collect();
+        for line in complete.split(['\n', '\r']) {
+            let matched = self.settings.patterns.iter().any(|p| !p.is_empty() && line.contains(p.as_str()));
+            if !matched {
+                continue;
+            }
+            if self.last_pattern.is_some_and(|at| now.saturating_duration_since(at) < PATTERN_COOLDOWN) {
+                break;
+            }
+            self.last_pattern = Some(now);
+            alerts.push(Alert::new(AlertKind::Pattern, line.trim()));
+            break;
+        }
+        alerts
+    }
+
+    /// Alert for the process exiting with `status` (an exit code or signal name)
+    pub fn exit(&self, status: &str) -> Option<Alert> {
+        if status == "0" {
+            self.settings.on_success.then(|| Alert::new(AlertKind::Success, status))
+        } else {
+            self.settings.on_failure.then(|| Alert::new(AlertKind::Failure, status))
+        }
+    }
+}
+
+/// "45s", "3m 20s", "2h 5m"
+pub fn format_quiet(quiet: Duration) -> String {
+    let secs = quiet.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 if secs % 60 == 0 => format!("{}m", secs / 60),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// A notification handed to a [`Notifier`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notice {
+    pub tab: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// Delivers notifications outside the app window
+pub trait Notifier {
+    fn notify(&mut self, notice: &Notice) -> Result<(), String>;
+}
+
+/// Runs `[notifications] command`, or the platform's notifier when it is empty
+pub struct CommandNotifier {
+    command: String,
+    quote: fn(&str) -> String,
+}
+
+impl CommandNotifier {
+    /// `quote` shell-quotes the values put into `command`
+    pub fn new(command: &str, quote: fn(&str) -> String) -> Self {
+        Self { command: command.trim().to_string(), quote }
+    }
+
+    /// Program and arguments for `notice`
+    fn argv(&self, notice: &Notice) -> Vec<String> {
+        if !self.command.is_empty() {
+            let line = expand_command(&self.command, notice, self.quote);
+            return vec!["sh".to_string(), "-c".to_string(), line];
+        }
+        if cfg!(target_os = "macos") {
+            let script = ["on run argv", "display notification (item 2 of argv) with title (item 1 of argv)", "end run"];
+            let mut argv = vec!["osascript".to_string()];
+            for line in script {
+                argv.push("-e".to_string());
+                argv.push(line.to_string());
+            }
+            argv.extend([notice.title.clone(), notice.body.clone()]);
+            argv
+        } else {
+            let app = "--app-name=Audio Toolkit Shell".to_string();
+            vec!["notify-send".to_string(), app, notice.title.clone(), notice.body.clone()]
+        }
+    }
+}
+
+impl Notifier for CommandNotifier {
+    fn notify(&mut self, notice: &Notice) -> Result<(), String> {
+        let argv = self.argv(notice);
+        let mut child = Command::new(&argv[0])
+            .args(&argv[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Cannot run {}: {}", argv[0], e))?;
+        // Reap the notifier without blocking the UI
+        thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+/// `command` with `{title}`, `{body}` and `{tab}` replaced by quoted values
+pub fn expand_command(command: &str, notice: &Notice, quote: fn(&str) -> String) -> String {
+    let values: BTreeMap<String, String> = [("title", &notice.title), ("body", &notice.body), ("tab", &notice.tab)]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), quote(value)))
+        .collect();
+    bookmarks::expand(command, &values, str::to_string)
+}
+
+/// Placeholders in a notifier command other than `{title}`, `{body}` and `{tab}`
+pub fn unknown_placeholders(command: &str) -> Vec<String> {
+    bookmarks::placeholders(command)
+        .into_iter()
+        .filter(|name| !PLACEHOLDERS.contains(&name.as_str()))
+        .collect()
+}
+
+/// Whether desktop notifications work without `[notifications] command`
+pub fn has_native_notifier() -> bool {
+    cfg!(unix)
+}
+
+/// An alert shown in the corner of the window
+#[derive(Debug, Clone)]
+struct Toast {
+    pane: usize,
+    kind: AlertKind,
+    title: String,
+    body: String,
+    shown: Instant,
+}
+
+/// Toasts and desktop notifications for every tab
+pub struct Notifications {
+    settings: NotificationSettings,
+    notifier: Box<dyn Notifier>,
+    quote: fn(&str) -> String,
+    toasts: Vec<Toast>,
+}
+
+impl Notifications {
+    pub fn new(settings: &NotificationSettings, quote: fn(&str) -> String) -> Self {
+        let notifier = Box::new(CommandNotifier::new(&settings.command, quote));
+        Self::with_notifier(settings, notifier, quote)
+    }
+
+    /// Uses `notifier` for desktop notifications instead of the platform's
+    pub fn with_notifier(settings: &NotificationSettings, notifier: Box<dyn Notifier>, quote: fn(&str) -> String) -> Self {
+        Self { settings: settings.clone(), notifier, quote, toasts: Vec::new() }
+    }
+
+    /// Applies new settings; a changed `command` gets a new [`CommandNotifier`]
+    pub fn set_settings(&mut self, settings: &NotificationSettings) {
+        if settings.command != self.settings.command {
+            self.notifier = Box::new(CommandNotifier::new(&settings.command, self.quote));
+        }
+        if !settings.toasts {
+            self.toasts.clear();
+        }
+        self.settings = settings.clone();
+    }
+
+    /// Delivers `alert` from pane `pane`; `in_view` when the user is looking at that pane
+    ///
+    /// # Returns
+    ///
+    /// Whether the alert was delivered (it is skipped for the pane in view
+    /// unless `focused_pane` is set)
+    pub fn push(&mut self, pane: usize, tab: &str, alert: &Alert, in_view: bool, now: Instant) -> bool {
+        if in_view && !self.settings.focused_pane {
+            return false;
+        }
+        let notice = Notice { tab: tab.to_string(), title: alert.title(tab), body: alert.body() };
+        if self.settings.desktop {
+            if let Err(e) = self.notifier.notify(&notice) {
+                eprintln!("[NOTIFY] {}", e);
+            }
+        }
+        if self.settings.toasts {
+            if self.toasts.len() == MAX_TOASTS {
+                self.toasts.remove(0);
+            }
+            self.toasts.push(Toast { pane, kind: alert.kind, title: notice.title, body: notice.body, shown: now });
+        }
+        true
+    }
+
+    /// Drops the toasts of closed pane `index` and renumbers those of later panes
+    pub fn pane_removed(&mut self, index: usize) {
+        self.toasts.retain(|t| t.pane != index);
+        for toast in self.toasts.iter_mut().filter(|t| t.pane > index) {
+            toast.pane -= 1;
+        }
+    }
+
+    /// Drops every toast, for when the panes they point at are replaced
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+    }
+
+    /// Drops toasts older than `toast_seconds`
+    pub fn expire(&mut self, now: Instant) {
+        let lifetime = Duration::from_secs(self.settings.toast_seconds);
+        self.toasts.retain(|t| now.saturating_duration_since(t.shown) < lifetime);
+    }
+
+    /// Draws the toasts in the bottom-right corner, newest at the bottom
+    ///
+    /// # Returns
+    ///
+    /// The pane of a toast that was clicked, to focus it
+    pub fn show(&mut self, ctx: &egui::Context, palette: &Palette) -> Option<usize> {
+        if self.toasts.is_empty() {
+            return None;
+        }
+        let mut clicked = None;
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("notification_toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-16.0, -16.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (i, toast) in self.toasts.iter().enumerate() {
+                    let accent = match toast.kind {
+                        AlertKind::Success => palette.green,
+                        AlertKind::Failure => palette.red,
+                        AlertKind::Pattern => palette.yellow,
+                        AlertKind::Activity => palette.blue,
+                    };
+                    let frame = egui::Frame::popup(ui.style()).fill(palette.mantle).stroke(egui::Stroke::new(1.0, accent));
+                    let response = frame
+                        .show(ui, |ui| {
+                            ui.set_width(280.0);
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(&toast.title).color(accent).strong());
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    if ui.small_button("✕").clicked() {
+                                        dismissed = Some(i);
+                                    }
+                                });
+                            });
+                            ui.add(egui::Label::new(egui::RichText::new(&toast.body).color(palette.subtext1)).truncate(true));
+                        })
+                        .response
+                        .interact(egui::Sense::click())
+                        .on_hover_text("Click to focus the pane");
+                    if response.clicked() {
+                        clicked = Some(i);
+                    }
+                    ui.add_space(6.0);
+                }
+            });
+        if let Some(i) = dismissed.or(clicked) {
+            let toast = self.toasts.remove(i);
+            if dismissed.is_none() {
+                return Some(toast.pane);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::bookmarks::test_quote as quote;
+
+    /// Records notices instead of showing them
+    struct RecordingNotifier(Rc<RefCell<Vec<Notice>>>);
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&mut self, notice: &Notice) -> Result<(), String> {
+            self.0.borrow_mut().push(notice.clone());
+            Ok(())
+        }
+    }
+
+    fn watch(settings: NotifySettings, start: Instant) -> Watch {
+        Watch::new(settings, start)
+    }
+
+    #[test]
+    fn test_watch_patterns_and_silence() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let settings = NotifySettings {
+            patterns: vec!["ERROR".to_string()],
+            after_silence_secs: 60,
+            ..Default::default()
+        };
+        let mut watch = watch(settings, start);
+
+        // A match split across chunks is found once the line ends
+        assert!(watch.output("take1.wav ER", at(1)).is_empty());
+        let alerts = watch.output("ROR: clipping\r\nnext\n", at(2));
+        assert_eq!(alerts, vec![Alert::new(AlertKind::Pattern, "take1.wav ERROR: clipping")]);
+        // Further matches within the cooldown are dropped
+        assert!(watch.output("ERROR again\n", at(3)).is_empty());
+        assert_eq!(watch.output("ERROR later\n", at(10)).len(), 1);
+
+        // Output after a minute of silence
+        let alerts = watch.output("resumed\n", at(200));
+        assert_eq!(alerts, vec![Alert::new(AlertKind::Activity, "3m 10s")]);
+        assert!(watch.output("more\n", at(201)).is_empty());
+
+        watch.restart(at(300));
+        assert!(watch.output("fresh start\n", at(330)).is_empty());
+    }
+
+    #[test]
+    fn test_watch_exit() {
+        let now = Instant::now();
+        let both = NotifySettings { on_success: true, on_failure: true, ..Default::default() };
+        assert_eq!(watch(both.clone(), now).exit("0").map(|a| a.kind), Some(AlertKind::Success));
+        assert_eq!(watch(both, now).exit("Killed").map(|a| a.kind), Some(AlertKind::Failure));
+        let failures = NotifySettings { on_failure: true, ..Default::default() };
+        assert_eq!(watch(failures.clone(), now).exit("0"), None);
+        assert_eq!(watch(failures, now).exit("1").unwrap().body(), "Exited with 1");
+        assert_eq!(watch(NotifySettings::default(), now).exit("1"), None);
+    }
+
+    #[test]
+    fn test_notifications_headless() {
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let settings = NotificationSettings { desktop: true, ..Default::default() };
+        let mut notifications = Notifications::with_notifier(&settings, Box::new(RecordingNotifier(sent.clone())), quote);
+        let now = Instant::now();
+        let done = Alert::new(AlertKind::Success, "0");
+
+        assert!(notifications.push(1, "Normalizer", &done, false, now));
+        assert!(!notifications.push(0, "Shell", &done, true, now));
+        assert_eq!(
+            *sent.borrow(),
+            vec![Notice {
+                tab: "Normalizer".to_string(),
+                title: "Normalizer finished".to_string(),
+                body: "Exited with status 0".to_string(),
+            }]
+        );
+        assert_eq!(notifications.toasts.len(), 1);
+
+        for _ in 0..10 {
+            notifications.push(2, "Mixer", &done, false, now + Duration::from_secs(4));
+        }
+        assert_eq!(notifications.toasts.len(), MAX_TOASTS);
+        assert!(notifications.toasts.iter().all(|t| t.pane == 2));
+        notifications.expire(now + Duration::from_secs(9));
+        assert_eq!(notifications.toasts.len(), MAX_TOASTS);
+        notifications.expire(now + Duration::from_secs(10));
+        assert!(notifications.toasts.is_empty());
+
+        // Toasts only; the desktop notifier is not called
+        let toasts_only = NotificationSettings { focused_pane: true, ..Default::default() };
+        notifications.set_settings(&toasts_only);
+        assert!(notifications.push(0, "Shell", &done, true, now));
+        assert_eq!(sent.borrow().len(), 11);
+        assert_eq!(notifications.toasts.len(), 1);
+
+        // Closing a lower pane renumbers the toasts; closing their own pane drops them
+        notifications.push(2, "Mixer", &done, false, now);
+        notifications.pane_removed(1);
+        assert_eq!(notifications.toasts.iter().map(|t| t.pane).collect::<Vec<_>>(), vec![0, 1]);
+        notifications.pane_removed(1);
+        assert_eq!(notifications.toasts.iter().map(|t| t.pane).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn test_notifier_command() {
+        let notice = Notice { tab: "Mix".to_string(), title: "Mix failed".to_string(), body: "Exited with 1".to_string() };
+        assert_eq!(
+            expand_command("notifier -t {title} -m {body} # {tab} {{x}}", &notice, quote),
+            "notifier -t 'Mix failed' -m 'Exited with 1' # 'Mix' {x}"
+        );
+        let custom = CommandNotifier::new(" logger {body} ", quote);
+        assert_eq!(custom.argv(&notice), vec!["sh", "-c", "logger 'Exited with 1'"]);
+        assert_eq!(CommandNotifier::new("", quote).argv(&notice).last().map(String::as_str), Some("Exited with 1"));
+        assert_eq!(unknown_placeholders("n {title} {message} {tab}"), vec!["message"]);
+    }
+}
@@ -0,0 +1,203 @@
+//! # Command Palette Module
+//!
+//! This module implements the command palette (`Ctrl+Shift+P` by default): a
+//! fuzzy-searchable list of everything the app can do, with the key bindings of
+//! each entry.
+//!
+//! ## Entries
+//!
+//! The app builds the list each time the palette opens:
+//!
+//! - every argument-free [`Action`] from the action registry (see
+//!   [`Action::palette`]), so new actions appear without changes here
+//! - focus and restart entries for each pane, by title
+//! - one entry per theme, profile and bookmark
+//!
+//! ## Matching
+//!
+//! The query matches when its characters appear in order in the title
+//! (case-insensitive). Consecutive characters and word starts rank higher.
+
+use eframe::egui;
+
+use crate::input::Action;
+use crate::theme::Palette;
+
+/// What an entry does when chosen
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Action(Action),
+    /// Switch to the theme with this id
+    Theme(String),
+    /// Switch to this profile (`None`: no profile)
+    Profile(Option<String>),
+    /// Open the bookmark with this name in the bookmarks picker
+    Bookmark(String),
+}
+
+/// One row of the palette
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub title: String,
+    /// Key bindings shown on the right; empty when unbound
+    pub keys: String,
+    pub command: Command,
+}
+
+impl Entry {
+    pub fn new(title: impl Into<String>, keys: impl Into<String>, command: Command) -> Self {
+        Self { title: title.into(), keys: keys.into(), command }
+    }
+}
+
+/// Scores `text` against `query`; `None` when the query characters do not all
+/// appear in order. Higher is better.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last: Option<usize> = None;
+    for q in query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()) {
+        let found = pos + text[pos..].iter().position(|&c| c == q)?;
+        score += 1;
+        if last.is_some_and(|l| l + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - pos).min(10) as i32 / 2;
+        last = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+/// Indices of `entries` matching `query`, best first (ties keep list order)
+pub fn filter(entries: &[Entry], query: &str) -> Vec<usize> {
+    let mut scored: Vec<(usize, i32)> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| fuzzy_score(query, &e.title).map(|s| (i, s)))
+        .collect();
+    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+/// State of the command palette window
+#[derive(Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    entries: Vec<Entry>,
+    query: String,
+    /// Highlighted row among the filtered entries
+    cursor: usize,
+    focus_query: bool,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the palette with a fresh list of entries
+    pub fn open_with(&mut self, entries: Vec<Entry>) {
+        self.entries = entries;
+        self.query.clear();
+        self.cursor = 0;
+        self.focus_query = true;
+        self.open = true;
+    }
+
+    /// Draws the palette; returns the chosen command and closes
+    pub fn show(&mut self, ui: &mut egui::Ui, palette: &Palette) -> Option<Command> {
+        let filtered = filter(&self.entries, &self.query);
+        self.cursor = self.cursor.min(filtered.len().saturating_sub(1));
+        let mut chosen = None;
+
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut self.query)
+                .hint_text("Type a command")
+                .desired_width(f32::INFINITY),
+        );
+        if std::mem::take(&mut self.focus_query) {
+            response.request_focus();
+        }
+        if response.changed() {
+            self.cursor = 0;
+        }
+        let mut scroll_to_cursor = false;
+        if response.has_focus() {
+            ui.input(|i| {
+                if i.key_pressed(egui::Key::ArrowDown) {
+                    self.cursor = (self.cursor + 1).min(filtered.len().saturating_sub(1));
+                    scroll_to_cursor = true;
+                }
+                if i.key_pressed(egui::Key::ArrowUp) {
+                    self.cursor = self.cursor.saturating_sub(1);
+                    scroll_to_cursor = true;
+                }
+            });
+        }
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            chosen = filtered.get(self.cursor).copied();
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical().max_height(360.0).auto_shrink([false, true]).show(ui, |ui| {
+            if filtered.is_empty() {
+                ui.weak("No matching commands");
+            }
+            for (row, &index) in filtered.iter().enumerate() {
+                let entry = &self.entries[index];
+                let selected = row == self.cursor;
+                let inner = ui.horizontal(|ui| {
+                    let response = ui.selectable_label(selected, &entry.title);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(egui::RichText::new(&entry.keys).monospace().color(palette.overlay1));
+                    });
+                    response
+                });
+                if inner.inner.clicked() {
+                    chosen = Some(index);
+                }
+                if selected && scroll_to_cursor {
+                    inner.response.scroll_to_me(None);
+                }
+            }
+        });
+
+        let command = chosen.map(|index| self.entries[index].command.clone());
+        if command.is_some() {
+            self.open = false;
+        }
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Anything"), Some(0));
+        assert!(fuzzy_score("rst", "Restart all tabs").is_some());
+        assert!(fuzzy_score("tsr", "Restart all tabs").is_none());
+        // Consecutive and word-start matches rank higher
+        assert!(fuzzy_score("rel", "Reload config.toml") > fuzzy_score("rel", "Restart all tabs"));
+        assert!(fuzzy_score("o", "Open help") > fuzzy_score("o", "Toggle debug overlay"));
+    }
+
+    #[test]
+    fn test_filter_orders_by_score() {
+        let entries = vec![
+            Entry::new("Toggle debug overlay", "", Command::Action(Action::DebugOverlay)),
+            Entry::new("Open help", "Ctrl+Shift+H", Command::Action(Action::Help)),
+            Entry::new("Theme: Mocha", "", Command::Theme("mocha".into())),
+        ];
+        assert_eq!(filter(&entries, "o"), vec![1, 0, 2]);
+        assert_eq!(filter(&entries, ""), vec![0, 1, 2]);
+        assert_eq!(filter(&entries, "mocha"), vec![2]);
+    }
+}
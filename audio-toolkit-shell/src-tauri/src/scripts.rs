@@ -0,0 +1,568 @@
+//! # Scripts Module
+//!
+//! This module implements the script catalog opened from the "📜 Scripts"
+//! button: it scans the `[scripts] dirs` folders for runnable files, reads
+//! optional sidecar metadata, remembers recently launched scripts, and builds
+//! the command line the app types into the chosen pane.
+//!
+//! ## What Is Listed
+//!
+//! `.sh`, `.command` and `.py` files, plus any file with an executable bit (on
+//! Unix). Hidden entries and macOS `.app` bundles are skipped.
+//!
+//! ## Sidecar Metadata
+//!
+//! A `<file name>.toml` (or `<stem>.toml`) next to the script may contain:
+//!
+//! ```toml
+//! name = "Normalize to -23 LUFS"
+//! description = "Batch loudness normalization for broadcast"
+//! args = "--target {lufs} {dir}"    # appended to the command line
+//! interpreter = "python3"           # overrides the default for the extension
+//! ```
+//!
+//! `args` placeholders work like bookmark placeholders and are asked for
+//! before launching.
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use crate::bookmarks;
+use crate::config::ScriptsSettings;
+use crate::theme::Palette;
+
+/// A runnable entry in the catalog
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    pub path: PathBuf,
+    pub name: String,
+    pub description: String,
+    /// Arguments appended to the command line; may contain `{placeholders}`
+    pub args: String,
+    /// Program that runs the file (e.g. "python3"); empty runs it directly
+    pub interpreter: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct Sidecar {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    args: String,
+    interpreter: Option<String>,
+}
+
+impl Script {
+    /// Builds the catalog entry for `path`, reading its sidecar when present
+    pub fn from_path(path: &Path) -> Self {
+        let file_name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let ext = path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+        let sidecar = sidecar_path(path).map(|p| read_sidecar(&p)).unwrap_or_default();
+        let interpreter = sidecar.interpreter.unwrap_or_else(|| {
+            match ext.as_str() {
+                "py" => "python3",
+                "sh" | "command" => "bash",
+                _ => "",
+            }
+            .to_string()
+        });
+        Self {
+            path: path.to_path_buf(),
+            name: if sidecar.name.trim().is_empty() { file_name } else { sidecar.name },
+            description: sidecar.description,
+            args: sidecar.args,
+            interpreter,
+        }
+    }
+
+    /// Command line with `args` placeholders filled from `values`
+    pub fn command_line(&self, values: &BTreeMap<String, String>, quote: impl Fn(&str) -> String) -> String {
+        let mut line = String::new();
+        if !self.interpreter.trim().is_empty() {
+            line.push_str(self.interpreter.trim());
+            line.push(' ');
+        }
+        line.push_str(&quote(&self.path.to_string_lossy()));
+        let args = bookmarks::expand(&self.args, values, &quote);
+        if !args.trim().is_empty() {
+            line.push(' ');
+            line.push_str(args.trim());
+        }
+        line
+    }
+
+    /// True when every term of `query` occurs in the name, description or path
+    pub fn matches(&self, query: &str) -> bool {
+        bookmarks::matches_query(query, &[&self.name, &self.description, &self.path.to_string_lossy()])
+    }
+}
+
+fn sidecar_path(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?.to_string_lossy().into_owned();
+    let mut candidates = vec![path.with_file_name(format!("{}.toml", file_name))];
+    if let Some(stem) = path.file_stem().filter(|_| path.extension().is_some()) {
+        candidates.push(path.with_file_name(format!("{}.toml", stem.to_string_lossy())));
+    }
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+fn read_sidecar(path: &Path) -> Sidecar {
+    match fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|c| toml::from_str(&c).map_err(|e| e.to_string())) {
+        Ok(sidecar) => sidecar,
+        Err(e) => {
+            eprintln!("[SCRIPTS] Ignoring {:?}: {}", path, e);
+            Sidecar::default()
+        }
+    }
+}
+
+/// Whether `path` is something the catalog can launch
+fn is_script(path: &Path, meta: &fs::Metadata) -> bool {
+    let ext = path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+    if matches!(ext.as_str(), "sh" | "command" | "py") {
+        return true;
+    }
+    if ext == "toml" {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        meta.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = meta;
+        matches!(ext.as_str(), "exe" | "bat" | "cmd")
+    }
+}
+
+/// Scans `dirs` (up to `max_depth` folder levels below each) for scripts
+///
+/// Results are sorted by name; a script reachable from two dirs is listed once.
+pub fn scan(dirs: &[PathBuf], max_depth: usize) -> Vec<Script> {
+    fn walk(dir: &Path, depth: usize, max_depth: usize, out: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            eprintln!("[SCRIPTS] Cannot read {:?}", dir);
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let Ok(meta) = fs::metadata(&path) else {
+                continue;
+            };
+            if meta.is_dir() {
+                if depth < max_depth && !name.ends_with(".app") {
+                    walk(&path, depth + 1, max_depth, out);
+                }
+            } else if is_script(&path, &meta) {
+                out.push(path);
+            }
+        }
+    }
+
+    let mut paths = Vec::new();
+    for dir in dirs {
+        walk(dir, 0, max_depth, &mut paths);
+    }
+    paths.sort();
+    paths.dedup();
+    let mut scripts: Vec<Script> = paths.iter().map(|p| Script::from_path(p)).collect();
+    scripts.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()).then_with(|| a.path.cmp(&b.path)));
+    scripts
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+struct RecentFile {
+    #[serde(default)]
+    recent: Vec<PathBuf>,
+}
+
+/// Loads the recently launched script paths (most recent first)
+pub fn load_recent(path: &Path) -> Vec<PathBuf> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| toml::from_str::<RecentFile>(&content).ok())
+        .map(|file| file.recent)
+        .unwrap_or_default()
+}
+
+/// Writes the recently launched script paths
+pub fn save_recent(path: &Path, recent: &[PathBuf]) -> Result<(), String> {
+    let content = toml::to_string(&RecentFile { recent: recent.to_vec() }).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Moves `script` to the front of `recent`, keeping at most `limit` entries
+pub fn push_recent(recent: &mut Vec<PathBuf>, script: &Path, limit: usize) {
+    recent.retain(|p| p != script);
+    recent.insert(0, script.to_path_buf());
+    recent.truncate(limit);
+}
+
+/// Where a launched script runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchTarget {
+    /// An existing pane (index into the app's tabs)
+    Pane(usize),
+    /// A new floating pane
+    NewPane,
+}
+
+/// A script the user launched from the catalog
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptLaunch {
+    pub name: String,
+    pub command: String,
+    pub target: LaunchTarget,
+}
+
+/// State of the script catalog window
+pub struct ScriptCatalog {
+    pub open: bool,
+    dirs: Vec<PathBuf>,
+    max_depth: usize,
+    scripts: Vec<Script>,
+    recent: Vec<PathBuf>,
+    recent_path: PathBuf,
+    recent_limit: usize,
+    query: String,
+    /// Selected script (index into `scripts`) and its placeholder values
+    selected: Option<(usize, Vec<(String, String)>)>,
+    /// Defaults for `{dir}` and `{file}` (see [`bookmarks::path_context`])
+    context: BTreeMap<String, String>,
+    /// `{dir}` default still being looked up (see [`bookmarks::spawn_dir_lookup`])
+    pending_dir: Option<Receiver<Option<PathBuf>>>,
+    target: LaunchTarget,
+    focus_query: bool,
+    /// Focus the first placeholder field on the next frame
+    focus_form: bool,
+}
+
+impl ScriptCatalog {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            dirs: Vec::new(),
+            max_depth: 0,
+            scripts: Vec::new(),
+            recent: Vec::new(),
+            recent_path: PathBuf::new(),
+            recent_limit: 0,
+            query: String::new(),
+            selected: None,
+            context: BTreeMap::new(),
+            pending_dir: None,
+            target: LaunchTarget::NewPane,
+            focus_query: false,
+            focus_form: false,
+        }
+    }
+
+    /// Opens the catalog, rescanning the configured folders
+    ///
+    /// `focused` is the default target pane; `dir` and `file` become the
+    /// defaults for `{dir}` and `{file}` placeholders.
+    pub fn open_with(
+        &mut self,
+        settings: &ScriptsSettings,
+        recent_path: PathBuf,
+        focused: usize,
+        dir: Option<PathBuf>,
+        file: Option<PathBuf>,
+    ) {
+        self.dirs = settings.dirs.iter().map(PathBuf::from).collect();
+        self.max_depth = settings.max_depth;
+        self.recent_limit = settings.recent_limit;
+        self.scripts = scan(&self.dirs, self.max_depth);
+        self.recent = load_recent(&recent_path);
+        self.recent_path = recent_path;
+        self.context = bookmarks::path_context(dir, file);
+        self.pending_dir = None;
+        self.query.clear();
+        self.selected = None;
+        self.target = LaunchTarget::Pane(focused);
+        self.focus_query = true;
+        self.open = true;
+    }
+
+    /// Looks up the `{dir}` default in the background, replacing the one given when opening
+    pub fn lookup_dir(&mut self, find: impl FnOnce() -> Option<PathBuf> + Send + 'static) {
+        self.pending_dir = Some(bookmarks::spawn_dir_lookup(find));
+    }
+
+    fn select(&mut self, index: usize) {
+        let values = bookmarks::placeholder_values(&self.scripts[index].args, &BTreeMap::new(), &self.context);
+        self.selected = Some((index, values));
+    }
+
+    /// Whether the selected script can launch without asking: every placeholder has a value
+    fn ready(&self) -> bool {
+        self.selected.as_ref().is_some_and(|(_, values)| values.iter().all(|(_, value)| !value.trim().is_empty()))
+    }
+
+    /// Enter or double-click on `index`: launches when nothing needs asking, otherwise
+    /// selects the script so its placeholder form shows
+    fn activate(&mut self, index: usize, quote: impl Fn(&str) -> String) -> Option<ScriptLaunch> {
+        if self.selected.as_ref().map(|(i, _)| *i) != Some(index) {
+            self.select(index);
+        }
+        if self.ready() {
+            return self.launch(quote);
+        }
+        self.focus_form = true;
+        None
+    }
+
+    /// Builds the launch for the selected script and records it as recent
+    fn launch(&mut self, quote: impl Fn(&str) -> String) -> Option<ScriptLaunch> {
+        let (index, values) = self.selected.as_ref()?;
+        let script = &self.scripts[*index];
+        let values: BTreeMap<String, String> = values.iter().cloned().collect();
+        let launch = ScriptLaunch { name: script.name.clone(), command: script.command_line(&values, quote), target: self.target };
+        push_recent(&mut self.recent, &script.path, self.recent_limit);
+        if let Err(e) = save_recent(&self.recent_path, &self.recent) {
+            eprintln!("[SCRIPTS] {}", e);
+        }
+        self.open = false;
+        Some(launch)
+    }
+
+    /// Draws the catalog
+    ///
+    /// `panes` are the titles of the app's panes, used for the target picker.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        palette: &Palette,
+        panes: &[String],
+        quote: impl Fn(&str) -> String,
+    ) -> Option<ScriptLaunch> {
+        let values = self.selected.as_mut().map(|(_, values)| values);
+        if bookmarks::poll_dir_lookup(&mut self.pending_dir, &mut self.context, values) {
+            ui.ctx().request_repaint_after(Duration::from_millis(100));
+        }
+        let filtered: Vec<usize> = (0..self.scripts.len()).filter(|&i| self.scripts[i].matches(&self.query)).collect();
+        let mut launch = false;
+        let mut clicked = None;
+        let mut activated = None;
+
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text("Search scripts")
+                    .desired_width(ui.available_width() - 80.0),
+            );
+            if std::mem::take(&mut self.focus_query) {
+                response.request_focus();
+            }
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                activated = self.selected.as_ref().map(|(i, _)| *i).or_else(|| filtered.first().copied());
+            }
+            if ui.button("⟳ Rescan").clicked() {
+                self.scripts = scan(&self.dirs, self.max_depth);
+                self.selected = None;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Run in:");
+            let label = |target: LaunchTarget| match target {
+                LaunchTarget::Pane(i) => panes.get(i).cloned().unwrap_or_else(|| format!("Pane {}", i + 1)),
+                LaunchTarget::NewPane => "New pane".to_string(),
+            };
+            egui::ComboBox::from_id_source("scripts_target")
+                .selected_text(label(self.target))
+                .show_ui(ui, |ui| {
+                    for i in 0..panes.len() {
+                        ui.selectable_value(&mut self.target, LaunchTarget::Pane(i), label(LaunchTarget::Pane(i)));
+                    }
+                    ui.selectable_value(&mut self.target, LaunchTarget::NewPane, label(LaunchTarget::NewPane));
+                });
+        });
+        ui.separator();
+
+        egui::TopBottomPanel::bottom("scripts_details")
+            .frame(egui::Frame::none())
+            .show_inside(ui, |ui| {
+                let Some((index, values)) = self.selected.as_mut() else {
+                    ui.weak("Select a script to see its details. Double-click or Enter to launch.");
+                    return;
+                };
+                let script = &self.scripts[*index];
+                ui.add_space(4.0);
+                ui.strong(&script.name);
+                if !script.description.is_empty() {
+                    ui.label(&script.description);
+                }
+                egui::Grid::new("scripts_args").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                    for (i, (name, value)) in values.iter_mut().enumerate() {
+                        ui.label(format!("{{{}}}", name));
+                        let response = ui.add(egui::TextEdit::singleline(value).desired_width(f32::INFINITY));
+                        if i == 0 && std::mem::take(&mut self.focus_form) {
+                            response.request_focus();
+                        }
+                        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            launch = true;
+                        }
+                        ui.end_row();
+                    }
+                });
+                let preview: BTreeMap<String, String> = values.iter().cloned().collect();
+                ui.label(egui::RichText::new(script.command_line(&preview, &quote)).monospace().color(palette.green));
+                if ui.button("▶ Launch").clicked() {
+                    launch = true;
+                }
+            });
+
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            if self.dirs.is_empty() {
+                ui.weak("No script folders configured. Add them under [scripts] dirs in config.toml or in ⚙️ Settings.");
+            }
+            let selected = self.selected.as_ref().map(|(i, _)| *i);
+            let mut row = |ui: &mut egui::Ui, index: usize| {
+                let script = &self.scripts[index];
+                let folder = script.path.parent().and_then(Path::file_name).map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                let response = ui
+                    .horizontal(|ui| {
+                        let response = ui.selectable_label(selected == Some(index), egui::RichText::new(&script.name).strong());
+                        ui.weak(folder);
+                        response
+                    })
+                    .inner
+                    .on_hover_text(script.path.display().to_string());
+                if response.double_clicked() {
+                    activated = Some(index);
+                } else if response.clicked() {
+                    clicked = Some(index);
+                }
+            };
+
+            if self.query.trim().is_empty() {
+                let recent: Vec<usize> = self
+                    .recent
+                    .iter()
+                    .filter_map(|p| self.scripts.iter().position(|s| &s.path == p))
+                    .collect();
+                if !recent.is_empty() {
+                    ui.label(egui::RichText::new("Recent").color(palette.subtext0));
+                    for index in recent {
+                        row(ui, index);
+                    }
+                    ui.separator();
+                }
+            }
+            for &index in &filtered {
+                row(ui, index);
+            }
+        });
+
+        if let Some(index) = clicked {
+            self.select(index);
+        }
+        if let Some(index) = activated {
+            return self.activate(index, quote);
+        }
+        if launch {
+            return self.launch(quote);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::test_quote as quote;
+
+    #[test]
+    fn test_scan_and_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("clean.sh"), "echo").unwrap();
+        fs::write(root.join("clean.sh.toml"), "name = \"Clean up\"\ndescription = \"Removes temp files\"\nargs = \"--in {dir}\"\n").unwrap();
+        fs::write(root.join("notes.txt"), "x").unwrap();
+        fs::write(root.join(".hidden.sh"), "x").unwrap();
+        fs::create_dir_all(root.join("tools/deeper/too_deep")).unwrap();
+        fs::write(root.join("tools/analyze.py"), "print()").unwrap();
+        fs::write(root.join("tools/deeper/too_deep/skip.sh"), "x").unwrap();
+        fs::create_dir_all(root.join("Thing.app/Contents")).unwrap();
+        fs::write(root.join("Thing.app/Contents/run.sh"), "x").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::write(root.join("normalizer"), "bin").unwrap();
+            fs::set_permissions(root.join("normalizer"), fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let scripts = scan(&[root.to_path_buf(), root.to_path_buf()], 1);
+        let names: Vec<&str> = scripts.iter().map(|s| s.name.as_str()).collect();
+        #[cfg(unix)]
+        assert_eq!(names, vec!["analyze.py", "Clean up", "normalizer"]);
+        #[cfg(not(unix))]
+        assert_eq!(names, vec!["analyze.py", "Clean up"]);
+
+        let clean = scripts.iter().find(|s| s.name == "Clean up").unwrap();
+        assert_eq!(clean.description, "Removes temp files");
+        let mut values = BTreeMap::new();
+        values.insert("dir".to_string(), "/work dir".to_string());
+        assert_eq!(
+            clean.command_line(&values, quote),
+            format!("bash '{}' --in '/work dir'", root.join("clean.sh").display())
+        );
+        let analyze = scripts.iter().find(|s| s.name == "analyze.py").unwrap();
+        assert_eq!(analyze.command_line(&BTreeMap::new(), quote), format!("python3 '{}'", analyze.path.display()));
+        assert!(clean.matches("clean TEMP"));
+    }
+
+    #[test]
+    fn test_recent_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recent_scripts.toml");
+        assert!(load_recent(&path).is_empty());
+
+        let mut recent = Vec::new();
+        for name in ["a", "b", "c", "a"] {
+            push_recent(&mut recent, Path::new(name), 2);
+        }
+        assert_eq!(recent, vec![PathBuf::from("a"), PathBuf::from("c")]);
+        save_recent(&path, &recent).unwrap();
+        assert_eq!(load_recent(&path), recent);
+    }
+
+    #[test]
+    fn test_activate_asks_for_unfilled_placeholders() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("norm.sh"), "x").unwrap();
+        fs::write(root.join("norm.sh.toml"), "args = \"--lufs {lufs} {dir}\"\n").unwrap();
+        fs::write(root.join("plain.sh"), "x").unwrap();
+        let settings = ScriptsSettings { dirs: vec![root.to_string_lossy().into_owned()], ..ScriptsSettings::default() };
+        let mut catalog = ScriptCatalog::new();
+        catalog.open_with(&settings, root.join("recent.toml"), 0, Some(PathBuf::from("/work")), None);
+
+        // {lufs} has no value: the form shows instead of launching
+        assert_eq!(catalog.activate(0, quote), None);
+        assert!(catalog.open && catalog.focus_form);
+        assert_eq!(catalog.selected.as_ref().unwrap().1[1], ("dir".to_string(), "/work".to_string()));
+        // Activating again keeps what was typed and launches once it is filled in
+        catalog.selected.as_mut().unwrap().1[0].1 = "-16".to_string();
+        let launch = catalog.activate(0, quote).expect("launched");
+        assert!(launch.command.ends_with("--lufs -16 '/work'"));
+
+        // Nothing to ask: launches right away
+        catalog.open = true;
+        let launch = catalog.activate(1, quote).expect("launched");
+        assert_eq!(launch.command, format!("bash '{}'", root.join("plain.sh").display()));
+    }
+}
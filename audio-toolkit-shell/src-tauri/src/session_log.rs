@@ -0,0 +1,313 @@
+//! # Session Log Module
+//!
+//! This module writes a pane's output to disk when its `[[tabs]]` entry sets
+//! `log = "plain"` or `log = "raw"`, giving an audit trail of what ran.
+//!
+//! ## Files
+//!
+//! Logs go to `[logging] dir` (default: `logs` next to `config.toml`), one file
+//! per run, named `<tab>_<YYYY-MM-DD>_<HHMMSS>Z.log` (UTC). A file that reaches
+//! `max_file_mb` is closed and the next one continues the run. When a file
+//! starts, the tab's logs older than `max_age_days` are deleted.
+//!
+//! Each file starts with `#` header lines: tab title, command and start time.
+//!
+//! ## Formats
+//!
+//! - **plain**: escape sequences and control characters removed; CR LF and
+//!   lone CR (progress updates) become line breaks
+//! - **raw**: the output exactly as received, for replay with `cat`
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::analytics::{self, format_timestamp};
+use crate::config::{LogMode, LoggingSettings};
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Parser state for removing escape sequences across output chunks
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum StripState {
+    #[default]
+    Text,
+    /// After ESC
+    Escape,
+    /// Inside `ESC [`, until the final byte
+    Csi,
+    /// Inside an OSC/DCS string, until BEL or ST
+    String,
+    /// ESC inside a string, usually the start of ST (`ESC \`)
+    StringEscape,
+    /// After `ESC (` or `ESC )`, which take one more character
+    Charset,
+}
+
+/// Turns terminal output into plain text, keeping state between chunks
+#[derive(Debug, Default)]
+pub struct PlainText {
+    state: StripState,
+    /// A CR was seen; a following LF belongs to it
+    carriage_return: bool,
+}
+
+impl PlainText {
+    pub fn strip(&mut self, data: &str) -> String {
+        let mut out = String::with_capacity(data.len());
+        for ch in data.chars() {
+            self.state = match self.state {
+                StripState::Text => {
+                    let after_cr = std::mem::take(&mut self.carriage_return);
+                    match ch {
+                        '\u{1b}' => StripState::Escape,
+                        '\r' => {
+                            self.carriage_return = true;
+                            out.push('\n');
+                            StripState::Text
+                        }
+                        '\n' if after_cr => StripState::Text,
+                        '\n' | '\t' => {
+                            out.push(ch);
+                            StripState::Text
+                        }
+                        c if c.is_control() => StripState::Text,
+                        c => {
+                            out.push(c);
+                            StripState::Text
+                        }
+                    }
+                }
+                StripState::Escape => match ch {
+                    '[' => StripState::Csi,
+                    ']' | 'P' | 'X' | '^' | '_' => StripState::String,
+                    '(' | ')' => StripState::Charset,
+                    _ => StripState::Text,
+                },
+                StripState::Csi if ('\u{40}'..='\u{7e}').contains(&ch) => StripState::Text,
+                StripState::Csi => StripState::Csi,
+                StripState::String => match ch {
+                    '\u{07}' => StripState::Text,
+                    '\u{1b}' => StripState::StringEscape,
+                    _ => StripState::String,
+                },
+                StripState::StringEscape | StripState::Charset => StripState::Text,
+            };
+        }
+        out
+    }
+}
+
+/// File-name-safe form of a tab title, e.g. "Start Scripts" -> "start-scripts"
+pub fn file_prefix(title: &str) -> String {
+    let mut prefix = String::new();
+    for ch in title.chars() {
+        if ch.is_alphanumeric() {
+            prefix.extend(ch.to_lowercase());
+        } else if !prefix.is_empty() && !prefix.ends_with('-') {
+            prefix.push('-');
+        }
+    }
+    let prefix = prefix.trim_end_matches('-');
+    if prefix.is_empty() {
+        "tab".to_string()
+    } else {
+        prefix.to_string()
+    }
+}
+
+/// File name for a tab's log or recording started at Unix time `at`
+pub fn file_name(prefix: &str, at: u64, extension: &str) -> String {
+    let stamp = format_timestamp(at).replace('T', "_").replace(':', "");
+    format!("{}_{}.{}", prefix, stamp, extension)
+}
+
+/// Deletes `<prefix>_*.log` files in `dir` last modified more than `max_age` ago
+///
+/// Returns the number of files removed.
+pub fn prune(dir: &Path, prefix: &str, max_age: Duration) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let cutoff = SystemTime::now().checked_sub(max_age).unwrap_or(SystemTime::UNIX_EPOCH);
+    let start = format!("{}_", prefix);
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name.starts_with(&start) || !name.ends_with(".log") {
+            continue;
+        }
+        let old = entry.metadata().and_then(|m| m.modified()).is_ok_and(|modified| modified < cutoff);
+        if old && fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Session log of one tab
+#[derive(Debug)]
+pub struct SessionLog {
+    mode: LogMode,
+    dir: PathBuf,
+    max_bytes: u64,
+    max_age_days: u64,
+    title: String,
+    command: String,
+    /// Open file and its path; `None` after a write error until the next run
+    file: Option<(File, PathBuf)>,
+    written: u64,
+    plain: PlainText,
+}
+
+impl SessionLog {
+    /// Starts logging a run of `command`; `None` when the tab's mode is off
+    pub fn start(settings: &LoggingSettings, mode: LogMode, title: &str, command: &str) -> Option<Self> {
+        if mode == LogMode::Off {
+            return None;
+        }
+        let mut log = Self {
+            mode,
+            dir: settings.log_dir(),
+            max_bytes: settings.max_file_mb.max(1) * 1024 * 1024,
+            max_age_days: settings.max_age_days,
+            title: title.to_string(),
+            command: command.to_string(),
+            file: None,
+            written: 0,
+            plain: PlainText::default(),
+        };
+        log.open("started");
+        Some(log)
+    }
+
+    /// Path of the file being written
+    pub fn path(&self) -> Option<&Path> {
+        self.file.as_ref().map(|(_, path)| path.as_path())
+    }
+
+    pub fn mode(&self) -> LogMode {
+        self.mode
+    }
+
+    /// Begins a new file for the next run of the tab
+    pub fn restart(&mut self, command: &str) {
+        self.command = command.to_string();
+        self.plain = PlainText::default();
+        self.open("started");
+    }
+
+    /// Opens a new file with a header; `event` says why ("started", "continued")
+    fn open(&mut self, event: &str) {
+        self.file = None;
+        self.written = 0;
+        if let Err(e) = fs::create_dir_all(&self.dir) {
+            eprintln!("[LOG] Cannot create {:?}: {}", self.dir, e);
+            return;
+        }
+        let prefix = file_prefix(&self.title);
+        if self.max_age_days > 0 {
+            prune(&self.dir, &prefix, Duration::from_secs(self.max_age_days * SECS_PER_DAY));
+        }
+        let at = analytics::now();
+        let mut path = self.dir.join(file_name(&prefix, at, "log"));
+        // Restarts within the same second get a numbered name
+        let mut n = 1;
+        while path.exists() {
+            n += 1;
+            path = self.dir.join(file_name(&prefix, at, &format!("{}.log", n)).replace("Z.", "Z-"));
+        }
+        let header = format!(
+            "# Audio Toolkit Shell session log\n# tab: {}\n# command: {}\n# {}: {}\n\n",
+            self.title,
+            self.command,
+            event,
+            format_timestamp(at)
+        );
+        match File::create(&path).and_then(|mut file| file.write_all(header.as_bytes()).map(|_| file)) {
+            Ok(file) => self.file = Some((file, path)),
+            Err(e) => eprintln!("[LOG] Cannot write {:?}: {}", path, e),
+        }
+    }
+
+    /// Appends a chunk of output, moving to a new file at the size limit
+    pub fn write(&mut self, data: &str) {
+        if self.file.is_none() {
+            return;
+        }
+        let text = match self.mode {
+            LogMode::Plain => self.plain.strip(data),
+            _ => data.to_string(),
+        };
+        if text.is_empty() {
+            return;
+        }
+        if self.written >= self.max_bytes {
+            self.open("continued");
+        }
+        let Some((file, path)) = self.file.as_mut() else {
+            return;
+        };
+        match file.write_all(text.as_bytes()) {
+            Ok(()) => self.written += text.len() as u64,
+            Err(e) => {
+                eprintln!("[LOG] Stopped logging to {:?}: {}", path, e);
+                self.file = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_strips_sequences_across_chunks() {
+        let mut plain = PlainText::default();
+        assert_eq!(plain.strip("\x1b[1;31mred\x1b[0m ok\r\n"), "red ok\n");
+        assert_eq!(plain.strip("a\x1b]0;ti"), "a");
+        assert_eq!(plain.strip("tle\x07b\x1b[3"), "b");
+        assert_eq!(plain.strip("2mc\x1b]133;C\x1b\\d\x1b(Be\x07"), "cde");
+        assert_eq!(plain.strip("10%\r20%\r"), "10%\n20%\n");
+        assert_eq!(plain.strip("\ndone\n"), "done\n");
+    }
+
+    #[test]
+    fn test_file_prefix_and_name() {
+        assert_eq!(file_prefix("Start Scripts"), "start-scripts");
+        assert_eq!(file_prefix("  Loudness / R128 "), "loudness-r128");
+        assert_eq!(file_prefix("🎧"), "tab");
+        assert_eq!(file_name("tab", 86_400 + 3_723, "log"), "tab_1970-01-02_010203Z.log");
+    }
+
+    #[test]
+    fn test_session_log_writes_rotates_and_prunes() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        fs::write(dir.path().join("mixer_2000-01-01_000000Z.log"), "old").unwrap();
+        fs::write(dir.path().join("other_2000-01-01_000000Z.log"), "keep").unwrap();
+        let old = SystemTime::now() - Duration::from_secs(40 * SECS_PER_DAY);
+        for name in ["mixer_2000-01-01_000000Z.log", "other_2000-01-01_000000Z.log"] {
+            File::options().write(true).open(dir.path().join(name)).unwrap().set_modified(old).unwrap();
+        }
+        let settings = LoggingSettings { dir: dir.path().to_string_lossy().into_owned(), max_file_mb: 1, max_age_days: 30 };
+
+        let mut log = SessionLog::start(&settings, LogMode::Plain, "Mixer", "normalize -23").expect("log enabled");
+        assert!(!dir.path().join("mixer_2000-01-01_000000Z.log").exists());
+        assert!(dir.path().join("other_2000-01-01_000000Z.log").exists());
+        log.write("\x1b[32mnormalized\x1b[0m take1.wav\r\n");
+        let first = log.path().unwrap().to_path_buf();
+        let content = fs::read_to_string(&first).unwrap();
+        assert!(content.starts_with("# Audio Toolkit Shell session log\n# tab: Mixer\n# command: normalize -23\n"));
+        assert!(content.ends_with("\nnormalized take1.wav\n"));
+
+        log.write(&"x".repeat(1024 * 1024));
+        log.write("more");
+        let second = log.path().unwrap().to_path_buf();
+        assert_ne!(first, second);
+        assert!(fs::read_to_string(&second).unwrap().contains("# continued: "));
+
+        assert!(SessionLog::start(&settings, LogMode::Off, "Mixer", "bash").is_none());
+    }
+}
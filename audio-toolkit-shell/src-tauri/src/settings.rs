@@ -0,0 +1,847 @@
+//! # Settings Module
+//!
+//! This module implements the in-app settings editor opened from the
+//! "⚙️ Settings" button. It edits a draft copy of the loaded [`AppConfig`]
+//! (application settings, font, every tab, key bindings and buttons), validates
+//! it, and hands the result back to the app, which writes it to `config.toml`
+//! with [`crate::config::save_config`] and applies it live.
+//!
+//! List-valued fields are edited as text, one entry per line. Control characters
+//! in `send` bindings and button `keys` are shown escaped (`\r`, `\e`, `\x03`).
+
+use eframe::egui;
+use std::path::Path;
+
+use crate::config::{self, AppConfig, ButtonConfig, DndRouting, DndSettings, DropMode, IdleAction, IdleSettings, KeyBinding, LogMode, NotifySettings, QueueWait, TabConfig, ZoomScope, MAX_TABS};
+use crate::dnd;
+use crate::notify;
+use crate::input::{Action, Chord};
+use crate::theme::{self, Palette, Theme};
+
+/// One `[keybindings]` row while editing
+#[derive(Debug, Clone, PartialEq)]
+struct BindingRow {
+    chord: String,
+    /// Action name, or the escaped text to send when `send` is set
+    value: String,
+    send: bool,
+}
+
+/// State of the settings editor window
+pub struct SettingsEditor {
+    pub open: bool,
+    draft: AppConfig,
+    /// `[app.font] fallbacks`, one path per line
+    fallbacks: String,
+    /// `success_patterns` per tab, one pattern per line
+    patterns: Vec<String>,
+    /// `[tabs.notify] patterns` per tab, one pattern per line
+    notify_patterns: Vec<String>,
+    /// `[scripts] dirs`, one folder per line
+    script_dirs: String,
+    bindings: Vec<BindingRow>,
+    /// Escaped `keys` per button
+    button_keys: Vec<String>,
+    /// Result of the last save (message, is_error)
+    status: Option<(String, bool)>,
+}
+
+impl SettingsEditor {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            draft: crate::config::default_config(),
+            fallbacks: String::new(),
+            patterns: Vec::new(),
+            notify_patterns: Vec::new(),
+            script_dirs: String::new(),
+            bindings: Vec::new(),
+            button_keys: Vec::new(),
+            status: None,
+        }
+    }
+
+    /// Opens the editor on a copy of `config`
+    pub fn open_with(&mut self, config: &AppConfig) {
+        self.draft = config.clone();
+        self.fallbacks = config.app.font.fallbacks.join("\n");
+        self.patterns = config.tabs.iter().map(|t| t.success_patterns.join("\n")).collect();
+        self.notify_patterns = config.tabs.iter().map(|t| t.notify.patterns.join("\n")).collect();
+        self.script_dirs = config.scripts.dirs.join("\n");
+        self.bindings = config
+            .keybindings
+            .iter()
+            .map(|(chord, binding)| match binding {
+                KeyBinding::Action(name) => BindingRow { chord: chord.clone(), value: name.clone(), send: false },
+                KeyBinding::Send { send } => BindingRow { chord: chord.clone(), value: escape_control(send), send: true },
+            })
+            .collect();
+        self.button_keys = config.buttons.iter().map(|b| escape_control(&b.keys)).collect();
+        self.status = None;
+        self.open = true;
+    }
+
+    /// Reports the outcome of a save in the window footer
+    pub fn set_status(&mut self, message: String, is_error: bool) {
+        self.status = Some((message, is_error));
+    }
+
+    /// The draft with the text-edited fields folded back in
+    fn build(&self) -> AppConfig {
+        let lines = |text: &str| -> Vec<String> {
+            text.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect()
+        };
+        let mut config = self.draft.clone();
+        config.app.font.fallbacks = lines(&self.fallbacks);
+        for ((tab, patterns), notify_patterns) in config.tabs.iter_mut().zip(&self.patterns).zip(&self.notify_patterns) {
+            tab.success_patterns = lines(patterns);
+            tab.notify.patterns = lines(notify_patterns);
+        }
+        config.scripts.dirs = lines(&self.script_dirs);
+        config.keybindings = self
+            .bindings
+            .iter()
+            .filter(|row| !row.chord.trim().is_empty())
+            .map(|row| {
+                let binding = if row.send {
+                    KeyBinding::Send { send: unescape_control(&row.value) }
+                } else {
+                    KeyBinding::Action(row.value.trim().to_string())
+                };
+                (row.chord.trim().to_string(), binding)
+            })
+            .collect();
+        for (button, keys) in config.buttons.iter_mut().zip(&self.button_keys) {
+            button.keys = unescape_control(keys);
+        }
+        config
+    }
+
+    /// Draws the editor
+    ///
+    /// # Returns
+    ///
+    /// The edited configuration when "Save & Apply" is clicked and it validates
+    pub fn show(&mut self, ui: &mut egui::Ui, themes: &[Theme], palette: &Palette) -> Option<AppConfig> {
+        let mut save = false;
+        let config = self.build();
+        let problems = validate(&config, themes);
+
+        egui::TopBottomPanel::bottom("settings_footer")
+            .frame(egui::Frame::none())
+            .show_inside(ui, |ui| {
+                ui.add_space(4.0);
+                for problem in &problems {
+                    ui.colored_label(palette.red, format!("• {}", problem));
+                }
+                if let Some((message, is_error)) = &self.status {
+                    ui.colored_label(if *is_error { palette.red } else { palette.green }, message);
+                }
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(problems.is_empty(), egui::Button::new("Save & Apply")).clicked() {
+                        save = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.open = false;
+                    }
+                });
+            });
+
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            self.app_section(ui, themes);
+            self.font_section(ui);
+            self.tabs_section(ui);
+            self.keybindings_section(ui);
+            self.buttons_section(ui);
+            self.scripts_section(ui);
+            self.analytics_section(ui);
+            self.logging_section(ui);
+            self.notifications_section(ui);
+            self.control_section(ui);
+            self.profiles_section(ui);
+        });
+
+        if save && problems.is_empty() {
+            Some(config)
+        } else {
+            None
+        }
+    }
+
+    fn app_section(&mut self, ui: &mut egui::Ui, themes: &[Theme]) {
+        let app = &mut self.draft.app;
+        egui::CollapsingHeader::new("Application").default_open(true).show(ui, |ui| {
+            egui::Grid::new("settings_app").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Window title");
+                ui.text_edit_singleline(&mut app.name);
+                ui.end_row();
+                ui.label("Window size");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut app.window_width).clamp_range(200.0..=10000.0).suffix(" w"));
+                    ui.add(egui::DragValue::new(&mut app.window_height).clamp_range(200.0..=10000.0).suffix(" h"));
+                });
+                ui.end_row();
+                ui.label("Minimum panel widths");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut app.min_left_width).clamp_range(0.0..=2000.0).suffix(" left"));
+                    ui.add(egui::DragValue::new(&mut app.min_right_width).clamp_range(0.0..=2000.0).suffix(" right"));
+                });
+                ui.end_row();
+                ui.label("Allow zero-width panels");
+                ui.checkbox(&mut app.allow_zero_collapse, "");
+                ui.end_row();
+                ui.label("Right top row height");
+                ui.add(egui::Slider::new(&mut app.right_top_fraction, 0.2..=0.8));
+                ui.end_row();
+                ui.label("Right top row split");
+                ui.add(egui::Slider::new(&mut app.right_top_hsplit_fraction, 0.2..=0.8));
+                ui.end_row();
+                ui.label("Theme");
+                let selected = themes.iter().rev().find(|t| t.matches(&app.theme)).map(|t| t.name.clone());
+                egui::ComboBox::from_id_source("settings_theme")
+                    .selected_text(selected.unwrap_or_else(|| app.theme.clone()))
+                    .show_ui(ui, |ui| {
+                        for theme in themes {
+                            ui.selectable_value(&mut app.theme, theme.id.clone(), &theme.name);
+                        }
+                    });
+                ui.end_row();
+                ui.label("Themes folder");
+                ui.add(egui::TextEdit::singleline(&mut app.themes_dir).hint_text("<config dir>/themes"));
+                ui.end_row();
+                ui.label("Confirm multi-line paste");
+                ui.checkbox(&mut app.confirm_multiline_paste, "");
+                ui.end_row();
+                ui.label("Bookmarks file");
+                ui.add(egui::TextEdit::singleline(&mut app.bookmarks_file).hint_text("<config dir>/bookmarks.toml"));
+                ui.end_row();
+                ui.label("Drop files on");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut app.dnd_routing, DndRouting::Focused, "Focused pane");
+                    ui.radio_value(&mut app.dnd_routing, DndRouting::Pointer, "Pane under pointer");
+                });
+                ui.end_row();
+            });
+        });
+    }
+
+    fn font_section(&mut self, ui: &mut egui::Ui) {
+        let font = &mut self.draft.app.font;
+        egui::CollapsingHeader::new("Font").show(ui, |ui| {
+            egui::Grid::new("settings_font").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Font file");
+                ui.add(egui::TextEdit::singleline(&mut font.family).hint_text("built-in monospace"));
+                ui.end_row();
+                ui.label("Size");
+                ui.add(egui::DragValue::new(&mut font.size).clamp_range(4.0..=96.0).speed(0.5));
+                ui.end_row();
+                ui.label("Line height");
+                ui.add(egui::DragValue::new(&mut font.line_height).clamp_range(0.5..=3.0).speed(0.05));
+                ui.end_row();
+                ui.label("Fallback fonts\n(one per line)");
+                ui.add(egui::TextEdit::multiline(&mut self.fallbacks).desired_rows(2));
+                ui.end_row();
+                ui.label("Zoom scope");
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut font.zoom_scope, ZoomScope::Pane, "Focused pane");
+                    ui.radio_value(&mut font.zoom_scope, ZoomScope::Global, "All panes");
+                });
+                ui.end_row();
+            });
+        });
+    }
+
+    fn tabs_section(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Tabs").show(ui, |ui| {
+            let mut remove = None;
+            let rows = self.draft.tabs.iter_mut().zip(self.patterns.iter_mut()).zip(self.notify_patterns.iter_mut());
+            for (i, ((tab, patterns), notify_patterns)) in rows.enumerate() {
+                egui::CollapsingHeader::new(format!("Pane {}: {}", i + 1, tab.title))
+                    .id_source(("settings_tab", i))
+                    .show(ui, |ui| {
+                        egui::Grid::new(("settings_tab_grid", i)).num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                            ui.label("Title");
+                            ui.text_edit_singleline(&mut tab.title);
+                            ui.end_row();
+                            ui.label("Command");
+                            ui.text_edit_singleline(&mut tab.command);
+                            ui.end_row();
+                            ui.label("Restart on success");
+                            ui.checkbox(&mut tab.auto_restart_on_success, "");
+                            ui.end_row();
+                            ui.label("Success patterns\n(one per line)");
+                            ui.add(egui::TextEdit::multiline(patterns).desired_rows(2));
+                            ui.end_row();
+                            ui.label("Session log");
+                            ui.horizontal(|ui| {
+                                ui.radio_value(&mut tab.log, LogMode::Off, "Off");
+                                ui.radio_value(&mut tab.log, LogMode::Plain, "Plain text");
+                                ui.radio_value(&mut tab.log, LogMode::Raw, "Raw");
+                            });
+                            ui.end_row();
+                            ui.label("Folder drop");
+                            ui.vertical(|ui| {
+                                ui.checkbox(&mut tab.dnd.auto_cd_on_folder_drop, "cd into a dropped folder");
+                                ui.checkbox(&mut tab.dnd.auto_run_on_folder_drop, "Run with a dropped folder");
+                            });
+                            ui.end_row();
+                            ui.label("File drop template");
+                            ui.horizontal(|ui| {
+                                ui.add(egui::TextEdit::singleline(&mut tab.dnd.on_file).hint_text("{path}"));
+                                ui.checkbox(&mut tab.dnd.on_file_enter, "Enter");
+                            });
+                            ui.end_row();
+                            ui.label("Folder drop template");
+                            ui.horizontal(|ui| {
+                                ui.add(egui::TextEdit::singleline(&mut tab.dnd.on_folder).hint_text("cd {path}"));
+                                ui.checkbox(&mut tab.dnd.on_folder_enter, "Enter");
+                            });
+                            ui.end_row();
+                            ui.label("Drop rules");
+                            ui.vertical(|ui| {
+                                for rule in &mut tab.dnd.rules {
+                                    ui.horizontal(|ui| {
+                                        ui.monospace(rule.extensions.join(" "));
+                                        ui.add(egui::TextEdit::singleline(&mut rule.template));
+                                        ui.checkbox(&mut rule.enter, "Enter");
+                                    });
+                                }
+                                ui.weak("Add rules as [[tabs.dnd.rules]] in config.toml");
+                            });
+                            ui.end_row();
+                            ui.label("Drop mode");
+                            ui.horizontal(|ui| {
+                                ui.radio_value(&mut tab.dnd.mode, DropMode::Insert, "Insert");
+                                ui.radio_value(&mut tab.dnd.mode, DropMode::Queue, "Queue, one file at a time");
+                            });
+                            ui.end_row();
+                            if tab.dnd.mode == DropMode::Queue {
+                                ui.label("Next file after");
+                                ui.horizontal(|ui| {
+                                    ui.radio_value(&mut tab.dnd.queue_wait, QueueWait::Exit, "Exit status");
+                                    ui.radio_value(&mut tab.dnd.queue_wait, QueueWait::Pattern, "Success pattern");
+                                });
+                                ui.end_row();
+                            }
+                            ui.label("Notify on");
+                            ui.vertical(|ui| {
+                                ui.checkbox(&mut tab.notify.on_success, "Successful exit");
+                                ui.checkbox(&mut tab.notify.on_failure, "Failed exit");
+                                ui.horizontal(|ui| {
+                                    ui.label("Output after");
+                                    ui.add(egui::DragValue::new(&mut tab.notify.after_silence_secs).clamp_range(0..=86_400).suffix(" s"));
+                                    ui.label("of silence (0 = off)");
+                                });
+                            });
+                            ui.end_row();
+                            ui.label("Notify patterns\n(one per line)");
+                            ui.add(egui::TextEdit::multiline(notify_patterns).desired_rows(2));
+                            ui.end_row();
+                            ui.label("Idle timeout");
+                            ui.horizontal(|ui| {
+                                ui.add(egui::DragValue::new(&mut tab.idle.idle_timeout).clamp_range(0..=86_400).suffix(" s"));
+                                ui.label("without output or input (0 = off)");
+                            });
+                            ui.end_row();
+                            ui.label("Stall pattern");
+                            ui.add(egui::TextEdit::singleline(&mut tab.idle.stall_pattern).hint_text("built-in prompt check"));
+                            ui.end_row();
+                            ui.label("When idle");
+                            ui.horizontal(|ui| {
+                                ui.radio_value(&mut tab.idle.on_idle, IdleAction::Flag, "Flag");
+                                ui.radio_value(&mut tab.idle.on_idle, IdleAction::Respond, "Respond");
+                                ui.radio_value(&mut tab.idle.on_idle, IdleAction::Restart, "Restart");
+                            });
+                            ui.end_row();
+                            if tab.idle.on_idle == IdleAction::Respond {
+                                ui.label("Response");
+                                ui.add(egui::TextEdit::singleline(&mut tab.idle.idle_response).hint_text("typed, then Enter"));
+                                ui.end_row();
+                            }
+                        });
+                        if ui.small_button("Remove tab").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+            }
+            if let Some(i) = remove {
+                self.draft.tabs.remove(i);
+                self.patterns.remove(i);
+                self.notify_patterns.remove(i);
+            }
+            if self.draft.tabs.len() < MAX_TABS && ui.button("+ Add tab").clicked() {
+                self.draft.tabs.push(TabConfig {
+                    title: format!("Terminal {}", self.draft.tabs.len() + 1),
+                    command: "bash".to_string(),
+                    auto_restart_on_success: false,
+                    success_patterns: vec![],
+                    dnd: DndSettings::default(),
+                    log: LogMode::Off,
+                    notify: NotifySettings::default(),
+                    idle: IdleSettings::default(),
+                });
+                self.patterns.push(String::new());
+                self.notify_patterns.push(String::new());
+            }
+        });
+    }
+
+    fn keybindings_section(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Key bindings").show(ui, |ui| {
+            ui.label("Entries are merged over the built-in defaults; use the action \"none\" to remove one.");
+            let mut remove = None;
+            egui::Grid::new("settings_keys").num_columns(4).spacing([8.0, 4.0]).show(ui, |ui| {
+                ui.strong("Chord");
+                ui.strong("Action / text");
+                ui.strong("Send text");
+                ui.end_row();
+                for (i, row) in self.bindings.iter_mut().enumerate() {
+                    ui.add(egui::TextEdit::singleline(&mut row.chord).hint_text("Ctrl+Shift+K").desired_width(120.0));
+                    let hint = if row.send { "ls -la\\r" } else { "clear" };
+                    ui.add(egui::TextEdit::singleline(&mut row.value).hint_text(hint).desired_width(180.0));
+                    ui.checkbox(&mut row.send, "");
+                    if ui.small_button("🗑").clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(i) = remove {
+                self.bindings.remove(i);
+            }
+            if ui.button("+ Add binding").clicked() {
+                self.bindings.push(BindingRow { chord: String::new(), value: String::new(), send: false });
+            }
+        });
+    }
+
+    fn buttons_section(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Action buttons").show(ui, |ui| {
+            let mut remove = None;
+            for (i, (button, keys)) in self.draft.buttons.iter_mut().zip(self.button_keys.iter_mut()).enumerate() {
+                egui::CollapsingHeader::new(format!("{} {}", button.icon, button.label).trim().to_string())
+                    .id_source(("settings_button", i))
+                    .show(ui, |ui| {
+                        egui::Grid::new(("settings_button_grid", i)).num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                            for (label, value, hint) in [
+                                ("Label", &mut button.label, ""),
+                                ("Icon", &mut button.icon, "🔧"),
+                                ("Color", &mut button.color, "blue or #89b4fa"),
+                                ("Tooltip", &mut button.tooltip, ""),
+                                ("Action", &mut button.action, "restart_all"),
+                                ("Command", &mut button.command, "ls -la"),
+                                ("Target", &mut button.target, "focused, new or a tab title"),
+                            ] {
+                                ui.label(label);
+                                ui.add(egui::TextEdit::singleline(value).hint_text(hint));
+                                ui.end_row();
+                            }
+                            ui.label("Keys");
+                            ui.add(egui::TextEdit::singleline(keys).hint_text("\\x03"));
+                            ui.end_row();
+                            ui.label("Confirm first");
+                            ui.checkbox(&mut button.confirm, "");
+                            ui.end_row();
+                        });
+                        if ui.small_button("Remove button").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+            }
+            if let Some(i) = remove {
+                self.draft.buttons.remove(i);
+                self.button_keys.remove(i);
+            }
+            if ui.button("+ Add button").clicked() {
+                self.draft.buttons.push(ButtonConfig { label: "New button".to_string(), ..ButtonConfig::default() });
+                self.button_keys.push(String::new());
+            }
+        });
+    }
+
+    fn scripts_section(&mut self, ui: &mut egui::Ui) {
+        let scripts = &mut self.draft.scripts;
+        egui::CollapsingHeader::new("Scripts").show(ui, |ui| {
+            egui::Grid::new("settings_scripts").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Script folders\n(one per line)");
+                ui.add(egui::TextEdit::multiline(&mut self.script_dirs).desired_rows(2));
+                ui.end_row();
+                ui.label("Scan depth");
+                ui.add(egui::DragValue::new(&mut scripts.max_depth).clamp_range(0..=10));
+                ui.end_row();
+                ui.label("Recent scripts");
+                ui.add(egui::DragValue::new(&mut scripts.recent_limit).clamp_range(0..=50));
+                ui.end_row();
+            });
+        });
+    }
+
+    fn analytics_section(&mut self, ui: &mut egui::Ui) {
+        let analytics = &mut self.draft.analytics;
+        egui::CollapsingHeader::new("Analytics").show(ui, |ui| {
+            egui::Grid::new("settings_analytics").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Record activity");
+                ui.checkbox(&mut analytics.enabled, "");
+                ui.end_row();
+                ui.label("Log file");
+                ui.add(egui::TextEdit::singleline(&mut analytics.log_file).hint_text("<config dir>/analytics.csv"));
+                ui.end_row();
+                ui.label("Keep days (0 = all)");
+                ui.add(egui::DragValue::new(&mut analytics.retention_days).clamp_range(0..=3650));
+                ui.end_row();
+            });
+        });
+    }
+
+    fn logging_section(&mut self, ui: &mut egui::Ui) {
+        let logging = &mut self.draft.logging;
+        egui::CollapsingHeader::new("Session logs").show(ui, |ui| {
+            ui.label("Panes log their output when their \"Session log\" is not off.");
+            egui::Grid::new("settings_logging").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Log folder");
+                ui.add(egui::TextEdit::singleline(&mut logging.dir).hint_text("<config dir>/logs"));
+                ui.end_row();
+                ui.label("New file after");
+                ui.add(egui::DragValue::new(&mut logging.max_file_mb).clamp_range(1..=4096).suffix(" MB"));
+                ui.end_row();
+                ui.label("Delete logs after");
+                ui.add(egui::DragValue::new(&mut logging.max_age_days).clamp_range(0..=3650).suffix(" days"))
+                    .on_hover_text("0 keeps logs forever");
+                ui.end_row();
+            });
+        });
+    }
+
+    fn profiles_section(&mut self, ui: &mut egui::Ui) {
+        let names = config::list_profiles(&self.draft);
+        let active = config::active_profile();
+        egui::CollapsingHeader::new("Profiles").show(ui, |ui| {
+            ui.label("Switch profiles with the Profile menu next to Actions. Profiles are edited in config.toml or in the profiles folder.");
+            if names.is_empty() {
+                ui.weak("No profiles");
+            }
+            for name in &names {
+                let source = if self.draft.profiles.contains_key(name) {
+                    format!("[profiles.{}] in config.toml", name)
+                } else {
+                    format!("profiles/{}.toml", name)
+                };
+                let marker = if active.as_deref() == Some(name.as_str()) { "● " } else { "" };
+                ui.label(format!("{}{} — {}", marker, name, source));
+            }
+            if let Some(name) = &active {
+                ui.weak(format!("The settings above include profile {:?}; values it sets cannot be saved from here.", name));
+            }
+        });
+    }
+
+    fn notifications_section(&mut self, ui: &mut egui::Ui) {
+        let notifications = &mut self.draft.notifications;
+        egui::CollapsingHeader::new("Notifications").show(ui, |ui| {
+            ui.label("Which events notify is set per tab under Tabs.");
+            egui::Grid::new("settings_notifications").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Toasts");
+                ui.checkbox(&mut notifications.toasts, "");
+                ui.end_row();
+                ui.label("Toast duration");
+                ui.add(egui::DragValue::new(&mut notifications.toast_seconds).clamp_range(1..=600).suffix(" s"));
+                ui.end_row();
+                ui.label("Desktop notifications");
+                ui.checkbox(&mut notifications.desktop, "");
+                ui.end_row();
+                ui.label("Notifier command");
+                ui.add(egui::TextEdit::singleline(&mut notifications.command).hint_text("notify-send {title} {body}"))
+                    .on_hover_text("Replaces the native notifier; {title}, {body} and {tab} are shell-quoted");
+                ui.end_row();
+                ui.label("Focused pane");
+                ui.checkbox(&mut notifications.focused_pane, "Notify for the pane in focus too");
+                ui.end_row();
+            });
+        });
+    }
+
+    fn control_section(&mut self, ui: &mut egui::Ui) {
+        let control = &mut self.draft.control;
+        egui::CollapsingHeader::new("Control socket").show(ui, |ui| {
+            ui.label("Lets scripts list, drive and watch the panes with \"audio-toolkit-shell ctl\".");
+            egui::Grid::new("settings_control").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                ui.label("Enabled");
+                ui.checkbox(&mut control.enabled, "");
+                ui.end_row();
+                ui.label("Socket");
+                ui.add(egui::TextEdit::singleline(&mut control.socket).hint_text("<config dir>/control.sock"));
+                ui.end_row();
+                ui.label("Scrollback");
+                ui.add(egui::DragValue::new(&mut control.scrollback_lines).clamp_range(0..=100_000).suffix(" lines"))
+                    .on_hover_text("Plain-text output kept per pane for \"ctl scrollback\"");
+                ui.end_row();
+            });
+        });
+    }
+}
+
+/// Checks a configuration before it is saved
+///
+/// # Returns
+///
+/// One message per problem; empty when the configuration can be saved. Each
+/// profile is checked too, reporting only the problems it adds.
+pub fn validate(config: &AppConfig, themes: &[Theme]) -> Vec<String> {
+    let mut problems = validate_settings(config, themes);
+    for name in config::list_profiles(config) {
+        match config::apply_profile(config, &name) {
+            Ok(profile) => {
+                let added: Vec<String> = validate_settings(&profile, themes)
+                    .into_iter()
+                    .filter(|p| !problems.contains(p))
+                    .collect();
+                problems.extend(added.into_iter().map(|p| format!("Profile {}: {}", name, p)));
+            }
+            Err(e) => problems.push(format!("Profile {}: {}", name, e)),
+        }
+    }
+    problems
+}
+
+fn validate_settings(config: &AppConfig, themes: &[Theme]) -> Vec<String> {
+    let mut problems = Vec::new();
+    let app = &config.app;
+    if app.name.trim().is_empty() {
+        problems.push("Window title must not be empty".to_string());
+    }
+    if app.window_width <= 0.0 || app.window_height <= 0.0 {
+        problems.push("Window size must be positive".to_string());
+    }
+    for (name, value) in [("right_top_fraction", app.right_top_fraction), ("right_top_hsplit_fraction", app.right_top_hsplit_fraction)] {
+        if !(0.0..=1.0).contains(&value) {
+            problems.push(format!("{} must be between 0 and 1", name));
+        }
+    }
+    if !themes.iter().any(|t| t.matches(&app.theme)) {
+        problems.push(format!("Unknown theme {:?}", app.theme));
+    }
+    let font = &app.font;
+    if !(4.0..=96.0).contains(&font.size) {
+        problems.push("Font size must be between 4 and 96".to_string());
+    }
+    if !(0.5..=3.0).contains(&font.line_height) {
+        problems.push("Line height must be between 0.5 and 3".to_string());
+    }
+    for path in std::iter::once(&font.family).chain(&font.fallbacks) {
+        let path = path.trim();
+        if !path.is_empty() && !Path::new(path).is_file() {
+            problems.push(format!("Font file not found: {}", path));
+        }
+    }
+
+    for dir in &config.scripts.dirs {
+        if !Path::new(dir).is_dir() {
+            problems.push(format!("Script folder not found: {}", dir));
+        }
+    }
+
+    for (i, tab) in config.tabs.iter().enumerate() {
+        if tab.title.trim().is_empty() {
+            problems.push(format!("Pane {}: title must not be empty", i + 1));
+        }
+        if tab.command.trim().is_empty() {
+            problems.push(format!("Pane {}: command must not be empty", i + 1));
+        }
+        let rules = tab.dnd.rules.iter().map(|rule| rule.template.as_str());
+        for template in [tab.dnd.on_file.as_str(), tab.dnd.on_folder.as_str()].into_iter().chain(rules) {
+            let unknown = dnd::unknown_placeholders(template);
+            if !unknown.is_empty() {
+                problems.push(format!("Pane {}: unknown drop placeholder {{{}}}", i + 1, unknown.join("}, {")));
+            }
+        }
+        for rule in &tab.dnd.rules {
+            if rule.extensions.iter().all(|e| e.trim().trim_start_matches('.').is_empty()) || rule.template.trim().is_empty() {
+                problems.push(format!("Pane {}: every drop rule needs extensions and a template", i + 1));
+            }
+        }
+        let waits_for_pattern = tab.dnd.mode == DropMode::Queue && tab.dnd.queue_wait == QueueWait::Pattern;
+        if waits_for_pattern && tab.success_patterns.iter().all(|p| p.is_empty()) {
+            problems.push(format!("Pane {}: a drop queue waiting for a pattern needs success patterns", i + 1));
+        }
+        if tab.idle.on_idle != IdleAction::Flag && tab.idle.idle_timeout == 0 {
+            problems.push(format!("Pane {}: on_idle needs an idle_timeout", i + 1));
+        }
+        if tab.idle.on_idle == IdleAction::Respond && tab.idle.idle_response.is_empty() {
+            problems.push(format!("Pane {}: on_idle = \"respond\" needs an idle_response", i + 1));
+        }
+    }
+
+    for (chord, binding) in &config.keybindings {
+        if let Err(e) = Chord::parse(chord) {
+            problems.push(format!("Key binding {:?}: {}", chord, e));
+        }
+        if let KeyBinding::Action(name) = binding {
+            if let Err(e) = Action::parse(name) {
+                problems.push(format!("Key binding {:?}: {}", chord, e));
+            }
+        }
+    }
+
+    for button in &config.buttons {
+        let name = format!("{} {}", button.icon, button.label).trim().to_string();
+        if name.is_empty() {
+            problems.push("Every button needs a label or an icon".to_string());
+        }
+        if !button.action.trim().is_empty() {
+            if let Err(e) = Action::parse(&button.action) {
+                problems.push(format!("Button {:?}: {}", name, e));
+            }
+        }
+        let color = button.color.trim();
+        if !color.is_empty()
+            && Theme::frappe().palette.role(&color.to_ascii_lowercase()).is_none()
+            && theme::parse_hex_color(color).is_err()
+        {
+            problems.push(format!("Button {:?}: unknown color {:?}", name, color));
+        }
+        let target = button.target.trim();
+        let known_target = target.is_empty()
+            || target.eq_ignore_ascii_case("focused")
+            || target.eq_ignore_ascii_case("new")
+            || config.tabs.iter().any(|t| t.title.eq_ignore_ascii_case(target));
+        if !known_target {
+            problems.push(format!("Button {:?}: no tab titled {:?}", name, target));
+        }
+    }
+
+    let notifications = &config.notifications;
+    if !(1..=600).contains(&notifications.toast_seconds) {
+        problems.push("Toast duration must be between 1 and 600 seconds".to_string());
+    }
+    let unknown = notify::unknown_placeholders(&notifications.command);
+    if !unknown.is_empty() {
+        problems.push(format!("Notifier command: unknown placeholder {{{}}}", unknown.join("}, {")));
+    }
+    if notifications.desktop && notifications.command.trim().is_empty() && !notify::has_native_notifier() {
+        problems.push("Desktop notifications need a notifier command on this platform".to_string());
+    }
+
+    // sun_path holds 104 bytes on macOS and 108 on Linux, including the NUL
+    let socket = config.control.socket_path();
+    if config.control.enabled && socket.as_os_str().len() > 103 {
+        problems.push(format!("Control socket path is too long ({} bytes, at most 103)", socket.as_os_str().len()));
+    }
+    problems
+}
+
+/// Shows control characters as `\r`, `\n`, `\t`, `\e` or `\xNN` (and `\` as `\\`)
+pub fn escape_control(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            '\r' => out.push_str("\\r"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\x1b' => out.push_str("\\e"),
+            c if c.is_control() && (c as u32) < 0x80 => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reverses [`escape_control`]; unknown escapes are kept as typed
+pub fn unescape_control(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => out.push('\\'),
+            Some('r') => out.push('\r'),
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('e') => out.push('\x1b'),
+            Some('x') => {
+                let hex: String = chars.clone().take(2).collect();
+                match u8::from_str_radix(&hex, 16) {
+                    Ok(byte) if hex.len() == 2 && byte < 0x80 => {
+                        out.push(byte as char);
+                        chars.nth(1);
+                    }
+                    _ => out.push_str("\\x"),
+                }
+            }
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_config;
+
+    #[test]
+    fn test_escape_round_trip() {
+        let text = "ls -la\r\x1b[A\x03 C:\\tmp";
+        let escaped = escape_control(text);
+        assert_eq!(escaped, "ls -la\\r\\e[A\\x03 C:\\\\tmp");
+        assert_eq!(unescape_control(&escaped), text);
+        assert_eq!(unescape_control("\\q \\xZZ"), "\\q \\xZZ");
+    }
+
+    #[test]
+    fn test_validate() {
+        let themes = Theme::builtins();
+        let config = default_config();
+        assert!(validate(&config, &themes).is_empty());
+
+        let mut broken = config.clone();
+        broken.app.theme = "nope".to_string();
+        broken.tabs[1].command = " ".to_string();
+        broken.keybindings.insert("Ctrl+Nope".to_string(), KeyBinding::Action("copy".to_string()));
+        broken.buttons[0].action = "explode".to_string();
+        broken.buttons[1].color = "#12".to_string();
+        broken.buttons[2].target = "Missing".to_string();
+        broken.tabs[0].dnd.on_file = "convert {file}".to_string();
+        broken.tabs[0].dnd.rules.push(crate::config::DropRule::default());
+        broken.tabs[2].dnd.mode = DropMode::Queue;
+        broken.tabs[2].dnd.queue_wait = QueueWait::Pattern;
+        broken.control.enabled = true;
+        broken.control.socket = format!("/tmp/{}.sock", "x".repeat(100));
+        broken.notifications.toast_seconds = 0;
+        broken.notifications.command = "notify {title} {message}".to_string();
+        broken.tabs[3].idle.on_idle = IdleAction::Restart;
+        assert_eq!(validate(&broken, &themes).len(), 13);
+
+        // Profiles report only what they add to the base problems
+        let profile = |text: &str| text.parse::<toml::Table>().unwrap();
+        broken.profiles.insert("same".to_string(), profile("[app]\ntheme = \"nope\""));
+        broken.profiles.insert("video".to_string(), profile("[app]\nwindow_width = 0.0"));
+        broken.profiles.insert("typo".to_string(), profile("[app]\nwindow_width = \"wide\""));
+        let problems = validate(&broken, &themes);
+        assert_eq!(problems.len(), 15);
+        assert!(problems.contains(&"Profile video: Window size must be positive".to_string()));
+        assert!(problems.iter().any(|p| p.starts_with("Profile typo: ")));
+    }
+
+    #[test]
+    fn test_editor_builds_edited_config() {
+        let mut config = default_config();
+        config.keybindings.insert("F5".to_string(), KeyBinding::Send { send: "ls\r".to_string() });
+        let mut editor = SettingsEditor::new();
+        editor.open_with(&config);
+        assert_eq!(editor.build(), config);
+
+        editor.patterns[0] = "Done\n\n  Finished  \n".to_string();
+        editor.bindings[0].value = "pwd\\r".to_string();
+        let built = editor.build();
+        assert_eq!(built.tabs[0].success_patterns, vec!["Done", "Finished"]);
+        assert_eq!(built.keybindings["F5"], KeyBinding::Send { send: "pwd\r".to_string() });
+    }
+}
@@ -27,8 +27,10 @@
 
 use eframe::egui;
 use unicode_width::UnicodeWidthChar;
-use crate::theme::{CatppuccinTheme, ansi_256_to_rgb};
+use crate::mouse::{MouseEncoding, MouseTracking};
+use crate::theme::CellColor;
 use std::fs::OpenOptions;
+use std::path::PathBuf;
 use std::io::Write;
 
 /// Represents different states of ANSI parameters during parsing
@@ -58,6 +60,10 @@ enum AnsiState {
     Escape,
     /// CSI sequence detected (ESC[), accumulating parameters
     CsiSequence,
+    /// OSC string detected (ESC]), ignored until BEL or ST
+    OscString,
+    /// ESC inside an OSC string, possibly the start of ST (ESC \)
+    OscEscape,
     /// Complete sequence ready for atomic processing
     SequenceComplete,
 }
@@ -76,7 +82,7 @@ enum AnsiState {
 #[derive(Clone)]
 pub struct TerminalCell {
     pub character: char,
-    pub color: egui::Color32,
+    pub color: CellColor,
     pub bold: bool,
 }
 
@@ -84,12 +90,35 @@ impl Default for TerminalCell {
     fn default() -> Self {
         Self {
             character: ' ',
-            color: CatppuccinTheme::FRAPPE.text,
+            color: CellColor::Default,
             bold: false,
         }
     }
 }
 
+/// One step of the parsed output stream, recorded while tracing is enabled
+///
+/// Used by the developer tools inspector to show what the emulator made of the
+/// bytes a program wrote.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceEvent {
+    /// Printable text written to the buffer
+    Text(String),
+    /// C0 control character and whether the emulator acts on it
+    Control(char, bool),
+    /// CSI sequence body (after `ESC[`) and whether the emulator acts on it
+    Csi(String, bool),
+    /// OSC payload (after `ESC]`), swallowed without effect
+    Osc(String),
+    /// Two-character escape (`ESC c`) the emulator handles
+    Esc(char),
+    /// Malformed or unsupported escape, printed as text
+    Invalid(String),
+}
+
+/// Longest OSC payload kept for the trace
+const MAX_OSC_TRACE: usize = 512;
+
 /// Terminal emulator that handles ANSI sequences and character rendering
 /// 
 /// This struct manages a 2D buffer of terminal cells and processes ANSI escape sequences
@@ -109,7 +138,7 @@ pub struct TerminalEmulator {
     cursor_col: usize,
     rows: usize,
     cols: usize,
-    current_color: egui::Color32,
+    current_color: CellColor,
     bold: bool,
     /// Flag to track if cursor was recently positioned, indicating potential need for clearing
     cursor_recently_positioned: bool,
@@ -121,6 +150,16 @@ pub struct TerminalEmulator {
     ansi_state: AnsiState,
     /// Debug logging enabled flag
     debug_logging: bool,
+    /// File the debug log is written to
+    debug_log_path: PathBuf,
+    /// Mouse events requested by the application (DEC 1000/1002/1003)
+    mouse_tracking: MouseTracking,
+    /// Mouse report encoding requested by the application (DEC 1006/1015)
+    mouse_encoding: MouseEncoding,
+    /// Whether pastes should be wrapped in `ESC[200~`/`ESC[201~` (DEC 2004)
+    bracketed_paste: bool,
+    /// Parsed stream recorded for the inspector; `None` when tracing is off
+    trace: Option<Vec<TraceEvent>>,
 }
 
 impl TerminalEmulator {
@@ -147,14 +186,140 @@ impl TerminalEmulator {
             cursor_col: 0,
             rows,
             cols,
-            current_color: CatppuccinTheme::FRAPPE.text,
+            current_color: CellColor::Default,
             bold: false,
             cursor_recently_positioned: false,
             wrap_pending: false,
             ansi_sequence_buffer: String::new(),
             ansi_state: AnsiState::Normal,
             debug_logging: false,
+            debug_log_path: PathBuf::from("terminal_debug.log"),
+            mouse_tracking: MouseTracking::Off,
+            mouse_encoding: MouseEncoding::X10,
+            bracketed_paste: false,
+            trace: None,
+        }
+    }
+
+    /// Mouse reporting mode currently requested by the application
+    pub fn mouse_mode(&self) -> (MouseTracking, MouseEncoding) {
+        (self.mouse_tracking, self.mouse_encoding)
+    }
+
+    /// Visible screen contents as text, one line per row, trailing blanks trimmed
+    pub fn screen_text(&self) -> String {
+        let mut lines: Vec<String> = self
+            .buffer
+            .iter()
+            .map(|row| {
+                let line: String = row.iter().map(|c| c.character).filter(|&c| c != '\0').collect();
+                line.trim_end().to_string()
+            })
+            .collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines.join("\n")
+    }
+
+    /// Text of the cursor's row, trailing blanks trimmed
+    pub fn cursor_line(&self) -> String {
+        let line: String = self.buffer.get(self.cursor_row).into_iter().flatten().map(|c| c.character).filter(|&c| c != '\0').collect();
+        line.trim_end().to_string()
+    }
+
+    /// Finds `query` on the visible screen (case-insensitive)
+    ///
+    /// # Returns
+    ///
+    /// Matches as `(row, start_col, width)` in cells, so wide glyphs are measured correctly
+    pub fn find(&self, query: &str) -> Vec<(usize, usize, usize)> {
+        let needle: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        let mut matches = Vec::new();
+        for (r, row) in self.buffer.iter().enumerate() {
+            // Characters with the column each one starts at (placeholders skipped)
+            let cells: Vec<(usize, char)> = row
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.character != '\0')
+                .map(|(col, c)| (col, c.character.to_lowercase().next().unwrap_or(c.character)))
+                .collect();
+            let mut i = 0;
+            while i + needle.len() <= cells.len() {
+                if cells[i..i + needle.len()].iter().map(|(_, ch)| *ch).eq(needle.iter().copied()) {
+                    let start = cells[i].0;
+                    let end = cells.get(i + needle.len()).map(|(col, _)| *col).unwrap_or(row.len());
+                    matches.push((r, start, end - start));
+                    i += needle.len();
+                } else {
+                    i += 1;
+                }
+            }
         }
+        matches
+    }
+
+    /// Whether the application enabled bracketed paste (DEC 2004)
+    pub fn bracketed_paste(&self) -> bool {
+        self.bracketed_paste
+    }
+
+    /// Cursor position as `(row, col)`, 0-based
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.cursor_row, self.cursor_col)
+    }
+
+    /// Current SGR state: foreground color and bold
+    pub fn sgr(&self) -> (CellColor, bool) {
+        (self.current_color, self.bold)
+    }
+
+    /// Buffer size as `(rows, cols)`
+    pub fn size(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Whether the next printable character wraps to the next line first
+    pub fn wrap_pending(&self) -> bool {
+        self.wrap_pending
+    }
+
+    /// Starts or stops recording the parsed stream; stopping discards it
+    pub fn set_tracing(&mut self, on: bool) {
+        if on != self.trace.is_some() {
+            self.trace = on.then(Vec::new);
+        }
+    }
+
+    /// Takes the events recorded since the last call
+    pub fn take_trace(&mut self) -> Vec<TraceEvent> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Records a trace event, merging consecutive text
+    fn trace(&mut self, event: TraceEvent) {
+        let Some(trace) = self.trace.as_mut() else { return };
+        if let (TraceEvent::Text(new), Some(TraceEvent::Text(last))) = (&event, trace.last_mut()) {
+            last.push_str(new);
+            return;
+        }
+        trace.push(event);
+    }
+
+    /// Full reset (RIS, `ESC c`): blank screen, cursor home, default colors, all modes off
+    ///
+    /// Tracing and debug logging survive the reset.
+    pub fn reset(&mut self) {
+        let trace = self.trace.take();
+        *self = Self {
+            trace,
+            debug_logging: self.debug_logging,
+            debug_log_path: self.debug_log_path.clone(),
+            ..Self::new(self.rows, self.cols)
+        };
     }
 
     /// Clears the entire terminal screen and resets cursor to top-left
@@ -171,6 +336,41 @@ impl TerminalEmulator {
         self.cursor_col = 0;
     }
 
+    /// Resizes the terminal buffer to the given dimensions
+    ///
+    /// Shrinking drops rows from the top so the cursor line stays visible; growing
+    /// appends blank rows at the bottom. Each row is truncated or padded to the new
+    /// width and the cursor is clamped to the new bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - New number of rows (ignored if zero)
+    /// * `cols` - New number of columns (ignored if zero)
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        if rows == 0 || cols == 0 || (rows == self.rows && cols == self.cols) {
+            return;
+        }
+
+        if rows < self.buffer.len() {
+            // Keep the cursor row on screen by scrolling excess rows off the top
+            let overflow = (self.cursor_row + 1).saturating_sub(rows);
+            self.buffer.drain(0..overflow);
+            self.buffer.truncate(rows);
+            self.cursor_row = self.cursor_row.saturating_sub(overflow);
+        }
+        while self.buffer.len() < rows {
+            self.buffer.push(vec![TerminalCell::default(); cols]);
+        }
+        for row in &mut self.buffer {
+            row.resize(cols, TerminalCell::default());
+        }
+
+        self.rows = rows;
+        self.cols = cols;
+        self.wrap_pending = false;
+        self.validate_cursor_position();
+    }
+
     /// Moves the cursor to the specified position
     /// 
     /// Positions are clamped to valid buffer bounds to prevent out-of-bounds access.
@@ -214,6 +414,7 @@ impl TerminalEmulator {
     /// * `row` - Target row (0-based, clamped to buffer height)
     /// * `col` - Target column (0-based, clamped to buffer width)
     /// * `clear_length` - Number of characters to clear from the cursor position
+    #[allow(dead_code)]
     pub fn move_cursor_and_clear(&mut self, row: usize, col: usize, _clear_length: usize) {
         // First move the cursor to the target position
         self.move_cursor(row, col);
@@ -259,7 +460,7 @@ impl TerminalEmulator {
     /// Used to preserve UI borders (e.g., the right-most vertical line) during auto-clears
     fn is_line_border_char(ch: char) -> bool {
         // Box Drawing block U+2500..U+257F covers single/heavy lines and junctions
-        (ch >= '\u{2500}' && ch <= '\u{257F}') || ch == '|'
+        ('\u{2500}'..='\u{257F}').contains(&ch) || ch == '|'
     }
 
     /// Clears to end-of-line but preserves the right-most border cell if present
@@ -291,6 +492,7 @@ impl TerminalEmulator {
     /// 
     /// * `ch` - The character to write
     /// * `clear_ahead` - Number of additional characters to clear ahead
+    #[allow(dead_code)]
     fn write_char_with_clearing(&mut self, ch: char, clear_ahead: usize) {
         // Clear the area ahead before writing the character
         if clear_ahead > 0 {
@@ -447,7 +649,7 @@ impl TerminalEmulator {
         if self.cursor_row < self.rows && self.cursor_col < self.cols {
             // Determine color: use white/gray for box drawing characters, current color for text
             let char_color = if self.is_box_drawing_char(ch) {
-                egui::Color32::from_rgb(128, 128, 128) // Gray for box drawing
+                CellColor::Rgb(egui::Color32::from_rgb(128, 128, 128)) // Gray for box drawing
             } else {
                 self.current_color
             };
@@ -470,7 +672,7 @@ impl TerminalEmulator {
                     if let Some(cell) = row.get_mut(self.cursor_col + 1) {
                         *cell = TerminalCell {
                             character: '\0',
-                            color: egui::Color32::TRANSPARENT,
+                            color: CellColor::Rgb(egui::Color32::TRANSPARENT),
                             bold: false,
                         };
                     }
@@ -589,14 +791,18 @@ impl TerminalEmulator {
                     self.ansi_state = AnsiState::Escape;
                     self.ansi_sequence_buffer.clear();
                 } else if ch == '\n' {
+                    self.trace(TraceEvent::Control(ch, true));
                     self.handle_newline();
                 } else if ch == '\r' {
+                    self.trace(TraceEvent::Control(ch, true));
                     self.handle_carriage_return();
                 } else if ch == '\t' {
+                    self.trace(TraceEvent::Control(ch, true));
                     // Handle tab - move to next tab stop (every 8 characters)
                     let next_tab = ((self.cursor_col / 8) + 1) * 8;
                     self.cursor_col = next_tab.min(self.cols - 1);
                 } else if ch == '\u{0008}' {
+                    self.trace(TraceEvent::Control(ch, true));
                     // ASCII Backspace: move cursor one position left without erasing
                     // Many shells echo backspace as "\x08 \x08" (BS, space, BS)
                     // so this must move left to make deletion sequences render correctly.
@@ -606,7 +812,11 @@ impl TerminalEmulator {
                     self.validate_cursor_position();
                 } else if ch.is_control() {
                     // Skip other control characters
+                    self.trace(TraceEvent::Control(ch, false));
                 } else {
+                    if self.trace.is_some() {
+                        self.trace(TraceEvent::Text(ch.to_string()));
+                    }
                     self.write_char(ch);
                 }
             }
@@ -615,8 +825,16 @@ impl TerminalEmulator {
                     // CSI sequence (Control Sequence Introducer)
                     self.ansi_state = AnsiState::CsiSequence;
                     self.ansi_sequence_buffer.clear();
+                } else if ch == ']' {
+                    // OSC (titles, shell integration marks) - not rendered
+                    self.ansi_state = AnsiState::OscString;
+                } else if ch == 'c' {
+                    // RIS: full reset
+                    self.trace(TraceEvent::Esc(ch));
+                    self.reset();
                 } else {
                     // Other escape sequences - treat as normal character for now
+                    self.trace(TraceEvent::Invalid(format!("\u{1b}{}", ch)));
                     self.ansi_state = AnsiState::Normal;
                     self.write_char('\u{1b}');
                     self.write_char(ch);
@@ -633,6 +851,7 @@ impl TerminalEmulator {
                     self.ansi_sequence_buffer.push(ch);
                 } else {
                     // Invalid character - abort sequence and treat as normal text
+                    self.trace(TraceEvent::Invalid(format!("\u{1b}[{}{}", self.ansi_sequence_buffer, ch)));
                     self.ansi_state = AnsiState::Normal;
                     self.write_char('\u{1b}');
                     self.write_char('[');
@@ -645,6 +864,26 @@ impl TerminalEmulator {
                     self.ansi_sequence_buffer.clear();
                 }
             }
+            AnsiState::OscString => {
+                if ch == '\u{07}' {
+                    self.ansi_state = AnsiState::Normal;
+                    self.trace_osc();
+                } else if ch == '\u{1b}' {
+                    self.ansi_state = AnsiState::OscEscape;
+                } else if self.trace.is_some() && self.ansi_sequence_buffer.len() < MAX_OSC_TRACE {
+                    self.ansi_sequence_buffer.push(ch);
+                }
+            }
+            AnsiState::OscEscape => {
+                self.trace_osc();
+                if ch == '\\' {
+                    self.ansi_state = AnsiState::Normal;
+                } else {
+                    // Unterminated OSC; the ESC starts a new sequence
+                    self.ansi_state = AnsiState::Escape;
+                    self.process_char_atomic(ch);
+                }
+            }
             AnsiState::SequenceComplete => {
                 // This state should not be reached as we immediately process and reset
                 self.ansi_state = AnsiState::Normal;
@@ -653,6 +892,12 @@ impl TerminalEmulator {
         }
     }
 
+    /// Records the accumulated OSC payload and clears it
+    fn trace_osc(&mut self) {
+        let payload = std::mem::take(&mut self.ansi_sequence_buffer);
+        self.trace(TraceEvent::Osc(payload));
+    }
+
     /// Processes a complete ANSI sequence atomically
     /// 
     /// This method handles complete ANSI sequences as single atomic operations,
@@ -669,6 +914,10 @@ impl TerminalEmulator {
             
             // Debug log the complete sequence
             self.debug_log(&format!("ANSI_SEQUENCE: '\\x1b[{}'", sequence));
+            if self.trace.is_some() {
+                let supported = csi_support(&sequence).is_ok();
+                self.trace(TraceEvent::Csi(sequence.clone(), supported));
+            }
             
             // Log buffer state before processing
             self.debug_log_buffer_state("BEFORE_ANSI", None, 0, 20);
@@ -692,6 +941,7 @@ impl TerminalEmulator {
     /// # Arguments
     /// 
     /// * `text` - The text to write atomically
+    #[allow(dead_code)]
     pub fn write_text_atomic(&mut self, text: &str) {
         if text.is_empty() {
             return;
@@ -714,6 +964,7 @@ impl TerminalEmulator {
     /// This method enables internal debugging that writes to a file instead of stdout
     /// to avoid interfering with terminal layout. Useful for tracking ANSI sequences
     /// and cursor movements that might cause text contamination.
+    #[allow(dead_code)]
     pub fn enable_debug_logging(&mut self) {
         self.debug_logging = true;
         // Clear the debug log file first
@@ -721,7 +972,7 @@ impl TerminalEmulator {
             .create(true)
             .write(true)
             .truncate(true)
-            .open("terminal_debug.log")
+            .open(&self.debug_log_path)
         {
             let _ = writeln!(file, "=== Terminal Debug Logging Session Started ===");
         }
@@ -729,6 +980,7 @@ impl TerminalEmulator {
     }
 
     /// Disables debug logging
+    #[allow(dead_code)]
     pub fn disable_debug_logging(&mut self) {
         if self.debug_logging {
             self.debug_log("=== Terminal Debug Logging Disabled ===");
@@ -753,7 +1005,7 @@ impl TerminalEmulator {
         if let Ok(mut file) = OpenOptions::new()
             .create(true)
             .append(true)
-            .open("terminal_debug.log")
+            .open(&self.debug_log_path)
         {
             let timestamp = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -763,10 +1015,9 @@ impl TerminalEmulator {
             let cursor_info = format!("cursor:({},{})", self.cursor_row, self.cursor_col);
             let state_info = format!("state:{:?}", self.ansi_state);
             
-            if let Err(_) = writeln!(file, "[{}] {} | {} | {}", 
-                timestamp, cursor_info, state_info, message) {
-                // Silently ignore write errors to avoid disrupting terminal operation
-            }
+            // Silently ignore write errors to avoid disrupting terminal operation
+            let _ = writeln!(file, "[{}] {} | {} | {}",
+                timestamp, cursor_info, state_info, message);
         }
     }
 
@@ -820,16 +1071,23 @@ impl TerminalEmulator {
             None => return, // Empty sequence, nothing to do
         };
         
-        // Safe parameter parsing with bounds checking
-        if sequence.len() == 0 {
-            return;
-        }
-        
         let param_str = if sequence.len() > 1 {
             &sequence[..sequence.len() - 1]
         } else {
             ""
         };
+
+        // DEC private modes: CSI ? Pm h (set) / CSI ? Pm l (reset)
+        if let Some(private) = param_str.strip_prefix('?') {
+            if cmd == 'h' || cmd == 'l' {
+                for param in self.parse_ansi_parameters(private) {
+                    if let AnsiParameter::Value(mode) = param {
+                        self.set_private_mode(mode, cmd == 'h');
+                    }
+                }
+            }
+            return;
+        }
         
         // Enhanced parameter parsing with proper edge case handling
         let params = self.parse_ansi_parameters(param_str);
@@ -913,25 +1171,23 @@ impl TerminalEmulator {
                 self.validate_cursor_position();
                 
                 match mode {
-                    0 => {
+                    0 if self.cursor_row < self.rows => {
                         // Clear from cursor to end of screen with bounds checking
-                        if self.cursor_row < self.rows {
-                            // Clear current line from cursor with bounds checking
-                            if let Some(row) = self.buffer.get_mut(self.cursor_row) {
-                                for col in self.cursor_col..self.cols.min(row.len()) {
-                                    if let Some(cell) = row.get_mut(col) {
-                                        *cell = TerminalCell::default();
-                                    }
+                        // Clear current line from cursor with bounds checking
+                        if let Some(row) = self.buffer.get_mut(self.cursor_row) {
+                            for col in self.cursor_col..self.cols.min(row.len()) {
+                                if let Some(cell) = row.get_mut(col) {
+                                    *cell = TerminalCell::default();
                                 }
                             }
-                            
-                            // Clear all lines below with bounds checking
-                            for row_idx in (self.cursor_row + 1)..self.rows.min(self.buffer.len()) {
-                                if let Some(row) = self.buffer.get_mut(row_idx) {
-                                    for col in 0..self.cols.min(row.len()) {
-                                        if let Some(cell) = row.get_mut(col) {
-                                            *cell = TerminalCell::default();
-                                        }
+                        }
+
+                        // Clear all lines below with bounds checking
+                        for row_idx in (self.cursor_row + 1)..self.rows.min(self.buffer.len()) {
+                            if let Some(row) = self.buffer.get_mut(row_idx) {
+                                for col in 0..self.cols.min(row.len()) {
+                                    if let Some(cell) = row.get_mut(col) {
+                                        *cell = TerminalCell::default();
                                     }
                                 }
                             }
@@ -1029,6 +1285,35 @@ impl TerminalEmulator {
         }
     }
 
+    /// Applies a DEC private mode set/reset; unsupported modes are ignored
+    fn set_private_mode(&mut self, mode: usize, enabled: bool) {
+        self.debug_log(&format!("PRIVATE_MODE: {} {}", mode, if enabled { "set" } else { "reset" }));
+        match mode {
+            1000 | 1002 | 1003 if enabled => {
+                self.mouse_tracking = match mode {
+                    1000 => MouseTracking::Click,
+                    1002 => MouseTracking::Drag,
+                    _ => MouseTracking::Any,
+                };
+            }
+            1000 | 1002 | 1003 => self.mouse_tracking = MouseTracking::Off,
+            1006 if enabled => self.mouse_encoding = MouseEncoding::Sgr,
+            // SGR takes precedence when both are requested
+            1015 if enabled && self.mouse_encoding != MouseEncoding::Sgr => {
+                self.mouse_encoding = MouseEncoding::Urxvt;
+            }
+            1015 if enabled => {}
+            1006 | 1015 => {
+                let current = if mode == 1006 { MouseEncoding::Sgr } else { MouseEncoding::Urxvt };
+                if self.mouse_encoding == current {
+                    self.mouse_encoding = MouseEncoding::X10;
+                }
+            }
+            2004 => self.bracketed_paste = enabled,
+            _ => {}
+        }
+    }
+
     /// Parse ANSI parameters with proper edge case handling
     /// 
     /// This method handles various edge cases in ANSI parameter parsing:
@@ -1098,13 +1383,11 @@ impl TerminalEmulator {
     }
 
     fn handle_graphics_mode(&mut self, params: &[AnsiParameter]) {
-        // Use Catppuccin Frappé theme for ANSI color mapping
-        const THEME: &CatppuccinTheme = &CatppuccinTheme::FRAPPE;
-        
+        // Colors are stored symbolically and resolved against the active theme at render time
         // Handle empty parameters or single empty parameter (reset case)
         if params.is_empty() || (params.len() == 1 && matches!(params[0], AnsiParameter::Empty(_))) {
-            // Reset to Catppuccin text color instead of white
-            self.current_color = THEME.text;
+            // Reset to the theme's default foreground
+            self.current_color = CellColor::Default;
             self.bold = false;
             return;
         }
@@ -1129,48 +1412,38 @@ impl TerminalEmulator {
             
             match param_value {
                 0 => {
-                    // Reset to Catppuccin text color
-                    self.current_color = THEME.text;
+                    // Reset to the theme's default foreground
+                    self.current_color = CellColor::Default;
                     self.bold = false;
                 }
                 1 => self.bold = true,
                 22 => self.bold = false,
-                // ANSI color codes 30-37 mapped to Catppuccin Frappé colors
-                30 => self.current_color = THEME.surface1,  // Black -> surface1
-                31 => self.current_color = THEME.red,       // Red -> red
-                32 => self.current_color = THEME.green,     // Green -> green
-                33 => self.current_color = THEME.yellow,    // Yellow -> yellow
-                34 => self.current_color = THEME.blue,      // Blue -> blue
-                35 => self.current_color = THEME.mauve,     // Magenta -> mauve
-                36 => self.current_color = THEME.teal,      // Cyan -> teal
-                37 => self.current_color = THEME.text,      // White -> text
-                // Bright ANSI color codes 90-97 mapped to same Catppuccin colors with surface2 for bright black
-                90 => self.current_color = THEME.surface2,  // Bright Black -> surface2
-                91 => self.current_color = THEME.red,       // Bright Red -> red
-                92 => self.current_color = THEME.green,     // Bright Green -> green
-                93 => self.current_color = THEME.yellow,    // Bright Yellow -> yellow
-                94 => self.current_color = THEME.blue,      // Bright Blue -> blue
-                95 => self.current_color = THEME.mauve,     // Bright Magenta -> mauve
-                96 => self.current_color = THEME.teal,      // Bright Cyan -> teal
-                97 => self.current_color = THEME.text,      // Bright White -> text
-                38 => {
-                    // 256-color foreground with bounds checking and proper parameter handling
-                    if i + 2 < params.len() && i + 2 < 100 { // Additional bounds check
-                        // Check if next parameter is "5" (256-color mode indicator)
-                        let mode_param = match params.get(i + 1) {
-                            Some(AnsiParameter::Value(5)) => true,
-                            _ => false,
-                        };
-                        
-                        if mode_param {
-                            // Get color index parameter
-                            if let Some(AnsiParameter::Value(color_index)) = params.get(i + 2) {
-                                if *color_index <= 255 {
-                                    self.current_color = ansi_256_to_rgb(*color_index as u8);
-                                }
+                // ANSI color codes 30-37 map to the theme's normal colors (0-7)
+                30..=37 => self.current_color = CellColor::Indexed((param_value - 30) as u8),
+                39 => self.current_color = CellColor::Default,
+                // Bright ANSI color codes 90-97 map to the theme's bright colors (8-15)
+                90..=97 => self.current_color = CellColor::Indexed((param_value - 90 + 8) as u8),
+                // 256-color foreground with bounds checking and proper parameter handling
+                38 if i + 2 < params.len() && i + 2 < 100 => { // Additional bounds check
+                    // Check if next parameter is "5" (256-color mode indicator)
+                    let mode_param = matches!(params.get(i + 1), Some(AnsiParameter::Value(5)));
+
+                    if mode_param {
+                        // Get color index parameter
+                        if let Some(AnsiParameter::Value(color_index)) = params.get(i + 2) {
+                            if *color_index <= 255 {
+                                self.current_color = CellColor::Indexed(*color_index as u8);
                             }
-                            i += 2; // Skip the next two parameters
                         }
+                        i += 2; // Skip the next two parameters
+                    } else if matches!(params.get(i + 1), Some(AnsiParameter::Value(2))) && i + 4 < params.len() {
+                        // Truecolor: 38;2;r;g;b
+                        let channel = |k: usize| match params.get(i + k) {
+                            Some(AnsiParameter::Value(v)) => (*v).min(255) as u8,
+                            _ => 0,
+                        };
+                        self.current_color = CellColor::Rgb(egui::Color32::from_rgb(channel(2), channel(3), channel(4)));
+                        i += 4; // Skip mode and the three channels
                     }
                 }
                 _ => {
@@ -1182,6 +1455,189 @@ impl TerminalEmulator {
     }
 }
 
+/// Encodes pasted text for the PTY
+///
+/// Line endings are sent as carriage returns, as typed Enter would be. In bracketed
+/// mode the text is wrapped in `ESC[200~` … `ESC[201~` and any end marker inside the
+/// text is removed, repeatedly, so the paste cannot terminate the bracket early even
+/// when removing one marker joins the pieces of another.
+pub fn encode_paste(text: &str, bracketed: bool) -> Vec<u8> {
+    let normalized = text.replace("\r\n", "\r").replace('\n', "\r");
+    if bracketed {
+        let mut body = normalized;
+        while body.contains("\x1b[201~") {
+            body = body.replace("\x1b[201~", "");
+        }
+        format!("\x1b[200~{}\x1b[201~", body).into_bytes()
+    } else {
+        normalized.into_bytes()
+    }
+}
+
+/// Names a CSI sequence body (after `ESC[`) and says whether the emulator acts on it
+///
+/// Kept in step with `handle_ansi_sequence` for the developer tools inspector.
+///
+/// # Returns
+///
+/// `Ok(name)` when every part of the sequence is handled, or `Err(reason)` naming
+/// the parts (SGR attributes, DEC modes, whole sequences) that are ignored.
+pub fn csi_support(sequence: &str) -> Result<String, String> {
+    let Some(cmd) = sequence.chars().last() else {
+        return Err("empty sequence".to_string());
+    };
+    let params = &sequence[..sequence.len() - cmd.len_utf8()];
+
+    if let Some(private) = params.strip_prefix('?') {
+        let name = match cmd {
+            'h' => "DECSET",
+            'l' => "DECRST",
+            _ => return Err(format!("private CSI ?…{} not supported", cmd)),
+        };
+        let modes: Vec<&str> = private.split(';').collect();
+        let label = format!(
+            "{} {}",
+            name,
+            modes.iter().map(|m| format!("{} {}", m, dec_mode_name(m))).collect::<Vec<_>>().join(", ")
+        );
+        let ignored: Vec<&str> = modes
+            .iter()
+            .filter(|m| !matches!(m.parse::<usize>(), Ok(1000 | 1002 | 1003 | 1006 | 1015 | 2004)))
+            .copied()
+            .collect();
+        return if ignored.is_empty() {
+            Ok(label)
+        } else {
+            Err(format!("{} — ignored: {}", label, ignored.join(", ")))
+        };
+    }
+
+    let name = match cmd {
+        'H' => "CUP cursor position",
+        'f' => "HVP cursor position",
+        'G' => "CHA cursor column",
+        'd' => "VPA cursor row",
+        'A' => "CUU cursor up",
+        'B' => "CUD cursor down",
+        'C' => "CUF cursor forward",
+        'D' => "CUB cursor back",
+        'J' => "ED erase display",
+        'K' => "EL erase line",
+        'X' => "ECH erase characters",
+        'm' => "SGR",
+        _ => return Err(format!("{} — not supported", unsupported_csi_name(cmd))),
+    };
+    match cmd {
+        'J' | 'K' if !matches!(params, "" | "0" | "1" | "2") => {
+            Err(format!("{} {} — mode ignored", name, params))
+        }
+        'm' => {
+            let ignored = ignored_sgr(params);
+            if ignored.is_empty() {
+                Ok(format!("SGR {}", params))
+            } else {
+                Err(format!("SGR {} — ignored: {}", params, ignored.join(", ")))
+            }
+        }
+        _ => Ok(name.to_string()),
+    }
+}
+
+/// SGR parameters in `params` that `handle_graphics_mode` skips, with their meaning
+fn ignored_sgr(params: &str) -> Vec<String> {
+    let values: Vec<usize> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+    let mut ignored = Vec::new();
+    let mut i = 0;
+    while i < values.len() {
+        let value = values[i];
+        let meaning = match value {
+            0 | 1 | 22 | 30..=37 | 39 | 90..=97 => None,
+            38 if values.get(i + 1) == Some(&5) && i + 2 < values.len() => {
+                i += 2;
+                None
+            }
+            38 if values.get(i + 1) == Some(&2) && i + 4 < values.len() => {
+                i += 4;
+                None
+            }
+            38 => Some("malformed extended color"),
+            2 => Some("dim"),
+            3 => Some("italic"),
+            4 => Some("underline"),
+            5 | 6 => Some("blink"),
+            7 => Some("reverse"),
+            8 => Some("hidden"),
+            9 => Some("strikethrough"),
+            21 | 23..=29 => Some("attribute reset"),
+            40..=47 | 49 | 100..=107 => Some("background"),
+            48 | 58 => {
+                // Skip the extended color arguments
+                match values.get(i + 1) {
+                    Some(5) => i += 2,
+                    Some(2) => i += 4,
+                    _ => {}
+                }
+                Some(if value == 48 { "background" } else { "underline color" })
+            }
+            _ => Some("unknown"),
+        };
+        if let Some(meaning) = meaning {
+            ignored.push(format!("{} ({})", value, meaning));
+        }
+        i += 1;
+    }
+    ignored
+}
+
+/// Short description of a DEC private mode number
+fn dec_mode_name(mode: &str) -> &'static str {
+    match mode {
+        "1" => "cursor keys",
+        "7" => "autowrap",
+        "12" => "cursor blink",
+        "25" => "cursor visible",
+        "47" | "1047" | "1049" => "alternate screen",
+        "1000" => "mouse click",
+        "1002" => "mouse drag",
+        "1003" => "mouse motion",
+        "1004" => "focus events",
+        "1006" => "SGR mouse",
+        "1015" => "urxvt mouse",
+        "2004" => "bracketed paste",
+        "2026" => "synchronized output",
+        _ => "",
+    }
+}
+
+/// Name of a CSI final byte the emulator does not handle
+fn unsupported_csi_name(cmd: char) -> String {
+    let name = match cmd {
+        'r' => "DECSTBM scroll region",
+        'L' => "IL insert lines",
+        'M' => "DL delete lines",
+        'P' => "DCH delete characters",
+        '@' => "ICH insert characters",
+        'S' => "SU scroll up",
+        'T' => "SD scroll down",
+        'E' => "CNL next line",
+        'F' => "CPL previous line",
+        'n' => "DSR status report",
+        'c' => "DA device attributes",
+        's' => "save cursor",
+        'u' => "restore cursor",
+        'h' => "SM set mode",
+        'l' => "RM reset mode",
+        't' => "window operation",
+        'b' => "REP repeat",
+        'g' => "TBC tab clear",
+        'I' => "CHT tab forward",
+        'Z' => "CBT tab back",
+        '~' => "function key",
+        _ => return format!("CSI {}", cmd),
+    };
+    name.to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1190,7 +1646,7 @@ mod tests {
     fn test_terminal_cell_default() {
         let cell = TerminalCell::default();
         assert_eq!(cell.character, ' ');
-        assert_eq!(cell.color, CatppuccinTheme::FRAPPE.text);
+        assert_eq!(cell.color, CellColor::Default);
         assert!(!cell.bold);
     }
 
@@ -1201,9 +1657,36 @@ mod tests {
         assert_eq!(terminal.buffer[0].len(), 80);
     }
 
+    #[test]
+    fn test_resize_grow_and_shrink() {
+        let mut terminal = TerminalEmulator::new(3, 10);
+        terminal.process_ansi_data("one\ntwo\nthree");
+
+        // Growing appends blank rows and widens every row
+        terminal.resize(5, 20);
+        assert_eq!((terminal.rows, terminal.cols), (5, 20));
+        assert_eq!(terminal.buffer.len(), 5);
+        assert!(terminal.buffer.iter().all(|r| r.len() == 20));
+        assert_eq!(terminal.buffer[2][0].character, 't');
+        assert_eq!(terminal.cursor_row, 2);
+
+        // Shrinking below the cursor row scrolls the top off and keeps the cursor line
+        terminal.resize(2, 4);
+        assert_eq!(terminal.buffer.len(), 2);
+        assert!(terminal.buffer.iter().all(|r| r.len() == 4));
+        assert_eq!(terminal.buffer[1][0].character, 't');
+        assert_eq!(terminal.buffer[1][1].character, 'h');
+        assert_eq!(terminal.cursor_row, 1);
+        assert!(terminal.cursor_col < 4);
+
+        // Zero dimensions are ignored
+        terminal.resize(0, 10);
+        assert_eq!((terminal.rows, terminal.cols), (2, 4));
+    }
+
     #[test]
     fn test_ansi_parameter_parsing_edge_cases() {
-        let mut terminal = TerminalEmulator::new(24, 80);
+        let terminal = TerminalEmulator::new(24, 80);
         
         // Test empty parameters
         let params = terminal.parse_ansi_parameters("");
@@ -1316,7 +1799,7 @@ mod tests {
         terminal.bold = true;
         terminal.process_ansi_data("\x1b[m");
         assert!(!terminal.bold);
-        assert_eq!(terminal.current_color, CatppuccinTheme::FRAPPE.text);
+        assert_eq!(terminal.current_color, CellColor::Default);
         
         // Test empty parameter in graphics mode (should reset)
         terminal.bold = true;
@@ -1553,7 +2036,9 @@ mod tests {
 
     #[test]
     fn test_debug_logging() {
+        let dir = tempfile::tempdir().unwrap();
         let mut terminal = TerminalEmulator::new(3, 20);
+        terminal.debug_log_path = dir.path().join("terminal_debug.log");
         
         // Enable debug logging
         terminal.enable_debug_logging();
@@ -1566,12 +2051,15 @@ mod tests {
         
         // Check that debug file was created (we can't easily test content in unit tests)
         // This test mainly verifies the logging methods don't crash
-        assert!(terminal.debug_logging == false);
+        assert!(!terminal.debug_logging);
+        assert!(terminal.debug_log_path.exists());
     }
 
     #[test]
     fn test_contamination_scenario_with_debug() {
+        let dir = tempfile::tempdir().unwrap();
         let mut terminal = TerminalEmulator::new(5, 50);
+        terminal.debug_log_path = dir.path().join("terminal_debug.log");
         
         // Enable debug logging for this specific test
         terminal.enable_debug_logging();
@@ -1673,7 +2161,7 @@ mod tests {
         
         // Test colors
         terminal.process_ansi_data("\x1b[31mRed Text\x1b[0m");
-        assert_eq!(terminal.current_color, CatppuccinTheme::FRAPPE.text); // Reset
+        assert_eq!(terminal.current_color, CellColor::Default); // Reset
         
         // Test screen clearing
         terminal.process_ansi_data("\x1b[2J");
@@ -1924,9 +2412,9 @@ mod tests {
         terminal.process_ansi_data("\x1b[31mRed");
         
         assert_eq!(terminal.buffer[0][0].character, 'R');
-        assert_eq!(terminal.buffer[0][0].color, CatppuccinTheme::FRAPPE.red);
-        assert_eq!(terminal.buffer[0][1].color, CatppuccinTheme::FRAPPE.red);
-        assert_eq!(terminal.buffer[0][2].color, CatppuccinTheme::FRAPPE.red);
+        assert_eq!(terminal.buffer[0][0].color, CellColor::Indexed(1));
+        assert_eq!(terminal.buffer[0][1].color, CellColor::Indexed(1));
+        assert_eq!(terminal.buffer[0][2].color, CellColor::Indexed(1));
     }
 
     #[test]
@@ -1950,10 +2438,10 @@ mod tests {
         // Set red and bold, then reset
         terminal.process_ansi_data("\x1b[31;1mRed\x1b[0mNormal");
         
-        assert_eq!(terminal.buffer[0][0].color, CatppuccinTheme::FRAPPE.red);
+        assert_eq!(terminal.buffer[0][0].color, CellColor::Indexed(1));
         assert!(terminal.buffer[0][0].bold);
         
-        assert_eq!(terminal.buffer[0][3].color, CatppuccinTheme::FRAPPE.text);
+        assert_eq!(terminal.buffer[0][3].color, CellColor::Default);
         assert!(!terminal.buffer[0][3].bold);
     }
 
@@ -1966,7 +2454,130 @@ mod tests {
         
         assert_eq!(terminal.buffer[0][0].character, 'R');
         // Should use the 256-color conversion
-        assert_eq!(terminal.buffer[0][0].color, ansi_256_to_rgb(196));
+        assert_eq!(terminal.buffer[0][0].color, CellColor::Indexed(196));
+    }
+
+    #[test]
+    fn test_mouse_private_modes() {
+        let mut terminal = TerminalEmulator::new(5, 10);
+        assert_eq!(terminal.mouse_mode(), (MouseTracking::Off, MouseEncoding::X10));
+
+        terminal.process_ansi_data("\x1b[?1002;1006h");
+        assert_eq!(terminal.mouse_mode(), (MouseTracking::Drag, MouseEncoding::Sgr));
+
+        // 1015 does not override SGR; resetting SGR falls back to X10
+        terminal.process_ansi_data("\x1b[?1015h\x1b[?1006l");
+        assert_eq!(terminal.mouse_mode(), (MouseTracking::Drag, MouseEncoding::X10));
+
+        terminal.process_ansi_data("\x1b[?1003h\x1b[?1003l");
+        assert_eq!(terminal.mouse_mode().0, MouseTracking::Off);
+
+        // Private mode sequences do not print anything
+        assert_eq!(terminal.buffer[0][0].character, ' ');
+    }
+
+    #[test]
+    fn test_bracketed_paste_mode_and_encoding() {
+        let mut terminal = TerminalEmulator::new(5, 10);
+        assert!(!terminal.bracketed_paste());
+        terminal.process_ansi_data("\x1b[?2004h");
+        assert!(terminal.bracketed_paste());
+        terminal.process_ansi_data("\x1b[?2004l");
+        assert!(!terminal.bracketed_paste());
+
+        assert_eq!(encode_paste("a\nb\r\nc", false), b"a\rb\rc");
+        assert_eq!(encode_paste("ls\n", true), b"\x1b[200~ls\r\x1b[201~");
+        // An embedded end marker cannot break out of the bracket
+        assert_eq!(encode_paste("x\x1b[201~rm", true), b"\x1b[200~xrm\x1b[201~");
+        // Removing the inner marker must not leave an outer one behind
+        assert_eq!(encode_paste("\x1b[2\x1b[201~01~rm -rf ~\n", true), b"\x1b[200~rm -rf ~\r\x1b[201~");
+    }
+
+    #[test]
+    fn test_screen_text_and_find() {
+        let mut terminal = TerminalEmulator::new(3, 12);
+        terminal.process_ansi_data("Kick.WAV ok\r\n界 kick");
+        assert_eq!(terminal.screen_text(), "Kick.WAV ok\n界 kick");
+        assert_eq!(terminal.cursor_line(), "界 kick");
+
+        let matches = terminal.find("KICK");
+        assert_eq!(matches, vec![(0, 0, 4), (1, 3, 4)]);
+        // Wide glyphs span two cells
+        assert_eq!(terminal.find("界"), vec![(1, 0, 2)]);
+        assert!(terminal.find("").is_empty());
+    }
+
+    #[test]
+    fn test_osc_strings_are_not_rendered() {
+        let mut terminal = TerminalEmulator::new(3, 20);
+        // BEL- and ST-terminated, split across chunks like PTY reads
+        terminal.process_ansi_data("\x1b]0;title\x07a\x1b]133;");
+        terminal.process_ansi_data("C\x1b\\b\x1b]2;x\x1b[31mc");
+        assert_eq!(terminal.screen_text(), "abc");
+        assert_eq!(terminal.buffer[0][2].color, CellColor::Indexed(1));
+    }
+
+    #[test]
+    fn test_trace_records_parsed_stream() {
+        let mut terminal = TerminalEmulator::new(3, 20);
+        terminal.process_ansi_data("ignored");
+        terminal.set_tracing(true);
+        terminal.process_ansi_data("ab\x1b[4;31mc\r\n\x1b]0;t\x07\x1b[2r\x1bZ\x07");
+        assert_eq!(
+            terminal.take_trace(),
+            vec![
+                TraceEvent::Text("ab".into()),
+                TraceEvent::Csi("4;31m".into(), false),
+                TraceEvent::Text("c".into()),
+                TraceEvent::Control('\r', true),
+                TraceEvent::Control('\n', true),
+                TraceEvent::Osc("0;t".into()),
+                TraceEvent::Csi("2r".into(), false),
+                TraceEvent::Invalid("\x1bZ".into()),
+                TraceEvent::Control('\x07', false),
+            ]
+        );
+        assert!(terminal.take_trace().is_empty());
+        assert_eq!(terminal.cursor(), (1, 1));
+        assert_eq!(terminal.sgr(), (CellColor::Indexed(1), false));
+    }
+
+    #[test]
+    fn test_ris_resets_screen_and_modes() {
+        let mut terminal = TerminalEmulator::new(3, 10);
+        terminal.process_ansi_data("\x1b[?1000h\x1b[?2004h\x1b[1;31mabc\x1bcd");
+        assert_eq!(terminal.screen_text(), "d");
+        assert_eq!(terminal.mouse_mode().0, MouseTracking::Off);
+        assert!(!terminal.bracketed_paste());
+        assert_eq!(terminal.sgr(), (CellColor::Default, false));
+        assert_eq!(terminal.cursor(), (0, 1));
+    }
+
+    #[test]
+    fn test_csi_support_names_ignored_parts() {
+        assert_eq!(csi_support("10;5H"), Ok("CUP cursor position".to_string()));
+        assert_eq!(csi_support("1;38;5;208m"), Ok("SGR 1;38;5;208".to_string()));
+        assert_eq!(csi_support("1;41m"), Err("SGR 1;41 — ignored: 41 (background)".to_string()));
+        assert!(csi_support("?1006;2004h").is_ok());
+        assert_eq!(
+            csi_support("?25l"),
+            Err("DECRST 25 cursor visible — ignored: 25".to_string())
+        );
+        assert!(csi_support("3J").is_err());
+        assert_eq!(csi_support("1;20r"), Err("DECSTBM scroll region — not supported".to_string()));
+    }
+
+    #[test]
+    fn test_ansi_bright_and_truecolor() {
+        let mut terminal = TerminalEmulator::new(5, 10);
+
+        // Bright colors keep their own palette slots; 39 restores the default
+        terminal.process_ansi_data("\x1b[31mA\x1b[91mB\x1b[39mC\x1b[38;2;10;20;30mD");
+
+        assert_eq!(terminal.buffer[0][0].color, CellColor::Indexed(1));
+        assert_eq!(terminal.buffer[0][1].color, CellColor::Indexed(9));
+        assert_eq!(terminal.buffer[0][2].color, CellColor::Default);
+        assert_eq!(terminal.buffer[0][3].color, CellColor::Rgb(egui::Color32::from_rgb(10, 20, 30)));
     }
 
     #[test]
@@ -2144,4 +2755,30 @@ mod tests {
         terminal.process_ansi_data("\x1b[100C"); // Move right 100 columns
         assert_eq!(terminal.cursor_col, 1);
     }
-}
\ No newline at end of file
+
+    /// Progress output recorded from a pane, with an escape split across events
+    const PROGRESS_CAST: &str = concat!(
+        r#"{"version": 2, "width": 24, "height": 4}"#,
+        "\n",
+        r#"[0.0, "o", "\u001b[2J\u001b[HNormalizing take1.wav\r\n"]"#,
+        "\n",
+        r#"[0.2, "o", " 10% [#   ]\r"]"#,
+        "\n",
+        r#"[0.4, "o", " 90% [###\u001b"]"#,
+        "\n",
+        r#"[0.5, "o", "[1m#]\u001b[0m\r100% done\u001b[K\r\n"]"#,
+        "\n",
+        r#"[0.6, "o", "\u001b[32mOK\u001b[0m"]"#,
+        "\n",
+    );
+
+    #[test]
+    fn test_replay_progress_cast() {
+        let cast = crate::cast::parse(PROGRESS_CAST).expect("valid cast");
+        let mut terminal = TerminalEmulator::new(cast.header.height, cast.header.width);
+        crate::cast::replay(&cast, &mut terminal);
+        assert_eq!(terminal.screen_text(), "Normalizing take1.wav\n100% done\nOK");
+        assert_eq!(terminal.cursor(), (2, 2));
+        assert_eq!(terminal.buffer[2][0].color, CellColor::Indexed(2));
+    }
+}
@@ -1,25 +1,47 @@
 //! # Theme Module
 //! 
-//! This module provides the Catppuccin Frappé color theme for the Audio Toolkit Shell.
-//! It includes the complete color palette and utility functions for color conversion.
+//! This module provides the color themes for the Audio Toolkit Shell: the UI palette,
+//! the 16-color ANSI table used by the terminal panes, and utilities for color conversion.
 //! 
 //! ## Usage
 //! 
 //! ```rust
-//! use crate::theme::{CatppuccinTheme, ansi_256_to_rgb};
+//! use crate::theme::{Theme, CellColor};
 //! 
-//! // Access theme colors
-//! let theme = CatppuccinTheme::FRAPPE;
-//! let background_color = theme.base;
-//! let text_color = theme.text;
+//! // Pick a built-in theme
+//! let theme = Theme::frappe();
+//! let background_color = theme.palette.base;
+//! let text_color = theme.palette.text;
 //! 
-//! // Convert ANSI colors
-//! let red_color = ansi_256_to_rgb(1); // ANSI red -> Catppuccin red
+//! // Resolve terminal cell colors
+//! let red_color = theme.resolve(CellColor::Indexed(1)); // ANSI red -> theme red
+//! ```
+//! 
+//! ## Built-in Themes
+//! 
+//! - Catppuccin Latte, Frappé (default), Macchiato and Mocha
+//! - High Contrast Dark and High Contrast Light
+//! 
+//! ## User Themes
+//! 
+//! Additional themes are loaded from `*.toml` files in the themes directory
+//! (`<config dir>/themes` by default). Every key is optional; unspecified colors
+//! are inherited from a built-in theme:
+//! 
+//! ```toml
+//! name = "Studio Night"
+//! inherits = "mocha"        # built-in to start from (default: frappe)
+//! ansi = ["#45475a", "#f38ba8", "#a6e3a1", "#f9e2af", "#89b4fa", "#f5c2e7", "#94e2d5", "#bac2de",
+//!         "#585b70", "#f38ba8", "#a6e3a1", "#f9e2af", "#89b4fa", "#f5c2e7", "#94e2d5", "#a6adc8"]
+//! 
+//! [ui]
+//! base = "#101018"          # any palette role: base, text, surface0, blue, ...
+//! text = "#e0e0ff"
 //! ```
 //! 
 //! ## Color Palette
 //! 
-//! The Catppuccin Frappé theme provides a carefully crafted color palette with:
+//! Every theme provides the same palette roles (named after Catppuccin):
 //! - **Base colors**: For backgrounds and surfaces (base, mantle, crust)
 //! - **Text colors**: For text hierarchy (text, subtext1, subtext0)
 //! - **Surface colors**: For UI elevation (surface0, surface1, surface2)
@@ -27,93 +49,123 @@
 //! - **Accent colors**: For semantic meaning and visual interest
 
 use eframe::egui;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 
-/// Catppuccin Frappé color theme for the Audio Toolkit Shell
+/// UI color palette shared by every theme
 /// 
-/// This struct provides compile-time constants for all Catppuccin Frappé colors,
-/// ensuring optimal performance and consistent theming throughout the application.
+/// The roles follow the Catppuccin naming so built-in and user themes can be used
+/// interchangeably throughout the UI code.
 /// 
-/// Color palette based on Catppuccin Frappé variant:
-/// https://github.com/catppuccin/catppuccin
-#[derive(Clone, Copy, Debug)]
-pub struct CatppuccinTheme {
+/// Catppuccin palettes: https://github.com/catppuccin/catppuccin
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
     // Base colors for backgrounds and surfaces
-    /// Main background color (#303446) - Used for primary backgrounds
+    /// Main background color - Used for primary backgrounds
     pub base: egui::Color32,
-    /// Secondary background color (#292c3c) - Used for elevated surfaces
+    /// Secondary background color - Used for elevated surfaces
     pub mantle: egui::Color32,
-    /// Darkest background color (#232634) - Used for deepest surfaces
+    /// Darkest background color - Used for deepest surfaces
     pub crust: egui::Color32,
 
     // Text hierarchy colors
-    /// Primary text color (#c6d0f5) - Used for main text content
+    /// Primary text color - Used for main text content
     pub text: egui::Color32,
-    /// Secondary text color (#b5bfe2) - Used for less important text
+    /// Secondary text color - Used for less important text
     pub subtext1: egui::Color32,
-    /// Tertiary text color (#a5adce) - Used for subtle text and hints
+    /// Tertiary text color - Used for subtle text and hints
     pub subtext0: egui::Color32,
 
     // Surface colors for UI elements
-    /// Lowest elevation surface (#414559) - Used for subtle elevation
+    /// Lowest elevation surface - Used for subtle elevation
     pub surface0: egui::Color32,
-    /// Medium elevation surface (#51576d) - Used for moderate elevation
+    /// Medium elevation surface - Used for moderate elevation
     pub surface1: egui::Color32,
-    /// Highest elevation surface (#626880) - Used for prominent elevation
+    /// Highest elevation surface - Used for prominent elevation
     pub surface2: egui::Color32,
 
     // Overlay colors
-    /// Overlay color (#737994) - Used for overlays and disabled states
+    /// Overlay color - Used for overlays and disabled states
     pub overlay0: egui::Color32,
-    /// Secondary overlay color (#838ba7) - Used for secondary overlays
+    /// Secondary overlay color - Used for secondary overlays
     pub overlay1: egui::Color32,
-    /// Tertiary overlay color (#949cbb) - Used for tertiary overlays
+    /// Tertiary overlay color - Used for tertiary overlays
     pub overlay2: egui::Color32,
 
     // Semantic accent colors
-    /// Blue accent color (#8caaee) - Used for primary actions and info
+    /// Blue accent color - Used for primary actions and info
     pub blue: egui::Color32,
-    /// Lavender accent color (#babbf1) - Used for special highlights
+    /// Lavender accent color - Used for special highlights
     pub lavender: egui::Color32,
-    /// Sapphire accent color (#85c1dc) - Used for secondary actions
+    /// Sapphire accent color - Used for secondary actions
     pub sapphire: egui::Color32,
-    /// Sky accent color (#99d1db) - Used for tertiary actions
+    /// Sky accent color - Used for tertiary actions
     pub sky: egui::Color32,
-    /// Teal accent color (#81c8be) - Used for success and safe actions
+    /// Teal accent color - Used for success and safe actions
     pub teal: egui::Color32,
-    /// Green accent color (#a6d189) - Used for success states
+    /// Green accent color - Used for success states
     pub green: egui::Color32,
-    /// Yellow accent color (#e5c890) - Used for warnings and caution
+    /// Yellow accent color - Used for warnings and caution
     pub yellow: egui::Color32,
-    /// Peach accent color (#ef9f76) - Used for warm accents
+    /// Peach accent color - Used for warm accents
     pub peach: egui::Color32,
-    /// Maroon accent color (#ea999c) - Used for muted red accents
+    /// Maroon accent color - Used for muted red accents
     pub maroon: egui::Color32,
-    /// Red accent color (#e78284) - Used for errors and danger
+    /// Red accent color - Used for errors and danger
     pub red: egui::Color32,
-    /// Mauve accent color (#ca9ee6) - Used for special accents and highlights
+    /// Mauve accent color - Used for special accents and highlights
     pub mauve: egui::Color32,
-    /// Pink accent color (#f4b8e4) - Used for decorative accents
+    /// Pink accent color - Used for decorative accents
     pub pink: egui::Color32,
-    /// Flamingo accent color (#eebebe) - Used for soft accents
+    /// Flamingo accent color - Used for soft accents
     pub flamingo: egui::Color32,
-    /// Rosewater accent color (#f2d5cf) - Used for subtle warm accents
+    /// Rosewater accent color - Used for subtle warm accents
     pub rosewater: egui::Color32,
 }
 
-impl CatppuccinTheme {
-    /// Catppuccin Frappé theme constant with all colors defined at compile-time
-    /// 
-    /// This constant provides immediate access to all Catppuccin Frappé colors
-    /// without any runtime initialization overhead.
-    /// 
-    /// # Example
-    /// 
-    /// ```rust
-    /// let theme = CatppuccinTheme::FRAPPE;
-    /// let bg_color = theme.base;      // Main background
-    /// let fg_color = theme.text;      // Primary text
-    /// let accent = theme.blue;        // Blue accent color
-    /// ```
+impl Palette {
+    /// Catppuccin Latte (light)
+    pub const LATTE: Self = Self {
+        // Base colors
+        base: egui::Color32::from_rgb(0xef, 0xf1, 0xf5),      // #eff1f5
+        mantle: egui::Color32::from_rgb(0xe6, 0xe9, 0xef),    // #e6e9ef
+        crust: egui::Color32::from_rgb(0xdc, 0xe0, 0xe8),     // #dce0e8
+
+        // Text colors
+        text: egui::Color32::from_rgb(0x4c, 0x4f, 0x69),      // #4c4f69
+        subtext1: egui::Color32::from_rgb(0x5c, 0x5f, 0x77),  // #5c5f77
+        subtext0: egui::Color32::from_rgb(0x6c, 0x6f, 0x85),  // #6c6f85
+
+        // Surface colors
+        surface0: egui::Color32::from_rgb(0xcc, 0xd0, 0xda),  // #ccd0da
+        surface1: egui::Color32::from_rgb(0xbc, 0xc0, 0xcc),  // #bcc0cc
+        surface2: egui::Color32::from_rgb(0xac, 0xb0, 0xbe),  // #acb0be
+
+        // Overlay colors
+        overlay0: egui::Color32::from_rgb(0x9c, 0xa0, 0xb0),  // #9ca0b0
+        overlay1: egui::Color32::from_rgb(0x8c, 0x8f, 0xa1),  // #8c8fa1
+        overlay2: egui::Color32::from_rgb(0x7c, 0x7f, 0x93),  // #7c7f93
+
+        // Accent colors
+        blue: egui::Color32::from_rgb(0x1e, 0x66, 0xf5),      // #1e66f5
+        lavender: egui::Color32::from_rgb(0x72, 0x87, 0xfd),  // #7287fd
+        sapphire: egui::Color32::from_rgb(0x20, 0x9f, 0xb5),  // #209fb5
+        sky: egui::Color32::from_rgb(0x04, 0xa5, 0xe5),       // #04a5e5
+        teal: egui::Color32::from_rgb(0x17, 0x92, 0x99),      // #179299
+        green: egui::Color32::from_rgb(0x40, 0xa0, 0x2b),     // #40a02b
+        yellow: egui::Color32::from_rgb(0xdf, 0x8e, 0x1d),    // #df8e1d
+        peach: egui::Color32::from_rgb(0xfe, 0x64, 0x0b),     // #fe640b
+        maroon: egui::Color32::from_rgb(0xe6, 0x45, 0x53),    // #e64553
+        red: egui::Color32::from_rgb(0xd2, 0x0f, 0x39),       // #d20f39
+        mauve: egui::Color32::from_rgb(0x88, 0x39, 0xef),     // #8839ef
+        pink: egui::Color32::from_rgb(0xea, 0x76, 0xcb),      // #ea76cb
+        flamingo: egui::Color32::from_rgb(0xdd, 0x78, 0x78),  // #dd7878
+        rosewater: egui::Color32::from_rgb(0xdc, 0x8a, 0x78), // #dc8a78
+    };
+
+    /// Catppuccin Frappé (dark, default)
     pub const FRAPPE: Self = Self {
         // Base colors
         base: egui::Color32::from_rgb(0x30, 0x34, 0x46),      // #303446
@@ -151,69 +203,462 @@ impl CatppuccinTheme {
         flamingo: egui::Color32::from_rgb(0xee, 0xbe, 0xbe),  // #eebebe
         rosewater: egui::Color32::from_rgb(0xf2, 0xd5, 0xcf), // #f2d5cf
     };
+
+    /// Catppuccin Macchiato (dark)
+    pub const MACCHIATO: Self = Self {
+        // Base colors
+        base: egui::Color32::from_rgb(0x24, 0x27, 0x3a),      // #24273a
+        mantle: egui::Color32::from_rgb(0x1e, 0x20, 0x30),    // #1e2030
+        crust: egui::Color32::from_rgb(0x18, 0x19, 0x26),     // #181926
+
+        // Text colors
+        text: egui::Color32::from_rgb(0xca, 0xd3, 0xf5),      // #cad3f5
+        subtext1: egui::Color32::from_rgb(0xb8, 0xc0, 0xe0),  // #b8c0e0
+        subtext0: egui::Color32::from_rgb(0xa5, 0xad, 0xcb),  // #a5adcb
+
+        // Surface colors
+        surface0: egui::Color32::from_rgb(0x36, 0x3a, 0x4f),  // #363a4f
+        surface1: egui::Color32::from_rgb(0x49, 0x4d, 0x64),  // #494d64
+        surface2: egui::Color32::from_rgb(0x5b, 0x60, 0x78),  // #5b6078
+
+        // Overlay colors
+        overlay0: egui::Color32::from_rgb(0x6e, 0x73, 0x8d),  // #6e738d
+        overlay1: egui::Color32::from_rgb(0x80, 0x87, 0xa2),  // #8087a2
+        overlay2: egui::Color32::from_rgb(0x93, 0x9a, 0xb7),  // #939ab7
+
+        // Accent colors
+        blue: egui::Color32::from_rgb(0x8a, 0xad, 0xf4),      // #8aadf4
+        lavender: egui::Color32::from_rgb(0xb7, 0xbd, 0xf8),  // #b7bdf8
+        sapphire: egui::Color32::from_rgb(0x7d, 0xc4, 0xe4),  // #7dc4e4
+        sky: egui::Color32::from_rgb(0x91, 0xd7, 0xe3),       // #91d7e3
+        teal: egui::Color32::from_rgb(0x8b, 0xd5, 0xca),      // #8bd5ca
+        green: egui::Color32::from_rgb(0xa6, 0xda, 0x95),     // #a6da95
+        yellow: egui::Color32::from_rgb(0xee, 0xd4, 0x9f),    // #eed49f
+        peach: egui::Color32::from_rgb(0xf5, 0xa9, 0x7f),     // #f5a97f
+        maroon: egui::Color32::from_rgb(0xee, 0x99, 0xa0),    // #ee99a0
+        red: egui::Color32::from_rgb(0xed, 0x87, 0x96),       // #ed8796
+        mauve: egui::Color32::from_rgb(0xc6, 0xa0, 0xf6),     // #c6a0f6
+        pink: egui::Color32::from_rgb(0xf5, 0xbd, 0xe6),      // #f5bde6
+        flamingo: egui::Color32::from_rgb(0xf0, 0xc6, 0xc6),  // #f0c6c6
+        rosewater: egui::Color32::from_rgb(0xf4, 0xdb, 0xd6), // #f4dbd6
+    };
+
+    /// Catppuccin Mocha (darkest)
+    pub const MOCHA: Self = Self {
+        // Base colors
+        base: egui::Color32::from_rgb(0x1e, 0x1e, 0x2e),      // #1e1e2e
+        mantle: egui::Color32::from_rgb(0x18, 0x18, 0x25),    // #181825
+        crust: egui::Color32::from_rgb(0x11, 0x11, 0x1b),     // #11111b
+
+        // Text colors
+        text: egui::Color32::from_rgb(0xcd, 0xd6, 0xf4),      // #cdd6f4
+        subtext1: egui::Color32::from_rgb(0xba, 0xc2, 0xde),  // #bac2de
+        subtext0: egui::Color32::from_rgb(0xa6, 0xad, 0xc8),  // #a6adc8
+
+        // Surface colors
+        surface0: egui::Color32::from_rgb(0x31, 0x32, 0x44),  // #313244
+        surface1: egui::Color32::from_rgb(0x45, 0x47, 0x5a),  // #45475a
+        surface2: egui::Color32::from_rgb(0x58, 0x5b, 0x70),  // #585b70
+
+        // Overlay colors
+        overlay0: egui::Color32::from_rgb(0x6c, 0x70, 0x86),  // #6c7086
+        overlay1: egui::Color32::from_rgb(0x7f, 0x84, 0x9c),  // #7f849c
+        overlay2: egui::Color32::from_rgb(0x93, 0x99, 0xb2),  // #9399b2
+
+        // Accent colors
+        blue: egui::Color32::from_rgb(0x89, 0xb4, 0xfa),      // #89b4fa
+        lavender: egui::Color32::from_rgb(0xb4, 0xbe, 0xfe),  // #b4befe
+        sapphire: egui::Color32::from_rgb(0x74, 0xc7, 0xec),  // #74c7ec
+        sky: egui::Color32::from_rgb(0x89, 0xdc, 0xeb),       // #89dceb
+        teal: egui::Color32::from_rgb(0x94, 0xe2, 0xd5),      // #94e2d5
+        green: egui::Color32::from_rgb(0xa6, 0xe3, 0xa1),     // #a6e3a1
+        yellow: egui::Color32::from_rgb(0xf9, 0xe2, 0xaf),    // #f9e2af
+        peach: egui::Color32::from_rgb(0xfa, 0xb3, 0x87),     // #fab387
+        maroon: egui::Color32::from_rgb(0xeb, 0xa0, 0xac),    // #eba0ac
+        red: egui::Color32::from_rgb(0xf3, 0x8b, 0xa8),       // #f38ba8
+        mauve: egui::Color32::from_rgb(0xcb, 0xa6, 0xf7),     // #cba6f7
+        pink: egui::Color32::from_rgb(0xf5, 0xc2, 0xe7),      // #f5c2e7
+        flamingo: egui::Color32::from_rgb(0xf2, 0xcd, 0xcd),  // #f2cdcd
+        rosewater: egui::Color32::from_rgb(0xf5, 0xe0, 0xdc), // #f5e0dc
+    };
+
+    /// High-contrast dark: pure black background, white text, saturated accents
+    pub const HIGH_CONTRAST_DARK: Self = Self {
+        // Base colors
+        base: egui::Color32::from_rgb(0x00, 0x00, 0x00),      // #000000
+        mantle: egui::Color32::from_rgb(0x0a, 0x0a, 0x0a),    // #0a0a0a
+        crust: egui::Color32::from_rgb(0x00, 0x00, 0x00),     // #000000
+
+        // Text colors
+        text: egui::Color32::from_rgb(0xff, 0xff, 0xff),      // #ffffff
+        subtext1: egui::Color32::from_rgb(0xf0, 0xf0, 0xf0),  // #f0f0f0
+        subtext0: egui::Color32::from_rgb(0xe0, 0xe0, 0xe0),  // #e0e0e0
+
+        // Surface colors
+        surface0: egui::Color32::from_rgb(0x1c, 0x1c, 0x1c),  // #1c1c1c
+        surface1: egui::Color32::from_rgb(0x33, 0x33, 0x33),  // #333333
+        surface2: egui::Color32::from_rgb(0x4d, 0x4d, 0x4d),  // #4d4d4d
+
+        // Overlay colors
+        overlay0: egui::Color32::from_rgb(0x8a, 0x8a, 0x8a),  // #8a8a8a
+        overlay1: egui::Color32::from_rgb(0xa6, 0xa6, 0xa6),  // #a6a6a6
+        overlay2: egui::Color32::from_rgb(0xc0, 0xc0, 0xc0),  // #c0c0c0
+
+        // Accent colors
+        blue: egui::Color32::from_rgb(0x4d, 0xa3, 0xff),      // #4da3ff
+        lavender: egui::Color32::from_rgb(0xb0, 0xb0, 0xff),  // #b0b0ff
+        sapphire: egui::Color32::from_rgb(0x33, 0xcc, 0xff),  // #33ccff
+        sky: egui::Color32::from_rgb(0x66, 0xe0, 0xff),       // #66e0ff
+        teal: egui::Color32::from_rgb(0x00, 0xe5, 0xc0),      // #00e5c0
+        green: egui::Color32::from_rgb(0x3d, 0xff, 0x6e),     // #3dff6e
+        yellow: egui::Color32::from_rgb(0xff, 0xe6, 0x00),    // #ffe600
+        peach: egui::Color32::from_rgb(0xff, 0x9e, 0x3d),     // #ff9e3d
+        maroon: egui::Color32::from_rgb(0xff, 0x66, 0x80),    // #ff6680
+        red: egui::Color32::from_rgb(0xff, 0x4d, 0x4d),       // #ff4d4d
+        mauve: egui::Color32::from_rgb(0xd5, 0x80, 0xff),     // #d580ff
+        pink: egui::Color32::from_rgb(0xff, 0x80, 0xdf),      // #ff80df
+        flamingo: egui::Color32::from_rgb(0xff, 0xb3, 0xb3),  // #ffb3b3
+        rosewater: egui::Color32::from_rgb(0xff, 0xd6, 0xcc), // #ffd6cc
+    };
+
+    /// High-contrast light: pure white background, black text, deep accents
+    pub const HIGH_CONTRAST_LIGHT: Self = Self {
+        // Base colors
+        base: egui::Color32::from_rgb(0xff, 0xff, 0xff),      // #ffffff
+        mantle: egui::Color32::from_rgb(0xf2, 0xf2, 0xf2),    // #f2f2f2
+        crust: egui::Color32::from_rgb(0xe6, 0xe6, 0xe6),     // #e6e6e6
+
+        // Text colors
+        text: egui::Color32::from_rgb(0x00, 0x00, 0x00),      // #000000
+        subtext1: egui::Color32::from_rgb(0x1a, 0x1a, 0x1a),  // #1a1a1a
+        subtext0: egui::Color32::from_rgb(0x33, 0x33, 0x33),  // #333333
+
+        // Surface colors
+        surface0: egui::Color32::from_rgb(0xe0, 0xe0, 0xe0),  // #e0e0e0
+        surface1: egui::Color32::from_rgb(0xc8, 0xc8, 0xc8),  // #c8c8c8
+        surface2: egui::Color32::from_rgb(0xa8, 0xa8, 0xa8),  // #a8a8a8
+
+        // Overlay colors
+        overlay0: egui::Color32::from_rgb(0x6e, 0x6e, 0x6e),  // #6e6e6e
+        overlay1: egui::Color32::from_rgb(0x55, 0x55, 0x55),  // #555555
+        overlay2: egui::Color32::from_rgb(0x3d, 0x3d, 0x3d),  // #3d3d3d
+
+        // Accent colors
+        blue: egui::Color32::from_rgb(0x00, 0x40, 0xc0),      // #0040c0
+        lavender: egui::Color32::from_rgb(0x3a, 0x3a, 0xa8),  // #3a3aa8
+        sapphire: egui::Color32::from_rgb(0x00, 0x68, 0x8b),  // #00688b
+        sky: egui::Color32::from_rgb(0x00, 0x6f, 0x99),       // #006f99
+        teal: egui::Color32::from_rgb(0x00, 0x69, 0x5c),      // #00695c
+        green: egui::Color32::from_rgb(0x00, 0x64, 0x00),     // #006400
+        yellow: egui::Color32::from_rgb(0x7a, 0x5c, 0x00),    // #7a5c00
+        peach: egui::Color32::from_rgb(0xa0, 0x40, 0x00),     // #a04000
+        maroon: egui::Color32::from_rgb(0x9b, 0x1b, 0x30),    // #9b1b30
+        red: egui::Color32::from_rgb(0xb0, 0x00, 0x00),       // #b00000
+        mauve: egui::Color32::from_rgb(0x6a, 0x1b, 0x9a),     // #6a1b9a
+        pink: egui::Color32::from_rgb(0xa0, 0x00, 0x5a),      // #a0005a
+        flamingo: egui::Color32::from_rgb(0x8b, 0x3a, 0x3a),  // #8b3a3a
+        rosewater: egui::Color32::from_rgb(0x7a, 0x4a, 0x3a), // #7a4a3a
+    };
+
+    /// Color for a role name (e.g., "blue"), with the same aliases as theme files
+    pub fn role(&self, role: &str) -> Option<egui::Color32> {
+        let mut copy = *self;
+        copy.role_mut(role).map(|c| *c)
+    }
+
+    /// Returns the palette slot for a role name (e.g., "base", "blue"), if any.
+    /// `background` and `foreground` are accepted as aliases for `base` and `text`.
+    fn role_mut(&mut self, role: &str) -> Option<&mut egui::Color32> {
+        let slot = match role {
+            "background" => &mut self.base,
+            "foreground" => &mut self.text,
+            "base" => &mut self.base,
+            "mantle" => &mut self.mantle,
+            "crust" => &mut self.crust,
+            "text" => &mut self.text,
+            "subtext1" => &mut self.subtext1,
+            "subtext0" => &mut self.subtext0,
+            "surface0" => &mut self.surface0,
+            "surface1" => &mut self.surface1,
+            "surface2" => &mut self.surface2,
+            "overlay0" => &mut self.overlay0,
+            "overlay1" => &mut self.overlay1,
+            "overlay2" => &mut self.overlay2,
+            "blue" => &mut self.blue,
+            "lavender" => &mut self.lavender,
+            "sapphire" => &mut self.sapphire,
+            "sky" => &mut self.sky,
+            "teal" => &mut self.teal,
+            "green" => &mut self.green,
+            "yellow" => &mut self.yellow,
+            "peach" => &mut self.peach,
+            "maroon" => &mut self.maroon,
+            "red" => &mut self.red,
+            "mauve" => &mut self.mauve,
+            "pink" => &mut self.pink,
+            "flamingo" => &mut self.flamingo,
+            "rosewater" => &mut self.rosewater,
+            _ => return None,
+        };
+        Some(slot)
+    }
 }
 
-/// Helper function for 256-color ANSI to RGB conversion
-/// 
-/// Converts ANSI 256-color codes to RGB values, using Catppuccin Frappé colors
-/// for the standard 16 colors (0-15) and standard color cube/grayscale for the rest.
+/// Color of a terminal cell, resolved against the active theme at render time
 /// 
-/// This function ensures that terminal colors are consistently themed with the
-/// Catppuccin palette while maintaining compatibility with standard ANSI color codes.
+/// Storing the symbolic color (instead of RGB) lets a theme switch recolor
+/// everything already on screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CellColor {
+    /// The theme's default foreground
+    #[default]
+    Default,
+    /// ANSI 256-color index (0-15 come from the theme's ANSI table)
+    Indexed(u8),
+    /// Explicit RGB color (truecolor SGR, box drawing)
+    Rgb(egui::Color32),
+}
+
+/// A complete color theme: UI palette plus the 16-color ANSI table
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    /// Stable identifier used in `config.toml` (e.g., "frappe", or a user theme's file stem)
+    pub id: String,
+    /// Display name shown in the theme menu
+    pub name: String,
+    /// Whether the theme has a dark background (selects egui's dark or light visuals)
+    pub dark: bool,
+    /// UI palette
+    pub palette: Palette,
+    /// ANSI colors 0-15 (normal 0-7, bright 8-15)
+    pub ansi: [egui::Color32; 16],
+}
+
+impl Theme {
+    /// Builds a theme from a palette, deriving the ANSI table from its roles.
+    /// 
+    /// Normal colors map to the palette accents; bright colors are the same accents
+    /// pushed further from the background so they stay distinguishable.
+    pub fn from_palette(id: &str, name: &str, palette: Palette, dark: bool) -> Self {
+        let p = &palette;
+        // "Bright" means more contrast against the background
+        let toward = if dark { egui::Color32::WHITE } else { egui::Color32::BLACK };
+        let bright = |c: egui::Color32| mix(c, toward, 0.2);
+        let (black, white, bright_black, bright_white) = if dark {
+            (p.surface1, p.subtext1, p.surface2, p.text)
+        } else {
+            (p.subtext1, p.surface2, p.subtext0, p.surface1)
+        };
+        let ansi = [
+            black, p.red, p.green, p.yellow, p.blue, p.mauve, p.teal, white,
+            bright_black, bright(p.red), bright(p.green), bright(p.yellow),
+            bright(p.blue), bright(p.mauve), bright(p.teal), bright_white,
+        ];
+        Self { id: id.to_string(), name: name.to_string(), dark, palette, ansi }
+    }
+
+    /// Catppuccin Latte
+    pub fn latte() -> Self {
+        Self::from_palette("latte", "Catppuccin Latte", Palette::LATTE, false)
+    }
+
+    /// Catppuccin Frappé (default theme)
+    pub fn frappe() -> Self {
+        Self::from_palette("frappe", "Catppuccin Frappé", Palette::FRAPPE, true)
+    }
+
+    /// Catppuccin Macchiato
+    pub fn macchiato() -> Self {
+        Self::from_palette("macchiato", "Catppuccin Macchiato", Palette::MACCHIATO, true)
+    }
+
+    /// Catppuccin Mocha
+    pub fn mocha() -> Self {
+        Self::from_palette("mocha", "Catppuccin Mocha", Palette::MOCHA, true)
+    }
+
+    /// High-contrast dark theme
+    pub fn high_contrast_dark() -> Self {
+        Self::from_palette("high-contrast-dark", "High Contrast Dark", Palette::HIGH_CONTRAST_DARK, true)
+    }
+
+    /// High-contrast light theme
+    pub fn high_contrast_light() -> Self {
+        Self::from_palette("high-contrast-light", "High Contrast Light", Palette::HIGH_CONTRAST_LIGHT, false)
+    }
+
+    /// All built-in themes in menu order
+    pub fn builtins() -> Vec<Self> {
+        vec![
+            Self::latte(),
+            Self::frappe(),
+            Self::macchiato(),
+            Self::mocha(),
+            Self::high_contrast_dark(),
+            Self::high_contrast_light(),
+        ]
+    }
+
+    /// Whether `query` names this theme (case-insensitive id or display name)
+    pub fn matches(&self, query: &str) -> bool {
+        let q = query.trim();
+        self.id.eq_ignore_ascii_case(q) || self.name.to_lowercase() == q.to_lowercase()
+    }
+
+    /// Resolves a cell color against this theme
+    pub fn resolve(&self, color: CellColor) -> egui::Color32 {
+        match color {
+            CellColor::Default => self.palette.text,
+            CellColor::Indexed(index) => self.indexed(index),
+            CellColor::Rgb(rgb) => rgb,
+        }
+    }
+
+    /// Converts an ANSI 256-color index to RGB
+    /// 
+    /// Indices 0-15 come from the theme's ANSI table; the 216-color cube (16-231)
+    /// and the grayscale ramp (232-255) are theme-independent.
+    /// 
+    /// # Example
+    /// 
+    /// ```rust
+    /// let theme = Theme::frappe();
+    /// let red = theme.indexed(1);      // ANSI red -> theme red
+    /// let custom = theme.indexed(196); // 256-color red
+    /// ```
+    pub fn indexed(&self, color_index: u8) -> egui::Color32 {
+        match color_index {
+            // Standard and bright colors (0-15) from the theme
+            0..=15 => self.ansi[color_index as usize],
+
+            // 216 color cube (16-231)
+            16..=231 => {
+                let index = color_index - 16;
+                let r = (index / 36) * 51;
+                let g = ((index % 36) / 6) * 51;
+                let b = (index % 6) * 51;
+                egui::Color32::from_rgb(r, g, b)
+            }
+
+            // Grayscale colors (232-255)
+            232..=255 => {
+                let gray = 8 + (color_index - 232) * 10;
+                egui::Color32::from_rgb(gray, gray, gray)
+            }
+        }
+    }
+}
+
+/// On-disk format of a user theme file
+#[derive(Debug, Deserialize)]
+struct ThemeFile {
+    name: Option<String>,
+    inherits: Option<String>,
+    dark: Option<bool>,
+    ansi: Option<Vec<String>>,
+    #[serde(default)]
+    ui: HashMap<String, String>,
+}
+
+/// Parses a user theme from TOML
 /// 
 /// # Arguments
 /// 
-/// * `color_index` - The ANSI color index (0-255)
+/// * `id` - Identifier for the theme (normally the file stem)
+/// * `content` - TOML text of the theme file
 /// 
 /// # Returns
 /// 
-/// An `egui::Color32` representing the RGB color
-/// 
-/// # Example
-/// 
-/// ```rust
-/// let red = ansi_256_to_rgb(1);    // ANSI red -> Catppuccin red
-/// let green = ansi_256_to_rgb(2);  // ANSI green -> Catppuccin green
-/// let custom = ansi_256_to_rgb(196); // 256-color red
-/// ```
-pub fn ansi_256_to_rgb(color_index: u8) -> egui::Color32 {
-    // Use Catppuccin Frappé theme for standard colors (0-15)
-    const THEME: &CatppuccinTheme = &CatppuccinTheme::FRAPPE;
-    
-    match color_index {
-        // Standard colors (0-15) mapped to Catppuccin Frappé colors
-        0 => THEME.surface1,   // Black -> surface1
-        1 => THEME.red,        // Dark Red -> Catppuccin red
-        2 => THEME.green,      // Dark Green -> Catppuccin green
-        3 => THEME.yellow,     // Dark Yellow -> Catppuccin yellow
-        4 => THEME.blue,       // Dark Blue -> Catppuccin blue
-        5 => THEME.mauve,      // Dark Magenta -> Catppuccin mauve
-        6 => THEME.teal,       // Dark Cyan -> Catppuccin teal
-        7 => THEME.subtext1,   // Light Gray -> Catppuccin subtext1
-        8 => THEME.surface2,   // Dark Gray -> Catppuccin surface2
-        9 => THEME.red,        // Bright Red -> Catppuccin red (same as dark red for consistency)
-        10 => THEME.green,     // Bright Green -> Catppuccin green
-        11 => THEME.yellow,    // Bright Yellow -> Catppuccin yellow
-        12 => THEME.blue,      // Bright Blue -> Catppuccin blue
-        13 => THEME.mauve,     // Bright Magenta -> Catppuccin mauve
-        14 => THEME.teal,      // Bright Cyan -> Catppuccin teal
-        15 => THEME.text,      // Bright White -> Catppuccin text
-
-        // 216 color cube (16-231)
-        16..=231 => {
-            let index = color_index - 16;
-            let r = (index / 36) * 51;
-            let g = ((index % 36) / 6) * 51;
-            let b = (index % 6) * 51;
-            egui::Color32::from_rgb(r, g, b)
+/// The theme, or a message describing the first invalid key or color
+pub fn parse_theme(id: &str, content: &str) -> Result<Theme, String> {
+    let file: ThemeFile = toml::from_str(content).map_err(|e| e.to_string())?;
+
+    let base = match file.inherits.as_deref() {
+        Some(parent) => Theme::builtins()
+            .into_iter()
+            .find(|t| t.matches(parent))
+            .ok_or_else(|| format!("unknown built-in theme in `inherits`: {:?}", parent))?,
+        None => Theme::frappe(),
+    };
+
+    let mut palette = base.palette;
+    let mut keys: Vec<&String> = file.ui.keys().collect();
+    keys.sort();
+    for key in keys {
+        let color = parse_hex_color(&file.ui[key]).map_err(|e| format!("ui.{}: {}", key, e))?;
+        let slot = palette
+            .role_mut(key)
+            .ok_or_else(|| format!("unknown ui color `{}`", key))?;
+        *slot = color;
+    }
+
+    let dark = file.dark.unwrap_or_else(|| luminance(palette.base) < 0.5);
+    let name = file.name.unwrap_or_else(|| id.to_string());
+    let mut theme = Theme::from_palette(id, &name, palette, dark);
+
+    if let Some(ansi) = file.ansi {
+        if ansi.len() != 16 {
+            return Err(format!("`ansi` must list exactly 16 colors, found {}", ansi.len()));
         }
+        for (i, hex) in ansi.iter().enumerate() {
+            theme.ansi[i] = parse_hex_color(hex).map_err(|e| format!("ansi[{}]: {}", i, e))?;
+        }
+    }
+
+    Ok(theme)
+}
+
+/// Loads every `*.toml` theme in `dir`, sorted by file name
+/// 
+/// Missing directories yield no themes. Invalid files are reported on stderr and skipped.
+pub fn load_user_themes(dir: &Path) -> Vec<Theme> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().map(|x| x == "toml").unwrap_or(false))
+        .collect();
+    paths.sort();
 
-        // Grayscale colors (232-255)
-        232..=255 => {
-            let gray = 8 + (color_index - 232) * 10;
-            egui::Color32::from_rgb(gray, gray, gray)
+    let mut themes = Vec::new();
+    for path in paths {
+        let id = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|c| parse_theme(&id, &c)) {
+            Ok(theme) => themes.push(theme),
+            Err(e) => eprintln!("[THEME] Skipping {:?}: {}", path, e),
         }
     }
+    themes
+}
+
+/// Parses `#rrggbb` or `#rrggbbaa` (leading `#` optional)
+pub fn parse_hex_color(value: &str) -> Result<egui::Color32, String> {
+    let hex = value.trim().trim_start_matches('#');
+    let byte = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|h| u8::from_str_radix(h, 16).ok())
+            .ok_or_else(|| format!("invalid color {:?}", value))
+    };
+    match hex.len() {
+        6 => Ok(egui::Color32::from_rgb(byte(0)?, byte(2)?, byte(4)?)),
+        8 => Ok(egui::Color32::from_rgba_unmultiplied(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+        _ => Err(format!("invalid color {:?} (expected #rrggbb)", value)),
+    }
+}
+
+/// Linear blend of two colors (`t` = 0 gives `a`, 1 gives `b`)
+fn mix(a: egui::Color32, b: egui::Color32, t: f32) -> egui::Color32 {
+    let lerp = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    egui::Color32::from_rgb(lerp(a.r(), b.r()), lerp(a.g(), b.g()), lerp(a.b(), b.b()))
+}
+
+/// Approximate relative luminance in 0..=1
+fn luminance(c: egui::Color32) -> f32 {
+    (0.2126 * c.r() as f32 + 0.7152 * c.g() as f32 + 0.0722 * c.b() as f32) / 255.0
 }
 
 #[cfg(test)]
@@ -222,7 +667,7 @@ mod tests {
 
     #[test]
     fn test_theme_colors_are_defined() {
-        let theme = CatppuccinTheme::FRAPPE;
+        let theme = Theme::frappe().palette;
         
         // Test that all colors are properly defined (not transparent/black)
         assert_ne!(theme.base, egui::Color32::TRANSPARENT);
@@ -235,36 +680,42 @@ mod tests {
     #[test]
     fn test_ansi_standard_colors() {
         // Test standard ANSI colors (0-15) map to Catppuccin colors
-        let theme = CatppuccinTheme::FRAPPE;
+        let theme = Theme::frappe();
+        let p = theme.palette;
         
-        assert_eq!(ansi_256_to_rgb(0), theme.surface1);  // Black
-        assert_eq!(ansi_256_to_rgb(1), theme.red);       // Red
-        assert_eq!(ansi_256_to_rgb(2), theme.green);     // Green
-        assert_eq!(ansi_256_to_rgb(3), theme.yellow);    // Yellow
-        assert_eq!(ansi_256_to_rgb(4), theme.blue);      // Blue
-        assert_eq!(ansi_256_to_rgb(5), theme.mauve);     // Magenta
-        assert_eq!(ansi_256_to_rgb(6), theme.teal);      // Cyan
-        assert_eq!(ansi_256_to_rgb(7), theme.subtext1);  // White
-        assert_eq!(ansi_256_to_rgb(15), theme.text);     // Bright White
+        assert_eq!(theme.indexed(0), p.surface1);  // Black
+        assert_eq!(theme.indexed(1), p.red);       // Red
+        assert_eq!(theme.indexed(2), p.green);     // Green
+        assert_eq!(theme.indexed(3), p.yellow);    // Yellow
+        assert_eq!(theme.indexed(4), p.blue);      // Blue
+        assert_eq!(theme.indexed(5), p.mauve);     // Magenta
+        assert_eq!(theme.indexed(6), p.teal);      // Cyan
+        assert_eq!(theme.indexed(7), p.subtext1);  // White
+        assert_eq!(theme.indexed(8), p.surface2);  // Bright Black
+        assert_eq!(theme.indexed(15), p.text);     // Bright White
     }
 
     #[test]
     fn test_ansi_256_color_cube() {
         // Test 216-color cube (16-231)
-        let color_16 = ansi_256_to_rgb(16);  // First color in cube
-        let color_231 = ansi_256_to_rgb(231); // Last color in cube
+        let theme = Theme::frappe();
+        let color_16 = theme.indexed(16);  // First color in cube
+        let color_231 = theme.indexed(231); // Last color in cube
         
         // These should not be transparent and should be different
         assert_ne!(color_16, egui::Color32::TRANSPARENT);
         assert_ne!(color_231, egui::Color32::TRANSPARENT);
         assert_ne!(color_16, color_231);
+        // The cube is theme-independent
+        assert_eq!(color_231, Theme::latte().indexed(231));
     }
 
     #[test]
     fn test_ansi_grayscale() {
         // Test grayscale colors (232-255)
-        let gray_start = ansi_256_to_rgb(232);
-        let gray_end = ansi_256_to_rgb(255);
+        let theme = Theme::frappe();
+        let gray_start = theme.indexed(232);
+        let gray_end = theme.indexed(255);
         
         // Should be different shades of gray
         assert_ne!(gray_start, gray_end);
@@ -273,25 +724,96 @@ mod tests {
     }
 
     #[test]
-    fn test_color_consistency() {
-        // Test that bright colors map to the same Catppuccin colors as dark colors
-        assert_eq!(ansi_256_to_rgb(1), ansi_256_to_rgb(9));   // Red
-        assert_eq!(ansi_256_to_rgb(2), ansi_256_to_rgb(10));  // Green
-        assert_eq!(ansi_256_to_rgb(3), ansi_256_to_rgb(11));  // Yellow
-        assert_eq!(ansi_256_to_rgb(4), ansi_256_to_rgb(12));  // Blue
-        assert_eq!(ansi_256_to_rgb(5), ansi_256_to_rgb(13));  // Magenta
-        assert_eq!(ansi_256_to_rgb(6), ansi_256_to_rgb(14));  // Cyan
+    fn test_bright_colors_are_distinct() {
+        // Bright colors must differ from their normal counterparts in every built-in
+        for theme in Theme::builtins() {
+            for i in 0..8 {
+                assert_ne!(theme.ansi[i], theme.ansi[i + 8], "{} color {}", theme.id, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_builtin_themes() {
+        let themes = Theme::builtins();
+        assert_eq!(themes.len(), 6);
+        assert!(themes.iter().any(|t| t.matches("Frappe")));
+        assert!(themes.iter().any(|t| t.matches("catppuccin mocha")));
+        assert!(!Theme::latte().dark);
+        assert!(!Theme::high_contrast_light().dark);
+        assert!(Theme::high_contrast_dark().dark);
+    }
+
+    #[test]
+    fn test_resolve_cell_colors() {
+        let theme = Theme::mocha();
+        assert_eq!(theme.resolve(CellColor::Default), theme.palette.text);
+        assert_eq!(theme.resolve(CellColor::Indexed(1)), theme.palette.red);
+        let rgb = egui::Color32::from_rgb(1, 2, 3);
+        assert_eq!(theme.resolve(CellColor::Rgb(rgb)), rgb);
+    }
+
+    #[test]
+    fn test_palette_role_lookup() {
+        let palette = Palette::MOCHA;
+        assert_eq!(palette.role("peach"), Some(palette.peach));
+        assert_eq!(palette.role("background"), Some(palette.base));
+        assert_eq!(palette.role("chartreuse"), None);
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff8000").unwrap(), egui::Color32::from_rgb(255, 128, 0));
+        assert_eq!(parse_hex_color("00ff00").unwrap(), egui::Color32::from_rgb(0, 255, 0));
+        assert!(parse_hex_color("#ff80").is_err());
+        assert!(parse_hex_color("#gg0000").is_err());
+    }
+
+    #[test]
+    fn test_parse_user_theme() {
+        let mut ansi: Vec<String> = (0..16).map(|i| format!("\"#0000{:02x}\"", i)).collect();
+        let content = format!(
+            "name = \"Studio\"\ninherits = \"mocha\"\nansi = [{}]\n[ui]\nbase = \"#ffffff\"\nforeground = \"#000000\"\n",
+            ansi.join(", ")
+        );
+        let theme = parse_theme("studio", &content).expect("theme parses");
+        assert_eq!(theme.id, "studio");
+        assert_eq!(theme.name, "Studio");
+        assert!(!theme.dark); // white background detected as light
+        assert_eq!(theme.palette.base, egui::Color32::WHITE);
+        assert_eq!(theme.palette.text, egui::Color32::BLACK);
+        assert_eq!(theme.palette.blue, Palette::MOCHA.blue); // inherited
+        assert_eq!(theme.ansi[15], egui::Color32::from_rgb(0, 0, 15));
+
+        // Wrong ANSI length, unknown role and unknown parent are rejected
+        ansi.pop();
+        let short = format!("ansi = [{}]", ansi.join(", "));
+        assert!(parse_theme("x", &short).is_err());
+        assert!(parse_theme("x", "[ui]\nnot_a_role = \"#000000\"").is_err());
+        assert!(parse_theme("x", "inherits = \"solarized\"").is_err());
+    }
+
+    #[test]
+    fn test_load_user_themes_skips_invalid() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        fs::write(dir.path().join("good.toml"), "[ui]\nbase = \"#000000\"").unwrap();
+        fs::write(dir.path().join("bad.toml"), "[ui]\nbase = \"nope\"").unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+        let themes = load_user_themes(dir.path());
+        assert_eq!(themes.len(), 1);
+        assert_eq!(themes[0].id, "good");
+        assert!(load_user_themes(&dir.path().join("missing")).is_empty());
     }
 
     #[test]
     fn test_theme_struct_properties() {
-        let theme = CatppuccinTheme::FRAPPE;
+        let theme = Theme::frappe();
         
-        // Test that the theme can be cloned and copied
-        let theme_copy = theme;
+        // Test that the palette can be copied and the theme cloned
+        let palette_copy = theme.palette;
         let theme_clone = theme.clone();
         
-        assert_eq!(theme.base, theme_copy.base);
-        assert_eq!(theme.text, theme_clone.text);
+        assert_eq!(theme.palette.base, palette_copy.base);
+        assert_eq!(theme.palette.text, theme_clone.palette.text);
     }
-}
\ No newline at end of file
+}